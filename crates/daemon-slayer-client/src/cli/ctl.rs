@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use daemon_slayer_core::cli::clap::{self, Args, FromArgMatches, Subcommand};
+use daemon_slayer_core::cli::{ActionType, CommandMatch, CommandOutput, CommandProvider, Printer};
+use daemon_slayer_core::control::{ControlClient, ControlRequest, ControlResponse};
+use daemon_slayer_core::{BoxedError, Label};
+use owo_colors::OwoColorize;
+
+#[derive(Subcommand, Clone, Debug)]
+enum CtlSubcommands {
+    /// Show the status of the running process
+    Status,
+    /// List the background services that are currently running
+    Services,
+    /// Reload the configuration of the running process
+    ReloadConfig,
+    /// Run a command registered by the service
+    Command {
+        name: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Args, Clone, Debug)]
+struct CtlArgs {
+    #[command(subcommand)]
+    commands: CtlSubcommands,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum LogLevelSubcommands {
+    /// Change the log level or filter directives of the running process, ex: `debug` or
+    /// `my_crate::db=trace,hyper=warn`
+    Set {
+        directive: String,
+        /// Revert to the previous level after the given duration, ex: `30s`, `10m`, or `1h`
+        #[arg(long = "for", value_parser = parse_duration)]
+        revert_after: Option<Duration>,
+    },
+}
+
+#[derive(Args, Clone, Debug)]
+struct LogLevelArgs {
+    #[command(subcommand)]
+    commands: LogLevelSubcommands,
+}
+
+#[derive(Subcommand)]
+enum CliCommands {
+    /// Administer the running service over its control socket
+    Ctl(CtlArgs),
+    /// Change the log level of the running service
+    LogLevel(LogLevelArgs),
+}
+
+#[derive(Clone, Debug)]
+enum MatchedArgs {
+    Ctl(CtlArgs),
+    LogLevel(LogLevelArgs),
+}
+
+#[derive(Clone, Debug)]
+pub struct CtlCliProvider {
+    label: Label,
+    matched_args: Option<MatchedArgs>,
+}
+
+impl CtlCliProvider {
+    pub fn new(label: Label) -> Self {
+        Self {
+            label,
+            matched_args: None,
+        }
+    }
+}
+
+#[async_trait]
+impl CommandProvider for CtlCliProvider {
+    fn get_commands(&self, command: clap::Command) -> clap::Command {
+        CliCommands::augment_subcommands(command)
+    }
+
+    fn matches(&mut self, matches: &clap::ArgMatches) -> Option<CommandMatch> {
+        self.matched_args = Some(match CliCommands::from_arg_matches(matches).ok()? {
+            CliCommands::Ctl(args) => MatchedArgs::Ctl(args),
+            CliCommands::LogLevel(args) => MatchedArgs::LogLevel(args),
+        });
+        Some(CommandMatch {
+            action_type: ActionType::Client,
+            action: None,
+        })
+    }
+
+    async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
+        let (request, success_message) = match self.matched_args {
+            None => return Ok(CommandOutput::unhandled()),
+            Some(MatchedArgs::Ctl(args)) => match args.commands {
+                CtlSubcommands::Status => (ControlRequest::Status, ""),
+                CtlSubcommands::Services => (ControlRequest::ListServices, ""),
+                CtlSubcommands::ReloadConfig => (ControlRequest::Reload, "Config reloaded"),
+                CtlSubcommands::Command { name, args } => {
+                    (ControlRequest::Command { name, args }, "Command sent")
+                }
+            },
+            Some(MatchedArgs::LogLevel(LogLevelArgs {
+                commands:
+                    LogLevelSubcommands::Set {
+                        directive,
+                        revert_after,
+                    },
+            })) => (
+                ControlRequest::SetLogLevel {
+                    level: directive,
+                    revert_after_secs: revert_after.map(|duration| duration.as_secs()),
+                },
+                "Log level updated",
+            ),
+        };
+        let client = ControlClient::new(&self.label)?;
+        let response = client.send(&request).await;
+        Ok(CommandOutput::handled(format_response(
+            response,
+            success_message,
+        )))
+    }
+}
+
+pub(crate) fn format_response(
+    response: std::io::Result<ControlResponse>,
+    success_message: &str,
+) -> String {
+    match response {
+        Ok(ControlResponse::Ok { output }) => output.unwrap_or_else(|| success_message.to_owned()),
+        Ok(ControlResponse::Error { message }) => message.red().to_string(),
+        Ok(ControlResponse::ServerStatus(status)) => Printer::default()
+            .with_line("PID", status.pid.to_string())
+            .with_line(
+                "Uptime",
+                format_uptime(Duration::from_secs(status.uptime_secs)),
+            )
+            .with_line("Paused", status.paused.to_string())
+            .with_line(
+                "Background Services",
+                status.background_services.to_string(),
+            )
+            .print(),
+        Ok(ControlResponse::Services { names }) if names.is_empty() => {
+            "No background services are running".to_owned()
+        }
+        Ok(ControlResponse::Services { names }) => names.join("\n"),
+        Err(e) => format!("Unable to reach the service: {e}")
+            .red()
+            .to_string(),
+    }
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration '{value}'"))?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => {
+            return Err(format!(
+                "unknown duration unit '{unit}', expected s, m, h, or d"
+            ));
+        }
+    };
+    amount
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration '{value}' is too large"))
+}
+
+fn format_uptime(uptime: Duration) -> String {
+    let seconds = uptime.as_secs() % 60;
+    let minutes = (uptime.as_secs() / 60) % 60;
+    let hours = (uptime.as_secs() / 60) / 60;
+    format!("{hours:0>2}:{minutes:0>2}:{seconds:0>2}")
+}
+
+#[cfg(test)]
+#[path = "./ctl_test.rs"]
+mod ctl_test;
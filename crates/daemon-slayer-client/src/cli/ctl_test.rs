@@ -0,0 +1,153 @@
+use std::io;
+use std::time::Duration;
+
+use daemon_slayer_core::cli::CommandProvider;
+use daemon_slayer_core::cli::clap;
+use daemon_slayer_core::control::ControlResponse;
+
+use super::{
+    CtlArgs, CtlCliProvider, CtlSubcommands, LogLevelArgs, LogLevelSubcommands, MatchedArgs,
+    format_response, format_uptime, parse_duration,
+};
+
+fn provider_matches(args: &[&str]) -> Option<MatchedArgs> {
+    let mut provider = CtlCliProvider::new("com.test.app".parse().unwrap());
+    let command = provider.get_commands(clap::Command::new("app"));
+    let matches = command.try_get_matches_from(args).ok()?;
+    provider.matches(&matches)?;
+    provider.matched_args
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(Ok(Duration::from_secs(30)), parse_duration("30"));
+    assert_eq!(Ok(Duration::from_secs(30)), parse_duration("30s"));
+    assert_eq!(Ok(Duration::from_secs(600)), parse_duration(" 10m "));
+    assert_eq!(Ok(Duration::from_secs(7200)), parse_duration("2h"));
+    assert_eq!(Ok(Duration::from_secs(86400)), parse_duration("1d"));
+}
+
+#[test]
+fn test_parse_duration_invalid() {
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("m").is_err());
+    assert!(parse_duration("-5m").is_err());
+    assert!(parse_duration("10w").is_err());
+    assert!(parse_duration("1.5h").is_err());
+}
+
+#[test]
+fn test_parse_duration_overflow() {
+    assert!(parse_duration(&format!("{}s", u64::MAX)).is_ok());
+    assert!(parse_duration(&format!("{}d", u64::MAX / 2)).is_err());
+    assert!(parse_duration(&format!("{}0s", u64::MAX)).is_err());
+}
+
+#[test]
+fn test_format_uptime() {
+    assert_eq!("00:00:00", format_uptime(Duration::ZERO));
+    assert_eq!("01:02:03", format_uptime(Duration::from_secs(3723)));
+    assert_eq!("100:00:00", format_uptime(Duration::from_secs(360000)));
+}
+
+#[test]
+fn test_format_response() {
+    assert_eq!(
+        "Log level updated",
+        format_response(
+            Ok(ControlResponse::Ok { output: None }),
+            "Log level updated"
+        )
+    );
+    assert_eq!(
+        "output",
+        format_response(
+            Ok(ControlResponse::Ok {
+                output: Some("output".to_owned())
+            }),
+            "Command sent"
+        )
+    );
+    assert_eq!(
+        "No background services are running",
+        format_response(Ok(ControlResponse::Services { names: vec![] }), "")
+    );
+    assert_eq!(
+        "a\nb",
+        format_response(
+            Ok(ControlResponse::Services {
+                names: vec!["a".to_owned(), "b".to_owned()]
+            }),
+            ""
+        )
+    );
+    assert!(
+        format_response(
+            Ok(ControlResponse::Error {
+                message: "invalid filter directive".to_owned()
+            }),
+            "Log level updated"
+        )
+        .contains("invalid filter directive")
+    );
+    assert!(
+        format_response(Err(io::Error::from(io::ErrorKind::ConnectionRefused)), "")
+            .contains("Unable to reach the service")
+    );
+}
+
+#[test]
+fn test_log_level_set() {
+    let matched = provider_matches(&["app", "log-level", "set", "app::db=trace"]);
+    assert!(matches!(
+        matched,
+        Some(MatchedArgs::LogLevel(LogLevelArgs {
+            commands: LogLevelSubcommands::Set {
+                directive,
+                revert_after: None,
+            },
+        })) if directive == "app::db=trace"
+    ));
+}
+
+#[test]
+fn test_log_level_set_for() {
+    let matched = provider_matches(&["app", "log-level", "set", "debug", "--for", "10m"]);
+    assert!(matches!(
+        matched,
+        Some(MatchedArgs::LogLevel(LogLevelArgs {
+            commands: LogLevelSubcommands::Set {
+                revert_after: Some(revert_after),
+                ..
+            },
+        })) if revert_after == Duration::from_secs(600)
+    ));
+
+    assert!(provider_matches(&["app", "log-level", "set", "debug", "--for", "soon"]).is_none());
+}
+
+#[test]
+fn test_ctl() {
+    let matched = provider_matches(&["app", "ctl", "command", "greet", "--loud", "world"]);
+    let Some(MatchedArgs::Ctl(CtlArgs {
+        commands: CtlSubcommands::Command { name, args },
+    })) = matched
+    else {
+        panic!("expected a ctl command");
+    };
+    assert_eq!("greet", name);
+    assert_eq!(vec!["--loud", "world"], args);
+
+    // Log level changes only go through the log-level command
+    assert!(provider_matches(&["app", "ctl", "log-level", "debug"]).is_none());
+}
+
+#[test]
+fn test_unrelated_command() {
+    let mut provider = CtlCliProvider::new("com.test.app".parse().unwrap());
+    let command = provider
+        .get_commands(clap::Command::new("app"))
+        .subcommand(clap::Command::new("install"));
+    let matches = command.try_get_matches_from(["app", "install"]).unwrap();
+    assert!(provider.matches(&matches).is_none());
+}
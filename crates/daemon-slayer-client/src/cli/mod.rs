@@ -1,14 +1,21 @@
+#[cfg(feature = "control")]
+mod ctl;
+
 use std::future::Future;
 use std::io;
 use std::process::Stdio;
 use std::time::Duration;
 
 use async_trait::async_trait;
+#[cfg(feature = "control")]
+pub use ctl::*;
 use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::cli::clap::{self, FromArgMatches, Subcommand};
 use daemon_slayer_core::cli::{
     Action, ActionType, ClientAction, CommandMatch, CommandOutput, CommandProvider,
 };
+#[cfg(feature = "control")]
+use daemon_slayer_core::control::{ControlClient, ControlRequest};
 use owo_colors::OwoColorize;
 use spinoff::Spinner;
 pub use spinoff::{Color, spinners};
@@ -59,6 +66,34 @@ enum CliCommands {
     Enable,
     /// Disable autostart
     Disable,
+    /// Pause the running service
+    #[cfg(feature = "control")]
+    Pause,
+    /// Resume the running service
+    #[cfg(feature = "control")]
+    Resume,
+    /// Send a custom command to the running service
+    #[cfg(feature = "control")]
+    Send {
+        command: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+impl CliCommands {
+    // Control commands talk to the running process directly, so they also work when the service
+    // was started without being installed
+    fn is_control(&self) -> bool {
+        #[cfg(feature = "control")]
+        {
+            matches!(self, Self::Pause | Self::Resume | Self::Send { .. })
+        }
+        #[cfg(not(feature = "control"))]
+        {
+            false
+        }
+    }
 }
 
 impl ClientCliProvider {
@@ -127,6 +162,21 @@ impl ClientCliProvider {
         }
         Ok(CommandOutput::handled(failure_message.red().to_string()))
     }
+
+    #[cfg(feature = "control")]
+    async fn send_control_request(
+        &self,
+        request: ControlRequest,
+        wait_message: &str,
+        success_message: &str,
+    ) -> io::Result<CommandOutput> {
+        let _sp = self.get_spinner(wait_message);
+        let client = ControlClient::new(self.manager.label())?;
+        Ok(CommandOutput::handled(format_response(
+            client.send(&request).await,
+            success_message,
+        )))
+    }
 }
 
 #[async_trait]
@@ -152,6 +202,12 @@ impl CommandProvider for ClientCliProvider {
                 CliCommands::Reload => ClientAction::Reload,
                 CliCommands::Enable => ClientAction::Enable,
                 CliCommands::Disable => ClientAction::Disable,
+                #[cfg(feature = "control")]
+                CliCommands::Pause => ClientAction::Pause,
+                #[cfg(feature = "control")]
+                CliCommands::Resume => ClientAction::Resume,
+                #[cfg(feature = "control")]
+                CliCommands::Send { .. } => ClientAction::Send,
             })),
         })
     }
@@ -160,6 +216,7 @@ impl CommandProvider for ClientCliProvider {
         if let Some(matched_command) = &self.matched_command {
             let state = self.manager.status().await?.state;
             if state == State::NotInstalled
+                && !matched_command.is_control()
                 && !matches!(
                     matched_command,
                     CliCommands::Install | CliCommands::Status { .. }
@@ -302,6 +359,31 @@ impl CommandProvider for ClientCliProvider {
                             .unwrap_or_else(|| "Not running".to_owned()),
                     ));
                 }
+                #[cfg(feature = "control")]
+                CliCommands::Pause => {
+                    return Ok(self
+                        .send_control_request(ControlRequest::Pause, "Pausing...", "Paused")
+                        .await?);
+                }
+                #[cfg(feature = "control")]
+                CliCommands::Resume => {
+                    return Ok(self
+                        .send_control_request(ControlRequest::Resume, "Resuming...", "Resumed")
+                        .await?);
+                }
+                #[cfg(feature = "control")]
+                CliCommands::Send { command, args } => {
+                    return Ok(self
+                        .send_control_request(
+                            ControlRequest::Command {
+                                name: command.clone(),
+                                args: args.clone(),
+                            },
+                            "Sending...",
+                            "Sent",
+                        )
+                        .await?);
+                }
             }
 
             return Ok(CommandOutput::handled(None));
@@ -1,5 +1,8 @@
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
-#[cfg_attr(feature = "config", derive(confique::Config, serde::Deserialize))]
+#[cfg_attr(
+    feature = "config",
+    derive(confique::Config, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct EnvironmentVariable {
     pub name: String,
     pub value: String,
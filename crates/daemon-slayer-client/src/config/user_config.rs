@@ -3,7 +3,7 @@ use daemon_slayer_core::config::Mergeable;
 use super::EnvironmentVariable;
 
 #[derive(Debug, Clone, Default)]
-#[cfg_attr(feature = "config", derive(confique::Config))]
+#[cfg_attr(feature = "config", derive(confique::Config, schemars::JsonSchema))]
 pub struct UserConfig {
     #[cfg_attr(feature="config",config(default=[]))]
     pub environment_variables: Vec<EnvironmentVariable>,
@@ -5,8 +5,12 @@ mod info;
 mod manager;
 mod platform;
 mod state;
+#[cfg(feature = "health-supervisor")]
+mod supervisor;
 
 pub use info::*;
 pub use manager::*;
 pub use platform::*;
 pub use state::*;
+#[cfg(feature = "health-supervisor")]
+pub use supervisor::*;
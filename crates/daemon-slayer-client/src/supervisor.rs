@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+#[cfg(feature = "notify")]
+use daemon_slayer_core::notify::AsyncNotification;
+use daemon_slayer_core::server::BroadcastEventStore;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+use tokio_util::future::FutureExt;
+
+use crate::{ServiceManager, State};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HealthSupervisorEvent {
+    Unhealthy {
+        reason: String,
+        consecutive_failures: u32,
+    },
+    Recovered,
+    Restarting {
+        attempt: u32,
+    },
+    RestartFailed {
+        error: String,
+    },
+    /// The service failed again after `restarts` restarts within the restart window. No more
+    /// restarts will be attempted until the window moves past the earlier restarts.
+    RestartLimitReached {
+        restarts: u32,
+    },
+}
+
+#[cfg(feature = "notify")]
+type CreateNotification = Box<
+    dyn FnMut(
+            &HealthSupervisorEvent,
+        ) -> Option<Box<dyn AsyncNotification<Output = ()> + Send + Sync>>
+        + Send,
+>;
+
+/// Restarts the service after its health check fails several times in a row. This covers cases
+/// where the process is still running but no longer responding, which the service manager won't
+/// detect by itself.
+pub struct HealthSupervisor {
+    manager: ServiceManager,
+    health_check: Box<dyn HealthCheck + Send>,
+    check_interval: Duration,
+    failure_threshold: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: u32,
+    restart_window: Duration,
+    event_tx: broadcast::Sender<HealthSupervisorEvent>,
+    #[cfg(feature = "notify")]
+    create_notification: Option<CreateNotification>,
+}
+
+impl HealthSupervisor {
+    pub fn new(manager: ServiceManager, health_check: impl HealthCheck + Send + 'static) -> Self {
+        let (event_tx, _) = broadcast::channel(32);
+        Self {
+            manager,
+            health_check: Box::new(health_check),
+            check_interval: Duration::from_secs(10),
+            failure_threshold: 3,
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(300),
+            max_restarts: 5,
+            restart_window: Duration::from_secs(600),
+            event_tx,
+            #[cfg(feature = "notify")]
+            create_notification: None,
+        }
+    }
+
+    pub fn with_check_interval(mut self, check_interval: Duration) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// Number of consecutive failed checks before the service is restarted.
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Time to wait after a restart before checking again. This doubles after each restart within
+    /// the restart window, up to `max_backoff`.
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Allows at most `max_restarts` restarts within any `restart_window`.
+    pub fn with_max_restarts(mut self, max_restarts: u32, restart_window: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.restart_window = restart_window;
+        self
+    }
+
+    #[cfg(feature = "notify")]
+    pub fn with_notification<F, N>(mut self, mut create_notification: F) -> Self
+    where
+        F: FnMut(&HealthSupervisorEvent) -> Option<N> + Send + 'static,
+        N: AsyncNotification<Output = ()> + Send + Sync + 'static,
+    {
+        self.create_notification = Some(Box::new(move |event| {
+            create_notification(event).map(|n| Box::new(n) as Box<_>)
+        }));
+        self
+    }
+
+    pub fn get_event_store(&self) -> BroadcastEventStore<HealthSupervisorEvent> {
+        BroadcastEventStore::new(self.event_tx.clone())
+    }
+
+    async fn emit(&mut self, event: HealthSupervisorEvent) {
+        #[cfg(feature = "notify")]
+        if let Some(notification) = self
+            .create_notification
+            .as_mut()
+            .and_then(|create_notification| create_notification(&event))
+        {
+            notification.show().await.ok();
+        }
+        // No subscribers isn't an error
+        self.event_tx.send(event).ok();
+    }
+
+    fn backoff(&self, restarts: usize) -> Duration {
+        let exponent = restarts.saturating_sub(1).min(31) as u32;
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_backoff)
+    }
+}
+
+impl BackgroundService for HealthSupervisor {
+    fn name(&self) -> &str {
+        "health_supervisor"
+    }
+
+    async fn run(mut self, context: ServiceContext) -> Result<(), BoxedError> {
+        let cancellation_token = context.cancellation_token().clone();
+        let mut consecutive_failures = 0;
+        let mut restarts: VecDeque<Instant> = VecDeque::new();
+        let mut limit_reached = false;
+
+        loop {
+            if tokio::time::sleep(self.check_interval)
+                .with_cancellation_token(&cancellation_token)
+                .await
+                .is_none()
+            {
+                return Ok(());
+            }
+
+            // Stopped services are expected to fail their checks
+            if !matches!(self.manager.status().await, Ok(status) if status.state == State::Started)
+            {
+                consecutive_failures = 0;
+                continue;
+            }
+
+            let Some(status) = self
+                .health_check
+                .status()
+                .with_cancellation_token(&cancellation_token)
+                .await
+            else {
+                return Ok(());
+            };
+
+            let HealthStatus::Unhealthy(reason) = status else {
+                if consecutive_failures > 0 {
+                    consecutive_failures = 0;
+                    self.emit(HealthSupervisorEvent::Recovered).await;
+                }
+                continue;
+            };
+
+            consecutive_failures += 1;
+            self.emit(HealthSupervisorEvent::Unhealthy {
+                reason,
+                consecutive_failures,
+            })
+            .await;
+            if consecutive_failures < self.failure_threshold {
+                continue;
+            }
+
+            while restarts
+                .front()
+                .is_some_and(|restart| restart.elapsed() > self.restart_window)
+            {
+                restarts.pop_front();
+            }
+            if restarts.len() >= self.max_restarts as usize {
+                if !limit_reached {
+                    limit_reached = true;
+                    self.emit(HealthSupervisorEvent::RestartLimitReached {
+                        restarts: restarts.len() as u32,
+                    })
+                    .await;
+                }
+                continue;
+            }
+
+            limit_reached = false;
+            consecutive_failures = 0;
+            restarts.push_back(Instant::now());
+            self.emit(HealthSupervisorEvent::Restarting {
+                attempt: restarts.len() as u32,
+            })
+            .await;
+            if let Err(e) = self.manager.restart().await {
+                self.emit(HealthSupervisorEvent::RestartFailed {
+                    error: e.to_string(),
+                })
+                .await;
+            }
+
+            // Give the service time to start up before checking again
+            if tokio::time::sleep(self.backoff(restarts.len()))
+                .with_cancellation_token(&cancellation_token)
+                .await
+                .is_none()
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./supervisor_test.rs"]
+mod supervisor_test;
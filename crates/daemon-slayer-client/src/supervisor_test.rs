@@ -0,0 +1,308 @@
+use std::io;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+use daemon_slayer_core::server::tracked::{Manager as BackgroundManager, Settings};
+use daemon_slayer_core::{BoxedError, CancellationToken, Label};
+use tokio::sync::broadcast;
+
+use super::{HealthSupervisor, HealthSupervisorEvent};
+use crate::config::{Builder, Config, Program};
+use crate::manager::{Command, Manager};
+use crate::{ServiceManager, State, Status};
+
+#[derive(Clone, Debug)]
+struct TestManager {
+    label: Label,
+    config: Config,
+    state: Arc<Mutex<State>>,
+    restarts: Arc<AtomicU32>,
+    fail_restart: bool,
+}
+
+impl TestManager {
+    fn new(state: State) -> Self {
+        let label: Label = "com.test.daemon_slayer_supervisor_test".parse().unwrap();
+        Self {
+            config: Builder::new(label.clone(), Program::new("test").unwrap()).into(),
+            label,
+            state: Arc::new(Mutex::new(state)),
+            restarts: Default::default(),
+            fail_restart: false,
+        }
+    }
+}
+
+#[async_trait]
+impl Manager for TestManager {
+    fn name(&self) -> String {
+        self.label.application.clone()
+    }
+
+    fn display_name(&self) -> &str {
+        &self.label.application
+    }
+
+    fn label(&self) -> &Label {
+        &self.label
+    }
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    fn arguments(&self) -> &Vec<String> {
+        &self.config.arguments
+    }
+
+    fn config(&self) -> Config {
+        self.config.clone()
+    }
+
+    async fn status_command(&self) -> io::Result<Command> {
+        Ok(Command {
+            program: self.name(),
+            args: vec![],
+        })
+    }
+
+    async fn reload_config(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn on_config_changed(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn install(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn uninstall(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn start(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn stop(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn restart(&self) -> io::Result<()> {
+        self.restarts.fetch_add(1, Ordering::SeqCst);
+        if self.fail_restart {
+            Err(io::Error::other("restart failed"))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn enable_autostart(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn disable_autostart(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn status(&self) -> io::Result<Status> {
+        Ok(Status {
+            state: *self.state.lock().unwrap(),
+            autostart: None,
+            pid: None,
+            last_exit_code: None,
+            id: None,
+        })
+    }
+
+    async fn pid(&self) -> io::Result<Option<u32>> {
+        Ok(None)
+    }
+}
+
+#[derive(Clone)]
+struct TestCheck(Arc<Mutex<HealthStatus>>);
+
+impl TestCheck {
+    fn new(status: HealthStatus) -> Self {
+        Self(Arc::new(Mutex::new(status)))
+    }
+
+    fn set(&self, status: HealthStatus) {
+        *self.0.lock().unwrap() = status;
+    }
+}
+
+#[async_trait]
+impl HealthCheck for TestCheck {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        unreachable!()
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+fn unhealthy() -> HealthStatus {
+    HealthStatus::Unhealthy("down".to_owned())
+}
+
+fn unhealthy_event(consecutive_failures: u32) -> HealthSupervisorEvent {
+    HealthSupervisorEvent::Unhealthy {
+        reason: "down".to_owned(),
+        consecutive_failures,
+    }
+}
+
+fn supervisor(manager: &TestManager, check: &TestCheck) -> HealthSupervisor {
+    HealthSupervisor::new(ServiceManager::new(manager.clone()), check.clone())
+        .with_check_interval(Duration::from_secs(1))
+        .with_failure_threshold(2)
+        .with_backoff(Duration::from_secs(5), Duration::from_secs(60))
+}
+
+fn spawn(
+    supervisor: HealthSupervisor,
+) -> (
+    BackgroundManager,
+    broadcast::Receiver<HealthSupervisorEvent>,
+) {
+    let events = supervisor.event_tx.subscribe();
+    let manager = BackgroundManager::new(CancellationToken::new(), Settings::default());
+    manager.get_context().spawn(supervisor);
+    (manager, events)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart() {
+    let manager = TestManager::new(State::Started);
+    let check = TestCheck::new(unhealthy());
+    let (background_manager, mut events) =
+        spawn(supervisor(&manager, &check).with_max_restarts(1, Duration::from_secs(600)));
+
+    assert_eq!(unhealthy_event(1), events.recv().await.unwrap());
+    assert_eq!(unhealthy_event(2), events.recv().await.unwrap());
+    assert_eq!(
+        HealthSupervisorEvent::Restarting { attempt: 1 },
+        events.recv().await.unwrap()
+    );
+    assert_eq!(1, manager.restarts.load(Ordering::SeqCst));
+
+    // Only one restart is allowed within the window
+    assert_eq!(unhealthy_event(1), events.recv().await.unwrap());
+    assert_eq!(unhealthy_event(2), events.recv().await.unwrap());
+    assert_eq!(
+        HealthSupervisorEvent::RestartLimitReached { restarts: 1 },
+        events.recv().await.unwrap()
+    );
+    assert_eq!(unhealthy_event(3), events.recv().await.unwrap());
+    assert_eq!(1, manager.restarts.load(Ordering::SeqCst));
+
+    check.set(HealthStatus::Healthy);
+    assert_eq!(
+        HealthSupervisorEvent::Recovered,
+        events.recv().await.unwrap()
+    );
+
+    background_manager.cancel().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_window() {
+    let manager = TestManager::new(State::Started);
+    let check = TestCheck::new(unhealthy());
+    let restart_window = Duration::from_secs(30);
+    let (background_manager, mut events) =
+        spawn(supervisor(&manager, &check).with_max_restarts(1, restart_window));
+
+    let mut restarted_at = Vec::new();
+    while restarted_at.len() < 2 {
+        if let HealthSupervisorEvent::Restarting { attempt } = events.recv().await.unwrap() {
+            // The earlier restart left the window, so it no longer counts towards the limit
+            assert_eq!(1, attempt);
+            restarted_at.push(tokio::time::Instant::now());
+        }
+    }
+    assert!(restarted_at[1] - restarted_at[0] > restart_window);
+    assert_eq!(2, manager.restarts.load(Ordering::SeqCst));
+
+    background_manager.cancel().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_degraded() {
+    let manager = TestManager::new(State::Started);
+    let check = TestCheck::new(unhealthy());
+    let (background_manager, mut events) = spawn(supervisor(&manager, &check));
+
+    assert_eq!(unhealthy_event(1), events.recv().await.unwrap());
+    // Degraded services are still functioning
+    check.set(HealthStatus::Degraded("slow".to_owned()));
+    assert_eq!(
+        HealthSupervisorEvent::Recovered,
+        events.recv().await.unwrap()
+    );
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(events.try_recv().is_err());
+    assert_eq!(0, manager.restarts.load(Ordering::SeqCst));
+
+    background_manager.cancel().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stopped() {
+    let manager = TestManager::new(State::Stopped);
+    let check = TestCheck::new(unhealthy());
+    let (background_manager, mut events) = spawn(supervisor(&manager, &check));
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(events.try_recv().is_err());
+
+    *manager.state.lock().unwrap() = State::Started;
+    assert_eq!(unhealthy_event(1), events.recv().await.unwrap());
+
+    background_manager.cancel().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_failed() {
+    let mut manager = TestManager::new(State::Started);
+    manager.fail_restart = true;
+    let check = TestCheck::new(unhealthy());
+    let (background_manager, mut events) =
+        spawn(supervisor(&manager, &check).with_failure_threshold(1));
+
+    assert_eq!(unhealthy_event(1), events.recv().await.unwrap());
+    assert_eq!(
+        HealthSupervisorEvent::Restarting { attempt: 1 },
+        events.recv().await.unwrap()
+    );
+    assert_eq!(
+        HealthSupervisorEvent::RestartFailed {
+            error: "restart failed".to_owned()
+        },
+        events.recv().await.unwrap()
+    );
+
+    background_manager.cancel().await.unwrap();
+}
+
+#[test]
+fn test_backoff() {
+    let supervisor = supervisor(
+        &TestManager::new(State::Started),
+        &TestCheck::new(HealthStatus::Healthy),
+    )
+    .with_backoff(Duration::from_secs(5), Duration::from_secs(30));
+    assert_eq!(Duration::from_secs(5), supervisor.backoff(1));
+    assert_eq!(Duration::from_secs(10), supervisor.backoff(2));
+    assert_eq!(Duration::from_secs(20), supervisor.backoff(3));
+    assert_eq!(Duration::from_secs(30), supervisor.backoff(4));
+    assert_eq!(Duration::from_secs(30), supervisor.backoff(100));
+}
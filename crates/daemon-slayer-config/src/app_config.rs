@@ -4,19 +4,21 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
+use confique::meta::FieldKind;
 use confique::{Config, json5, toml, yaml};
 use daemon_slayer_core::config::{Accessor, CachedConfig, Mergeable};
 use directories::ProjectDirs;
+use schemars::JsonSchema;
 use tracing::{debug, error};
 
 use crate::{
     AppConfigBuilder, ConfigDir, ConfigEditError, ConfigFileType, ConfigInitializationError,
-    ConfigLoadError, io_error,
+    ConfigLoadError, document, io_error,
 };
 
-pub trait Configurable: Config + Default + Send + Sync + Clone + 'static {}
+pub trait Configurable: Config + JsonSchema + Default + Send + Sync + Clone + 'static {}
 
-impl<T> Configurable for T where T: Config + Default + Send + Sync + Clone + 'static {}
+impl<T> Configurable for T where T: Config + JsonSchema + Default + Send + Sync + Clone + 'static {}
 
 #[derive(Clone, Debug)]
 pub struct AppConfig<T: Configurable> {
@@ -83,6 +85,12 @@ impl<T: Configurable> AppConfig<T> {
         }
     }
 
+    /// JSON Schema describing the config, which can be used by editors and other tools to validate
+    /// config files.
+    pub fn json_schema() -> serde_json::Value {
+        crate::json_schema::generate::<T>()
+    }
+
     pub fn file_type(&self) -> &ConfigFileType {
         &self.config_file_type
     }
@@ -98,6 +106,73 @@ impl<T: Configurable> AppConfig<T> {
         Ok(())
     }
 
+    /// Returns the value for a dotted path, ex: `server.port`, as it's written in the config file.
+    /// The default value is returned if it isn't set in the file.
+    pub fn get_value(&self, key: &str) -> Result<Option<String>, ConfigEditError> {
+        let (path, field) = document::find_field(&T::META, key)
+            .map_err(|e| ConfigEditError::InvalidKey(key.to_owned(), e))?;
+        let full_path = self.full_path();
+        let contents = self
+            .contents()
+            .map_err(|e| ConfigEditError::IOFailure(full_path.clone(), e))?;
+        let value = document::get(&self.config_file_type, &contents, &path)
+            .map_err(|e| ConfigEditError::ParseFailure(full_path, e))?;
+        Ok(value.or_else(|| document::default_value(field)))
+    }
+
+    /// Sets the value for a dotted path without changing the formatting or comments in the rest
+    /// of the file. Values are parsed as JSON if possible, otherwise they're treated as strings.
+    /// Fields with a string default are always set to a string. The file is only updated if the
+    /// new config is valid.
+    pub fn set_value(&self, key: &str, value: &str) -> Result<(), ConfigEditError> {
+        let (path, field) = document::find_field(&T::META, key)
+            .map_err(|e| ConfigEditError::InvalidKey(key.to_owned(), e))?;
+        if let FieldKind::Nested { .. } = field.kind {
+            return Err(ConfigEditError::InvalidKey(
+                key.to_owned(),
+                "sections can't be set directly".to_owned(),
+            ));
+        }
+        let full_path = self.full_path();
+        let contents = self
+            .contents()
+            .map_err(|e| ConfigEditError::IOFailure(full_path.clone(), e))?;
+        let new_contents = document::set(
+            &self.config_file_type,
+            &contents,
+            &path,
+            &document::parse_value(value, field),
+        )
+        .map_err(|e| ConfigEditError::ParseFailure(full_path.clone(), e))?;
+
+        // The file type is determined by the extension so the temporary file needs to keep it
+        let temp_path = self.config_dir.join(format!(
+            ".{}.tmp{}",
+            self.filename,
+            self.config_file_type.to_extension()
+        ));
+        std::fs::write(&temp_path, new_contents)
+            .map_err(|e| ConfigEditError::IOFailure(temp_path.clone(), e))?;
+        // The temporary file replaces the original so it needs to keep the same permissions
+        if let Err(e) = std::fs::metadata(&full_path)
+            .and_then(|metadata| std::fs::set_permissions(&temp_path, metadata.permissions()))
+        {
+            std::fs::remove_file(&temp_path).ok();
+            return Err(ConfigEditError::IOFailure(full_path, e));
+        }
+        if let Err(e) = T::builder().env().file(&temp_path).load() {
+            std::fs::remove_file(&temp_path).ok();
+            return Err(ConfigEditError::LoadFailure(ConfigLoadError(
+                full_path,
+                e.to_string(),
+            )));
+        }
+        std::fs::rename(&temp_path, &full_path)
+            .map_err(|e| ConfigEditError::IOFailure(full_path, e))?;
+        self.read_config().map_err(ConfigEditError::LoadFailure)?;
+        Ok(())
+    }
+
     pub fn contents(&self) -> io::Result<String> {
         let full_path = self.full_path();
         std::fs::read_to_string(&full_path)
@@ -102,13 +102,107 @@ fn test_access() {
     assert!(nested.snapshot().test);
 }
 
-#[derive(Default, Clone, Config, Debug)]
+#[test]
+fn test_get_value() {
+    let config_dir = tempdir().unwrap().keep();
+    let test_config = AppConfig::<TestConfig2>::builder(ConfigDir::Custom(config_dir.clone()))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        Some("true".to_owned()),
+        test_config.get_value("nested.test").unwrap()
+    );
+    std::fs::write(config_dir.join("config.toml"), "[nested]\ntest = false").unwrap();
+    assert_eq!(
+        Some("false".to_owned()),
+        test_config.get_value("nested.test").unwrap()
+    );
+    assert!(test_config.get_value("nested.missing").is_err());
+}
+
+#[test]
+fn test_set_value() {
+    for file_type in [
+        ConfigFileType::Toml,
+        ConfigFileType::Yaml,
+        ConfigFileType::Json5,
+    ] {
+        let config_dir = tempdir().unwrap().keep();
+        let test_config = AppConfig::<TestConfig2>::builder(ConfigDir::Custom(config_dir))
+            .with_config_file_type(file_type)
+            .build()
+            .unwrap();
+
+        test_config.set_value("nested.test", "false").unwrap();
+        assert!(!test_config.snapshot().nested.test);
+        assert!(
+            test_config
+                .contents()
+                .unwrap()
+                .contains("Default value: true")
+        );
+
+        assert!(test_config.set_value("nested.test", "invalid").is_err());
+        assert!(test_config.set_value("nested", "true").is_err());
+        assert_eq!(
+            Some("false".to_owned()),
+            test_config.get_value("nested.test").unwrap()
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_set_value_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let config_dir = tempdir().unwrap().keep();
+    let test_config = AppConfig::<TestConfig2>::builder(ConfigDir::Custom(config_dir))
+        .build()
+        .unwrap();
+    let full_path = test_config.full_path();
+    std::fs::set_permissions(&full_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    test_config.set_value("nested.test", "false").unwrap();
+    assert_eq!(
+        0o600,
+        std::fs::metadata(&full_path).unwrap().permissions().mode() & 0o777
+    );
+}
+
+#[test]
+fn test_json_schema() {
+    assert_eq!(
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "TestConfig2",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "nested": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "properties": {
+                        "test": {
+                            "type": "boolean",
+                            "default": true
+                        }
+                    }
+                }
+            }
+        }),
+        AppConfig::<TestConfig2>::json_schema()
+    );
+}
+
+#[derive(Default, Clone, Config, Debug, schemars::JsonSchema)]
 struct TestConfig {
     #[config(default = true)]
     test: bool,
 }
 
-#[derive(Default, Clone, Config, Debug)]
+#[derive(Default, Clone, Config, Debug, schemars::JsonSchema)]
 struct TestConfig2 {
     #[config(nested)]
     nested: Nested,
@@ -120,7 +214,7 @@ impl AsRef<Nested> for TestConfig2 {
     }
 }
 
-#[derive(Mergeable, Config, Default, Clone, Debug)]
+#[derive(Mergeable, Config, Default, Clone, Debug, schemars::JsonSchema)]
 struct Nested {
     #[config(default = true)]
     test: bool,
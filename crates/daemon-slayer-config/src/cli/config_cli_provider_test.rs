@@ -47,6 +47,28 @@ async fn test_config_validate() {
     assert_eq!("Valid\n", String::from_utf8(buf).unwrap());
 }
 
+#[tokio::test]
+async fn test_config_schema() {
+    let config_dir = tempdir().unwrap().keep();
+    let test_config = AppConfig::<TestConfig>::builder(ConfigDir::Custom(config_dir.clone()))
+        .build()
+        .unwrap();
+    let mut buf = Vec::new();
+
+    Cli::builder()
+        .with_provider(ConfigCliProvider::new(test_config))
+        .initialize_from(["provider", "config", "schema"])
+        .unwrap()
+        .handle_input_with_writer(&mut buf)
+        .await
+        .unwrap();
+    let schema: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+    assert_eq!(
+        serde_json::json!({ "type": "boolean", "default": true }),
+        schema["properties"]["test"]
+    );
+}
+
 #[tokio::test]
 async fn test_print() {
     let config_dir = tempdir().unwrap().keep();
@@ -91,7 +113,34 @@ async fn test_config_watcher() {
     rx.recv().await.unwrap();
 }
 
-#[derive(Default, Clone, Config, Debug)]
+#[tokio::test]
+async fn test_config_set() {
+    let config_dir = tempdir().unwrap().keep();
+    let test_config = AppConfig::<TestConfig>::builder(ConfigDir::Custom(config_dir.clone()))
+        .build()
+        .unwrap();
+    let (tx, mut rx) = mpsc::channel(32);
+    let _tx = tx.clone();
+    let watcher = TestConfigWatcher { tx };
+    let cli = Cli::builder()
+        .with_provider(ConfigCliProvider::new(test_config.clone()).with_config_watcher(watcher))
+        .initialize_from(["provider", "config", "set", "test", "false"])
+        .unwrap();
+    cli.handle_input().await.unwrap();
+    rx.recv().await.unwrap();
+
+    let mut buf = Vec::new();
+    Cli::builder()
+        .with_provider(ConfigCliProvider::new(test_config))
+        .initialize_from(["provider", "config", "get", "test"])
+        .unwrap()
+        .handle_input_with_writer(&mut buf)
+        .await
+        .unwrap();
+    assert_eq!("false\n", String::from_utf8(buf).unwrap());
+}
+
+#[derive(Default, Clone, Config, Debug, schemars::JsonSchema)]
 struct TestConfig {
     #[config(default = true)]
     #[allow(unused)]
@@ -16,6 +16,19 @@ enum ConfigCommands {
     Path,
     Edit,
     Validate,
+    /// Print a JSON Schema for the config. Field types are only inferred from default values, so
+    /// fields without a default accept any type
+    Schema,
+    /// Print the value for a dotted path, ex: `server.port`
+    Get {
+        key: String,
+    },
+    /// Update the value for a dotted path, ex: `server.port 8080`
+    Set {
+        key: String,
+        /// Parsed as JSON if possible, otherwise it's treated as a string
+        value: String,
+    },
 }
 
 #[derive(Args, Debug, Clone)]
@@ -77,19 +90,13 @@ impl<T: Configurable> CommandProvider for ConfigCliProvider<T> {
 
     async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
         if let Some(args) = &self.matched_args {
-            return Ok(match args.command {
+            return Ok(match &args.command {
                 Some(ConfigCommands::Path) => {
                     CommandOutput::handled(self.config.full_path().to_string_lossy().to_string())
                 }
                 Some(ConfigCommands::Edit) => {
                     self.config.edit()?;
-                    for watcher in &mut self.watchers {
-                        watcher
-                            .on_config_changed()
-                            .await
-                            .tap_err(|e| error!("Error handling config update: {e:?}"))
-                            .ok();
-                    }
+                    notify_watchers(&mut self.watchers).await;
                     CommandOutput::handled(None)
                 }
                 Some(ConfigCommands::Validate) => match self.config.read_config() {
@@ -98,6 +105,17 @@ impl<T: Configurable> CommandProvider for ConfigCliProvider<T> {
                         CommandOutput::handled(format!("Invalid: {msg}"))
                     }
                 },
+                Some(ConfigCommands::Schema) => CommandOutput::handled(
+                    serde_json::to_string_pretty(&AppConfig::<T>::json_schema())?,
+                ),
+                Some(ConfigCommands::Get { key }) => {
+                    CommandOutput::handled(self.config.get_value(key)?)
+                }
+                Some(ConfigCommands::Set { key, value }) => {
+                    self.config.set_value(key, value)?;
+                    notify_watchers(&mut self.watchers).await;
+                    CommandOutput::handled(None)
+                }
                 None => {
                     #[cfg(feature = "pretty-print")]
                     {
@@ -121,6 +139,16 @@ impl<T: Configurable> CommandProvider for ConfigCliProvider<T> {
     }
 }
 
+async fn notify_watchers(watchers: &mut [Box<dyn ConfigWatcher>]) {
+    for watcher in watchers {
+        watcher
+            .on_config_changed()
+            .await
+            .tap_err(|e| error!("Error handling config update: {e:?}"))
+            .ok();
+    }
+}
+
 #[cfg(test)]
 #[path = "./config_cli_provider_test.rs"]
 mod config_cli_provider_test;
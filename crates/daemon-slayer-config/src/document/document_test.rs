@@ -0,0 +1,32 @@
+use confique::meta::{Expr, Field, FieldKind, LeafKind};
+use serde_json::json;
+
+use super::parse_value;
+
+fn leaf(default: Option<Expr>) -> Field {
+    Field {
+        name: "test",
+        doc: &[],
+        kind: FieldKind::Leaf {
+            env: None,
+            kind: LeafKind::Required { default },
+        },
+    }
+}
+
+#[test]
+fn test_parse_value() {
+    let field = leaf(Some(Expr::Bool(true)));
+    assert_eq!(json!(false), parse_value("false", &field));
+    assert_eq!(json!([1, 2]), parse_value("[1,2]", &field));
+    assert_eq!(json!("name"), parse_value("name", &field));
+    assert_eq!(json!(123), parse_value("123", &leaf(None)));
+}
+
+#[test]
+fn test_parse_string_value() {
+    let field = leaf(Some(Expr::Str("app")));
+    assert_eq!(json!("123"), parse_value("123", &field));
+    assert_eq!(json!("true"), parse_value("true", &field));
+    assert_eq!(json!("quoted"), parse_value("\"quoted\"", &field));
+}
@@ -0,0 +1,305 @@
+use super::indent_of;
+
+struct Node {
+    start: usize,
+    end: usize,
+    object: Option<Object>,
+}
+
+struct Object {
+    close: usize,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    key: String,
+    key_start: usize,
+    value: Node,
+}
+
+/// Records where each value is located so it can be replaced without touching the rest of the
+/// file.
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    // Compared as bytes since the position may be in the middle of a multi-byte character
+    fn rest(&self) -> &[u8] {
+        &self.src.as_bytes()[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> String {
+        let line = self.src.as_bytes()[..self.pos]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count()
+            + 1;
+        format!("{msg} on line {line}")
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            match self.peek() {
+                Some(b) if b.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') if self.rest().starts_with(b"//") => {
+                    self.pos = self
+                        .rest()
+                        .iter()
+                        .position(|b| *b == b'\n')
+                        .map(|i| self.pos + i)
+                        .unwrap_or(self.src.len());
+                }
+                Some(b'/') if self.rest().starts_with(b"/*") => {
+                    let end = self.rest()[2..]
+                        .windows(2)
+                        .position(|window| window == b"*/")
+                        .ok_or_else(|| self.error("Unterminated comment"))?;
+                    self.pos += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, String> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        let object = match self.peek() {
+            Some(b'{') => Some(self.object()?),
+            Some(b'[') => {
+                self.array()?;
+                None
+            }
+            Some(b'"' | b'\'') => {
+                self.string()?;
+                None
+            }
+            Some(_) => {
+                self.scalar()?;
+                None
+            }
+            None => return Err(self.error("Unexpected end of file")),
+        };
+        Ok(Node {
+            start,
+            end: self.pos,
+            object,
+        })
+    }
+
+    fn string(&mut self) -> Result<&'a str, String> {
+        let src = self.src;
+        let quote = self.peek();
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b == b'\\' {
+                self.pos += 2;
+                continue;
+            }
+            self.pos += 1;
+            if Some(b) == quote {
+                return Ok(&src[start..self.pos - 1]);
+            }
+        }
+        Err(self.error("Unterminated string"))
+    }
+
+    // Numbers, literals and unquoted keys
+    fn scalar(&mut self) -> Result<&'a str, String> {
+        let src = self.src;
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if b.is_ascii_whitespace()
+                || matches!(b, b',' | b':' | b'{' | b'}' | b'[' | b']')
+                || self.rest().starts_with(b"//")
+                || self.rest().starts_with(b"/*")
+            {
+                break;
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("Unexpected character"));
+        }
+        Ok(&src[start..self.pos])
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        self.skip_trivia()?;
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("Expected '{}'", expected as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn object(&mut self) -> Result<Object, String> {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b'}') {
+                let close = self.pos;
+                self.pos += 1;
+                return Ok(Object { close, entries });
+            }
+            let key_start = self.pos;
+            let key = match self.peek() {
+                Some(b'"' | b'\'') => self.string()?,
+                _ => self.scalar()?,
+            };
+            self.expect(b':')?;
+            let value = self.value()?;
+            entries.push(Entry {
+                key: key.to_owned(),
+                key_start,
+                value,
+            });
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), String> {
+        self.pos += 1;
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(());
+            }
+            self.value()?;
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+}
+
+fn parse(contents: &str) -> Result<Object, String> {
+    let mut parser = Parser::new(contents);
+    let root = parser.value()?;
+    parser.skip_trivia()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("Unexpected content after the end of the config"));
+    }
+    root.object
+        .ok_or_else(|| "The config must be an object".to_owned())
+}
+
+fn find<'a>(object: &'a Object, key: &str) -> Option<&'a Entry> {
+    object.entries.iter().find(|entry| entry.key == key)
+}
+
+fn line_start(contents: &str, pos: usize) -> usize {
+    contents[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0)
+}
+
+pub(super) fn get(contents: &str, path: &[&str]) -> Result<Option<String>, String> {
+    let mut object = &parse(contents)?;
+    let Some((key, parents)) = path.split_last() else {
+        return Ok(None);
+    };
+    for parent in parents {
+        match find(object, parent).and_then(|entry| entry.value.object.as_ref()) {
+            Some(child) => object = child,
+            None => return Ok(None),
+        }
+    }
+    Ok(find(object, key).map(|entry| contents[entry.value.start..entry.value.end].to_owned()))
+}
+
+pub(super) fn set(contents: &str, path: &[&str], value: &str) -> Result<String, String> {
+    let root = parse(contents)?;
+    let mut object = &root;
+    let mut existing = 0;
+    for (i, key) in path.iter().enumerate() {
+        let Some(entry) = find(object, key) else {
+            break;
+        };
+        if i == path.len() - 1 {
+            let mut contents = contents.to_owned();
+            contents.replace_range(entry.value.start..entry.value.end, value);
+            return Ok(contents);
+        }
+        object = entry
+            .value
+            .object
+            .as_ref()
+            .ok_or_else(|| format!("'{}' is not a section", path[..=i].join(".")))?;
+        existing = i + 1;
+    }
+
+    // Add the rest of the path to the closest section that already exists
+    let close_line = line_start(contents, object.close);
+    let brace_indent = indent_of(&contents[close_line..]);
+    let entry_indent = object
+        .entries
+        .first()
+        .and_then(|entry| {
+            let line = line_start(contents, entry.key_start);
+            contents[line..entry.key_start]
+                .trim()
+                .is_empty()
+                .then(|| entry.key_start - line)
+        })
+        .unwrap_or(brace_indent + 2);
+
+    let missing = &path[existing..];
+    let mut lines = Vec::new();
+    for (depth, key) in missing.iter().enumerate() {
+        let padding = " ".repeat(entry_indent + depth * 2);
+        if depth == missing.len() - 1 {
+            lines.push(format!("{padding}{key}: {value},"));
+        } else {
+            lines.push(format!("{padding}{key}: {{"));
+        }
+    }
+    for depth in (0..missing.len() - 1).rev() {
+        lines.push(format!("{}}},", " ".repeat(entry_indent + depth * 2)));
+    }
+    let inserted = lines.join("\n");
+
+    let mut new_contents = contents.to_owned();
+    if contents[close_line..object.close].trim().is_empty() {
+        new_contents.insert_str(close_line, &format!("{inserted}\n"));
+    } else {
+        new_contents.insert_str(
+            object.close,
+            &format!("\n{inserted}\n{}", " ".repeat(brace_indent)),
+        );
+    }
+    // The previous entry needs a trailing comma. This is inserted last since it comes before the
+    // new entries.
+    if let Some(last) = object.entries.last() {
+        let mut parser = Parser::new(contents);
+        parser.pos = last.value.end;
+        parser.skip_trivia()?;
+        if parser.peek() != Some(b',') {
+            new_contents.insert(last.value.end, ',');
+        }
+    }
+    Ok(new_contents)
+}
+
+#[cfg(test)]
+#[path = "./json5_test.rs"]
+mod json5_test;
@@ -0,0 +1,38 @@
+use super::{get, set};
+
+#[test]
+fn test_non_ascii() {
+    let contents = "{\n  name: 'héllo', // ünïcode\n  ключ: 1,\n  /* ñ */ port: 8080,\n}\n";
+    assert_eq!(
+        Some("'héllo'".to_owned()),
+        get(contents, &["name"]).unwrap()
+    );
+    assert_eq!(Some("1".to_owned()), get(contents, &["ключ"]).unwrap());
+    assert_eq!(Some("8080".to_owned()), get(contents, &["port"]).unwrap());
+    assert_eq!(
+        "{\n  name: 'héllo', // ünïcode\n  ключ: 1,\n  /* ñ */ port: 9090,\n}\n",
+        set(contents, &["port"], "9090").unwrap()
+    );
+}
+
+#[test]
+fn test_non_ascii_errors() {
+    assert!(get("{ name: é", &["name"]).is_err());
+    assert!(get("{ name: 'é", &["name"]).is_err());
+    assert!(get("{ name: \"\\é\" é }", &["name"]).is_err());
+    assert!(get("{ /* é", &["name"]).is_err());
+}
+
+#[test]
+fn test_insert() {
+    let contents = "{\n  server: {\n    host: 'a' // the host\n  },\n}\n";
+    assert_eq!(
+        "{\n  server: {\n    host: 'a', // the host\n    port: 8080,\n  },\n}\n",
+        set(contents, &["server", "port"], "8080").unwrap()
+    );
+    assert_eq!(
+        "{\n  server: {\n    host: 'a' // the host\n  },\n  client: {\n    port: 1,\n  },\n}\n",
+        set(contents, &["client", "port"], "1").unwrap()
+    );
+    assert!(set(contents, &["server", "host", "name"], "1").is_err());
+}
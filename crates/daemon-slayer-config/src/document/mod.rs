@@ -0,0 +1,107 @@
+//! Reads and updates individual values in a config file without reformatting the rest of the file.
+//! YAML and JSON5 are edited in place by locating the span of the value in the original text.
+
+mod json5;
+mod toml;
+mod yaml;
+
+use confique::meta::{Expr, Field, FieldKind, LeafKind, Meta};
+use serde_json::Value;
+
+use crate::ConfigFileType;
+
+/// Returns the value at `path` as it's written in the file, or `None` if it isn't set.
+pub(crate) fn get(
+    file_type: &ConfigFileType,
+    contents: &str,
+    path: &[&str],
+) -> Result<Option<String>, String> {
+    match file_type {
+        ConfigFileType::Toml => toml::get(contents, path),
+        ConfigFileType::Yaml => Ok(yaml::get(contents, path)),
+        ConfigFileType::Json5 => json5::get(contents, path),
+    }
+}
+
+/// Sets the value at `path`, creating any missing sections.
+pub(crate) fn set(
+    file_type: &ConfigFileType,
+    contents: &str,
+    path: &[&str],
+    value: &Value,
+) -> Result<String, String> {
+    match file_type {
+        ConfigFileType::Toml => toml::set(contents, path, value),
+        // JSON is valid YAML and JSON5, so values can be written the same way for both
+        ConfigFileType::Yaml => yaml::set(contents, path, &value.to_string()),
+        ConfigFileType::Json5 => json5::set(contents, path, &value.to_string()),
+    }
+}
+
+/// Values are parsed as JSON so numbers, booleans, arrays and objects can be set. Anything that
+/// isn't valid JSON is treated as a string, as is any value for a field with a string default so
+/// `123` stays a string there.
+pub(crate) fn parse_value(value: &str, field: &Field) -> Value {
+    let is_string = matches!(
+        &field.kind,
+        FieldKind::Leaf {
+            kind: LeafKind::Required {
+                default: Some(Expr::Str(_)),
+            },
+            ..
+        }
+    );
+    match serde_json::from_str(value) {
+        Ok(Value::String(value)) => Value::String(value),
+        Ok(parsed) if !is_string => parsed,
+        _ => Value::String(value.to_owned()),
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Finds the field for a dotted path, ex: `server.port`.
+pub(crate) fn find_field<'a>(
+    meta: &'static Meta,
+    key: &'a str,
+) -> Result<(Vec<&'a str>, &'static Field), String> {
+    let path: Vec<_> = key.split('.').collect();
+    let mut meta = meta;
+    let mut field = None;
+    for (i, name) in path.iter().enumerate() {
+        let found = meta
+            .fields
+            .iter()
+            .find(|field| field.name == *name)
+            .ok_or_else(|| format!("'{name}' is not a config field"))?;
+        match &found.kind {
+            FieldKind::Nested { meta: nested } => meta = nested,
+            FieldKind::Leaf { .. } if i < path.len() - 1 => {
+                return Err(format!("'{name}' is not a section"));
+            }
+            FieldKind::Leaf { .. } => {}
+        }
+        field = Some(found);
+    }
+    field
+        .map(|field| (path, field))
+        .ok_or_else(|| "key is empty".to_owned())
+}
+
+pub(crate) fn default_value(field: &Field) -> Option<String> {
+    match &field.kind {
+        FieldKind::Leaf {
+            kind: LeafKind::Required {
+                default: Some(default),
+            },
+            ..
+        } => serde_json::to_string(default).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./document_test.rs"]
+mod document_test;
@@ -0,0 +1,80 @@
+use serde_json::Value;
+use toml_edit::{Array, DocumentMut, InlineTable, Item};
+
+fn parse(contents: &str) -> Result<DocumentMut, String> {
+    contents
+        .parse()
+        .map_err(|e: toml_edit::TomlError| e.to_string())
+}
+
+pub(super) fn get(contents: &str, path: &[&str]) -> Result<Option<String>, String> {
+    let document = parse(contents)?;
+    let mut item = document.as_item();
+    for key in path {
+        match item.get(key) {
+            Some(child) => item = child,
+            None => return Ok(None),
+        }
+    }
+    Ok(match item {
+        Item::None => None,
+        Item::Value(value) => Some(value.to_string().trim().to_owned()),
+        item => Some(item.to_string().trim().to_owned()),
+    })
+}
+
+pub(super) fn set(contents: &str, path: &[&str], value: &Value) -> Result<String, String> {
+    let mut document = parse(contents)?;
+    let Some((key, parents)) = path.split_last() else {
+        return Err("path is empty".to_owned());
+    };
+
+    let mut item = document.as_item_mut();
+    for parent in parents {
+        item = item
+            .as_table_like_mut()
+            .ok_or_else(|| format!("'{parent}' is not in a table"))?
+            .entry(parent)
+            .or_insert(toml_edit::table());
+    }
+    let table = item
+        .as_table_like_mut()
+        .ok_or_else(|| format!("'{key}' is not in a table"))?;
+
+    let mut new_value = to_toml(value)?;
+    // Keep any comments next to the old value
+    if let Some(old_value) = table.get(key).and_then(Item::as_value) {
+        *new_value.decor_mut() = old_value.decor().clone();
+    }
+    table.insert(key, Item::Value(new_value));
+    Ok(document.to_string())
+}
+
+fn to_toml(value: &Value) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        Value::Null => return Err("TOML doesn't support null values".to_owned()),
+        Value::Bool(value) => (*value).into(),
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => number.into(),
+            None => number
+                .as_f64()
+                .ok_or_else(|| format!("{number} is out of range"))?
+                .into(),
+        },
+        Value::String(value) => value.as_str().into(),
+        Value::Array(values) => {
+            let mut array = Array::new();
+            for value in values {
+                array.push(to_toml(value)?);
+            }
+            array.into()
+        }
+        Value::Object(values) => {
+            let mut table = InlineTable::new();
+            for (key, value) in values {
+                table.insert(key, to_toml(value)?);
+            }
+            table.into()
+        }
+    })
+}
@@ -0,0 +1,300 @@
+use super::indent_of;
+
+/// A line containing a mapping key. Keys inside sequences and block scalars are skipped since
+/// they can't be addressed with a dotted path.
+struct KeyLine<'a> {
+    index: usize,
+    indent: usize,
+    path: Vec<&'a str>,
+    // Byte offset of the end of the key's colon within the line
+    value_offset: usize,
+    // Index of the last line of the value, which is the end of the nested block for sections
+    end: usize,
+}
+
+fn parse_key(trimmed: &str) -> Option<(&str, usize)> {
+    if trimmed.starts_with(['"', '\'']) {
+        let quote = trimmed.chars().next()?;
+        let close = trimmed[1..].find(quote)? + 1;
+        let after = &trimmed[close + 1..];
+        let colon = close + 1 + (after.len() - after.trim_start().len());
+        return is_key_colon(trimmed, colon).then(|| (&trimmed[1..close], colon + 1));
+    }
+    if trimmed.starts_with(['{', '[', '&', '*', '!', '|', '>']) {
+        return None;
+    }
+    trimmed
+        .char_indices()
+        .find(|(i, c)| *c == ':' && is_key_colon(trimmed, *i))
+        .map(|(i, _)| (trimmed[..i].trim_end(), i + 1))
+}
+
+fn is_key_colon(trimmed: &str, index: usize) -> bool {
+    trimmed[index..].starts_with(':')
+        && trimmed[index + 1..]
+            .chars()
+            .next()
+            .is_none_or(char::is_whitespace)
+}
+
+/// Splits a value from a trailing comment.
+fn split_comment(value: &str) -> (&str, Option<&str>) {
+    let (value, comment, _) = split_comment_from(value, None);
+    (value, comment)
+}
+
+/// Splits a line of a value from a trailing comment, starting inside `quote` if a quoted scalar
+/// was left open on a previous line. Also returns the quote that's still open at the end.
+fn split_comment_from(value: &str, mut quote: Option<char>) -> (&str, Option<&str>, Option<char>) {
+    let mut after_whitespace = true;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' && after_whitespace => {
+                return (&value[..i], Some(&value[i..]), None);
+            }
+            None => {}
+        }
+        after_whitespace = c.is_whitespace();
+    }
+    (value, None, quote)
+}
+
+fn is_ignored(trimmed: &str) -> bool {
+    trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn scan<'a>(lines: &[&'a str]) -> Vec<KeyLine<'a>> {
+    let mut keys = Vec::new();
+    let mut parents: Vec<(usize, &str)> = Vec::new();
+    // Indentation of a sequence or block scalar whose contents are being skipped
+    let mut skip: Option<(usize, bool)> = None;
+    // Last line of a scalar that continues onto the following lines
+    let mut skip_until = None;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if is_ignored(trimmed) || skip_until.is_some_and(|end| index <= end) {
+            continue;
+        }
+        let indent = indent_of(line);
+        if let Some((skip_indent, is_sequence)) = skip {
+            if indent > skip_indent
+                || (is_sequence && indent == skip_indent && trimmed.starts_with('-'))
+            {
+                continue;
+            }
+            skip = None;
+        }
+        if trimmed.starts_with("---") || trimmed.starts_with("...") {
+            parents.clear();
+            continue;
+        }
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            skip = Some((indent, true));
+            continue;
+        }
+        let Some((key, value_offset)) = parse_key(trimmed) else {
+            continue;
+        };
+        let (value, _) = split_comment(&trimmed[value_offset..]);
+        let value = value.trim();
+        let end = if value.is_empty() || value.starts_with(['|', '>']) {
+            block_end(lines, index, indent)
+        } else {
+            scalar_end(lines, index, indent, &trimmed[value_offset..])
+        };
+        if value.starts_with(['|', '>']) {
+            skip = Some((indent, false));
+        } else if !value.is_empty() {
+            skip_until = Some(end);
+        }
+        while parents
+            .last()
+            .is_some_and(|(parent_indent, _)| *parent_indent >= indent)
+        {
+            parents.pop();
+        }
+        parents.push((indent, key));
+        keys.push(KeyLine {
+            index,
+            indent,
+            path: parents.iter().map(|(_, key)| *key).collect(),
+            value_offset: indent + value_offset,
+            end,
+        });
+    }
+    keys
+}
+
+/// Index of the last line that's nested under the key.
+fn block_end(lines: &[&str], index: usize, indent: usize) -> usize {
+    let mut end = index;
+    for (index, line) in lines.iter().enumerate().skip(index + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent_of(line) <= indent {
+            break;
+        }
+        end = index;
+    }
+    end
+}
+
+/// Index of the last line of a plain or quoted scalar. Plain scalars continue onto lines that are
+/// indented further than the key until a comment, and quoted scalars continue until the closing
+/// quote.
+fn scalar_end(lines: &[&str], index: usize, indent: usize, value: &str) -> usize {
+    let is_quoted = value.trim_start().starts_with(['"', '\'']);
+    let (_, mut comment, mut quote) = split_comment_from(value, None);
+    let mut end = index;
+    for (index, line) in lines.iter().enumerate().skip(index + 1) {
+        if quote.is_none() && (is_quoted || comment.is_some()) {
+            break;
+        }
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if indent_of(line) <= indent || (quote.is_none() && trimmed.starts_with('#')) {
+            break;
+        }
+        (_, comment, quote) = split_comment_from(trimmed, quote);
+        end = index;
+    }
+    end
+}
+
+fn inline_value<'a>(line: &'a str, key: &KeyLine) -> &'a str {
+    split_comment(&line[key.value_offset..]).0.trim()
+}
+
+/// The text of a value that spans multiple lines, starting after the key's colon.
+fn value_text(lines: &[&str], key: &KeyLine) -> String {
+    std::iter::once(&lines[key.index][key.value_offset..])
+        .chain(lines[key.index + 1..=key.end].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(super) fn get(contents: &str, path: &[&str]) -> Option<String> {
+    let lines: Vec<_> = contents.lines().collect();
+    let keys = scan(&lines);
+    let key = keys.iter().find(|key| key.path == path)?;
+    let value = inline_value(lines[key.index], key);
+    if value.starts_with(['|', '>']) || (!value.is_empty() && key.end == key.index) {
+        return Some(value.to_owned());
+    }
+    if !value.is_empty() {
+        return Some(
+            split_comment(&value_text(&lines, key))
+                .0
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+    let block = &lines[key.index + 1..=key.end];
+    let indent = block
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indent_of(line))
+        .min()
+        .unwrap_or_default();
+    Some(
+        block
+            .iter()
+            .map(|line| line.get(indent..).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+pub(super) fn set(contents: &str, path: &[&str], value: &str) -> Result<String, String> {
+    let lines: Vec<_> = contents.lines().collect();
+    let keys = scan(&lines);
+    let mut new_lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+
+    if let Some(key) = keys.iter().find(|key| key.path == path) {
+        let line = lines[key.index];
+        let old_value = inline_value(line, key);
+        // Sections and block scalars can only have a comment on the key's line, but comments
+        // after a multi-line scalar come after its last line
+        let comment = if old_value.is_empty() || old_value.starts_with(['|', '>']) {
+            split_comment(&line[key.value_offset..])
+                .1
+                .map(str::to_owned)
+        } else {
+            split_comment(&value_text(&lines, key)).1.map(str::to_owned)
+        };
+        let comment = comment
+            .map(|comment| format!(" {comment}"))
+            .unwrap_or_default();
+        // Replace the whole span of the old value, including any nested block
+        new_lines.splice(
+            key.index..=key.end,
+            [format!("{} {value}{comment}", &line[..key.value_offset])],
+        );
+        return Ok(join(new_lines, contents));
+    }
+
+    // Find the closest section that already exists and add the rest of the path to it
+    let (existing, parent) = (0..path.len())
+        .rev()
+        .find_map(|len| {
+            if len == 0 {
+                return Some((0, None));
+            }
+            keys.iter()
+                .find(|key| key.path == path[..len])
+                .map(|key| (len, Some(key)))
+        })
+        .unwrap_or((0, None));
+    if let Some(parent) = parent
+        && !inline_value(lines[parent.index], parent).is_empty()
+    {
+        return Err(format!("'{}' is not a section", path[..existing].join(".")));
+    }
+
+    let child_indent = keys
+        .iter()
+        .find(|key| key.path.len() == existing + 1 && key.path[..existing] == path[..existing])
+        .map(|key| key.indent)
+        .unwrap_or_else(|| parent.map(|parent| parent.indent + 2).unwrap_or_default());
+    let insert_at = parent.map(|parent| parent.end + 1).unwrap_or(lines.len());
+    let missing = &path[existing..];
+    let inserted = missing.iter().enumerate().map(|(depth, key)| {
+        let padding = " ".repeat(child_indent + depth * 2);
+        if depth == missing.len() - 1 {
+            format!("{padding}{key}: {value}")
+        } else {
+            format!("{padding}{key}:")
+        }
+    });
+    new_lines.splice(insert_at..insert_at, inserted);
+    Ok(join(new_lines, contents))
+}
+
+/// Joins the lines with the same line endings as the original contents.
+fn join(lines: Vec<String>, original: &str) -> String {
+    let newline = if original.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut contents = lines.join(newline);
+    if original.is_empty() || original.ends_with('\n') {
+        contents.push_str(newline);
+    }
+    contents
+}
+
+#[cfg(test)]
+#[path = "./yaml_test.rs"]
+mod yaml_test;
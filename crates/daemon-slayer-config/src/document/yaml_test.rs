@@ -0,0 +1,123 @@
+use super::{get, set};
+
+#[test]
+fn test_quoted_keys() {
+    let contents = "\"quoted key\": 1\n'single: quoted': a\n";
+    assert_eq!(Some("1".to_owned()), get(contents, &["quoted key"]));
+    assert_eq!(Some("a".to_owned()), get(contents, &["single: quoted"]));
+    assert_eq!(
+        "\"quoted key\": 1\n'single: quoted': \"b\"\n",
+        set(contents, &["single: quoted"], "\"b\"").unwrap()
+    );
+}
+
+#[test]
+fn test_sequences() {
+    let contents = "list:\n  - name: a\n  - name: b\nname: top\n";
+    assert_eq!(Some("top".to_owned()), get(contents, &["name"]));
+    assert_eq!(
+        Some("- name: a\n- name: b".to_owned()),
+        get(contents, &["list"])
+    );
+    assert_eq!(None, get(contents, &["list", "name"]));
+    assert_eq!(
+        "list:\n  - name: a\n  - name: b\nname: other\n",
+        set(contents, &["name"], "other").unwrap()
+    );
+    assert_eq!(
+        "list: [1,2]\nname: top\n",
+        set(contents, &["list"], "[1,2]").unwrap()
+    );
+}
+
+#[test]
+fn test_block_scalars() {
+    let contents = "script: |\n  name: not a key\n  run\nname: top\n";
+    assert_eq!(Some("top".to_owned()), get(contents, &["name"]));
+    assert_eq!(None, get(contents, &["script", "name"]));
+    assert_eq!(
+        "script: |\n  name: not a key\n  run\nname: other\n",
+        set(contents, &["name"], "other").unwrap()
+    );
+}
+
+#[test]
+fn test_trailing_comments() {
+    let contents = "port: 8080 # the port\nhost: \"a #b\" # the host\n";
+    assert_eq!(Some("8080".to_owned()), get(contents, &["port"]));
+    assert_eq!(Some("\"a #b\"".to_owned()), get(contents, &["host"]));
+    assert_eq!(
+        "port: 9090 # the port\nhost: \"a #b\" # the host\n",
+        set(contents, &["port"], "9090").unwrap()
+    );
+}
+
+#[test]
+fn test_insert_into_existing_section() {
+    let contents = "server:\n    host: a\n\nlogging:\n  level: info\n";
+    assert_eq!(
+        "server:\n    host: a\n    port: 8080\n\nlogging:\n  level: info\n",
+        set(contents, &["server", "port"], "8080").unwrap()
+    );
+    assert_eq!(
+        "server:\n    host: a\n    tls:\n      enabled: true\n\nlogging:\n  level: info\n",
+        set(contents, &["server", "tls", "enabled"], "true").unwrap()
+    );
+    assert_eq!(
+        "server:\n    host: a\n\nlogging:\n  level: info\nclient:\n  port: 1\n",
+        set(contents, &["client", "port"], "1").unwrap()
+    );
+    assert!(set(contents, &["server", "host", "name"], "b").is_err());
+}
+
+#[test]
+fn test_replace_section() {
+    let contents = "filters:\n  file: debug\nname: top\n";
+    assert_eq!(
+        "filters: {\"file\":\"trace\"}\nname: top\n",
+        set(contents, &["filters"], "{\"file\":\"trace\"}").unwrap()
+    );
+}
+
+#[test]
+fn test_crlf() {
+    let contents = "server:\r\n  port: 1\r\nname: top\r\n";
+    assert_eq!(Some("1".to_owned()), get(contents, &["server", "port"]));
+    assert_eq!(
+        "server:\r\n  port: 2\r\nname: top\r\n",
+        set(contents, &["server", "port"], "2").unwrap()
+    );
+    assert_eq!(
+        "server:\r\n  port: 1\r\n  host: a\r\nname: top\r\n",
+        set(contents, &["server", "host"], "a").unwrap()
+    );
+}
+
+#[test]
+fn test_multi_line_scalars() {
+    let contents =
+        "plain: first\n  second\nquoted: \"first\n  key: second\" # comment\nname: top\n";
+    assert_eq!(Some("first\nsecond".to_owned()), get(contents, &["plain"]));
+    assert_eq!(
+        Some("\"first\nkey: second\"".to_owned()),
+        get(contents, &["quoted"])
+    );
+    assert_eq!(None, get(contents, &["quoted", "key"]));
+    assert_eq!(
+        "plain: other\nquoted: \"first\n  key: second\" # comment\nname: top\n",
+        set(contents, &["plain"], "other").unwrap()
+    );
+    assert_eq!(
+        "plain: first\n  second\nquoted: \"other\" # comment\nname: top\n",
+        set(contents, &["quoted"], "\"other\"").unwrap()
+    );
+}
+
+#[test]
+fn test_replace_block_scalar() {
+    let contents = "script: | # comment\n  run\nname: top\n";
+    assert_eq!(
+        "script: \"other\" # comment\nname: top\n",
+        set(contents, &["script"], "\"other\"").unwrap()
+    );
+}
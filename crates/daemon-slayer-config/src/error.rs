@@ -17,6 +17,10 @@ pub enum ConfigEditError {
     LoadFailure(ConfigLoadError),
     #[error("Error editing config file {0}: {1}")]
     IOFailure(PathBuf, io::Error),
+    #[error("Invalid config key '{0}': {1}")]
+    InvalidKey(String, String),
+    #[error("Error parsing config file {0:#?}: {1}")]
+    ParseFailure(PathBuf, String),
 }
 
 #[derive(thiserror::Error, Debug)]
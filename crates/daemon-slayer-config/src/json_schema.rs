@@ -0,0 +1,90 @@
+use confique::Config;
+use confique::meta::{Field, FieldKind, LeafKind, Meta};
+use schemars::JsonSchema;
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value};
+
+pub(crate) fn generate<T: Config + JsonSchema>() -> Value {
+    // Inlining keeps each section's schema where its metadata can be applied to it
+    let generator = SchemaSettings::draft2020_12()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+    let mut schema = generator.into_root_schema_for::<T>().to_value();
+    if let Value::Object(schema) = &mut schema {
+        apply_meta(schema, &T::META);
+        schema.insert("title".to_owned(), T::META.name.into());
+    }
+    schema
+}
+
+// The types describe the shape of each field, but defaults and whether a field can be omitted
+// come from the config metadata
+fn apply_meta(schema: &mut Map<String, Value>, meta: &Meta) {
+    schema.insert("additionalProperties".to_owned(), false.into());
+    if let Some(description) = description(meta.doc) {
+        schema.insert("description".to_owned(), description.into());
+    }
+    let required: Vec<_> = meta
+        .fields
+        .iter()
+        .filter(|field| is_required(field))
+        .map(|field| field.name)
+        .collect();
+    if required.is_empty() {
+        schema.remove("required");
+    } else {
+        schema.insert("required".to_owned(), required.into());
+    }
+
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+    for field in meta.fields {
+        let Some(Value::Object(field_schema)) = properties.get_mut(field.name) else {
+            continue;
+        };
+        match &field.kind {
+            FieldKind::Nested { meta } => apply_meta(field_schema, meta),
+            FieldKind::Leaf {
+                kind:
+                    LeafKind::Required {
+                        default: Some(default),
+                    },
+                ..
+            } => {
+                field_schema.insert(
+                    "default".to_owned(),
+                    serde_json::to_value(default).unwrap_or(Value::Null),
+                );
+            }
+            FieldKind::Leaf { .. } => {}
+        }
+        // Docs on the field take precedence over docs on the nested struct
+        if let Some(description) = description(field.doc) {
+            field_schema.insert("description".to_owned(), description.into());
+        }
+    }
+}
+
+// Values that can be set from the environment are still considered optional since the file may
+// not contain them
+fn is_required(field: &Field) -> bool {
+    match &field.kind {
+        FieldKind::Nested { meta } => meta.fields.iter().any(is_required),
+        FieldKind::Leaf { env, kind } => {
+            env.is_none() && matches!(kind, LeafKind::Required { default: None })
+        }
+    }
+}
+
+fn description(doc: &[&str]) -> Option<String> {
+    if doc.is_empty() {
+        return None;
+    }
+    Some(
+        doc.iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
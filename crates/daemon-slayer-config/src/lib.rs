@@ -3,7 +3,9 @@ mod app_config_builder;
 #[cfg(feature = "cli")]
 pub mod cli;
 mod config_file_type;
+mod document;
 mod error;
+mod json_schema;
 #[cfg(feature = "pretty-print")]
 mod pretty_print;
 #[cfg(feature = "server")]
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use daemon_slayer_core::BoxedError;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use daemon_slayer_core::server::{BroadcastEventStore, EventStore};
 use daemon_slayer_file_watcher::FileWatcher;
 use futures::stream::StreamExt;
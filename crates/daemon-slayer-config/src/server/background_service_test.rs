@@ -3,7 +3,7 @@ use std::time::Duration;
 use confique::Config;
 use daemon_slayer_core::CancellationToken;
 use daemon_slayer_core::server::EventStore;
-use daemon_slayer_core::server::background_service::{self, Manager};
+use daemon_slayer_core::server::tracked::{self, Manager};
 use futures::StreamExt;
 use tempfile::tempdir;
 
@@ -13,10 +13,7 @@ use crate::{AppConfig, ConfigDir};
 #[tokio::test]
 async fn test_service() {
     let cancellation_token = CancellationToken::new();
-    let service_manager = Manager::new(
-        cancellation_token.clone(),
-        background_service::Settings::default(),
-    );
+    let service_manager = Manager::new(cancellation_token.clone(), tracked::Settings::default());
     let config_dir = tempdir().unwrap().keep();
     let test_config = AppConfig::<TestConfig>::builder(ConfigDir::Custom(config_dir.clone()))
         .build()
@@ -36,7 +33,7 @@ async fn test_service() {
     service_manager.cancel().await.unwrap();
 }
 
-#[derive(Default, Clone, Config, Debug)]
+#[derive(Default, Clone, Config, Debug, schemars::JsonSchema)]
 struct TestConfig {
     #[config(default = true)]
     test: bool,
@@ -12,10 +12,8 @@ use crossterm::terminal::{
 };
 use daemon_slayer_client::{ServiceManager, State, Status};
 use daemon_slayer_core::config::{Accessor, CachedConfig};
-use daemon_slayer_core::health_check::HealthCheck;
-use daemon_slayer_core::server::background_service::{
-    self, BackgroundService, Manager, ServiceContext,
-};
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+use daemon_slayer_core::server::tracked::{self, BackgroundService, Manager, ServiceContext};
 use daemon_slayer_core::{BoxedError, CancellationToken};
 use futures::StreamExt;
 use ratatui::backend::CrosstermBackend;
@@ -33,7 +31,10 @@ use tokio::sync::mpsc;
 use tokio_util::future::FutureExt;
 
 #[derive(daemon_slayer_core::Mergeable, Debug, Clone, Default, PartialEq, Eq)]
-#[cfg_attr(feature = "config", derive(confique::Config, serde::Deserialize))]
+#[cfg_attr(
+    feature = "config",
+    derive(confique::Config, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct UserConfig {
     #[cfg_attr(feature = "config", config(default = true))]
     pub enable_health_check: bool,
@@ -44,14 +45,14 @@ pub struct UserConfig {
 struct HealthChecker {
     user_config: CachedConfig<UserConfig>,
     health_check: Box<dyn HealthCheck + Send + 'static>,
-    tx: mpsc::Sender<bool>,
+    tx: mpsc::Sender<HealthStatus>,
 }
 
 impl HealthChecker {
     fn new(
         user_config: CachedConfig<UserConfig>,
         health_check: Box<dyn HealthCheck + Send + 'static>,
-        tx: mpsc::Sender<bool>,
+        tx: mpsc::Sender<HealthStatus>,
     ) -> Self {
         Self {
             user_config,
@@ -67,26 +68,16 @@ impl BackgroundService for HealthChecker {
     }
 
     async fn run(mut self, context: ServiceContext) -> Result<(), BoxedError> {
-        let mut is_healthy: Option<bool> = None;
-        while let Some(res) = self
+        let mut current_status: Option<HealthStatus> = None;
+        while let Some(status) = self
             .health_check
-            .invoke()
+            .status()
             .with_cancellation_token(context.cancellation_token())
             .await
         {
-            match res {
-                Ok(()) => {
-                    if is_healthy != Some(true) {
-                        is_healthy = Some(true);
-                        let _ = self.tx.send(true).await;
-                    }
-                }
-                Err(_e) => {
-                    if is_healthy != Some(false) {
-                        is_healthy = Some(false);
-                        let _ = self.tx.send(false).await;
-                    }
-                }
+            if current_status.as_ref() != Some(&status) {
+                current_status = Some(status.clone());
+                let _ = self.tx.send(status).await;
             }
             let sleep_time =
                 Duration::from_secs(self.user_config.load().health_check_interval_seconds);
@@ -108,7 +99,7 @@ pub struct Console {
     info: Status,
     logs: LogView<'static>,
     button_index: usize,
-    is_healthy: Option<bool>,
+    health_status: Option<HealthStatus>,
     health_check: Option<Box<dyn HealthCheck + Send + Sync + 'static>>,
     has_health_check: bool,
     user_config: CachedConfig<UserConfig>,
@@ -129,7 +120,7 @@ impl Console {
                 }
             },
             button_index: 0,
-            is_healthy: None,
+            health_status: None,
             health_check: None,
             has_health_check: false,
             user_config: Default::default(),
@@ -196,7 +187,7 @@ impl Console {
     ) -> Result<(), BoxedError> {
         let manager = Manager::new(
             cancellation_token.child_token(),
-            background_service::Settings::default(),
+            tracked::Settings::default(),
         );
         let context = manager.get_context();
         if let Some(event_fn) = self.event_fn.take() {
@@ -227,8 +218,8 @@ impl Console {
 
             tokio::select! {
                 _ = self.logs.update() => {}
-                is_healthy = health_rx.recv() => {
-                    self.is_healthy = is_healthy;
+                health_status = health_rx.recv() => {
+                    self.health_status = health_status;
                 }
                 maybe_event = event_reader.next() => {
                     if let Some(event) = maybe_event {
@@ -331,9 +322,16 @@ impl Console {
             None => get_label_value("N/A", Color::Reset),
         };
         let health_check_label = get_label("Health:");
-        let health_check_value = match (self.is_healthy, &self.info.state) {
-            (Some(true), State::Started) => get_label_value("Healthy", Color::Green),
-            (Some(false), State::Started) => get_label_value("Unhealthy", Color::Red),
+        let health_check_value = match (&self.health_status, &self.info.state) {
+            (Some(HealthStatus::Healthy), State::Started) => {
+                get_label_value("Healthy", Color::Green)
+            }
+            (Some(HealthStatus::Degraded(_)), State::Started) => {
+                get_label_value("Degraded", Color::Yellow)
+            }
+            (Some(HealthStatus::Unhealthy(_)), State::Started) => {
+                get_label_value("Unhealthy", Color::Red)
+            }
             _ => get_label_value("N/A", Color::Reset),
         };
 
@@ -12,6 +12,7 @@ pub enum Action {
 pub enum ServerAction {
     Run,
     Direct,
+    Daemonize,
 }
 
 #[derive(Display, Clone, PartialEq, Eq, Hash, Debug, EnumString)]
@@ -28,4 +29,7 @@ pub enum ClientAction {
     Enable,
     Disable,
     Pid,
+    Pause,
+    Resume,
+    Send,
 }
@@ -0,0 +1,66 @@
+use std::io;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tipsy::{Endpoint, IntoIpcPath, ServerId};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::{ControlRequest, ControlResponse};
+use crate::Label;
+use crate::runtime_dir::runtime_dir;
+
+/// Socket in the [`runtime_dir`] that the service listens on for control requests.
+pub fn control_socket_path(label: &Label) -> io::Result<PathBuf> {
+    ServerId::new(format!("{}_control", label.application))
+        .parent_folder(runtime_dir(label))
+        .into_ipc_path()
+}
+
+/// Reads a single newline-delimited JSON message. Returns `None` if the stream was closed.
+pub async fn read_message<T: DeserializeOwned>(
+    reader: &mut (impl AsyncBufRead + Unpin),
+) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+pub async fn write_message<T: Serialize>(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &T,
+) -> io::Result<()> {
+    let mut payload = serde_json::to_vec(message)?;
+    payload.push(b'\n');
+    writer.write_all(&payload).await?;
+    writer.flush().await
+}
+
+#[derive(Clone, Debug)]
+pub struct ControlClient {
+    sock_path: PathBuf,
+}
+
+impl ControlClient {
+    pub fn new(label: &Label) -> io::Result<Self> {
+        Ok(Self {
+            sock_path: control_socket_path(label)?,
+        })
+    }
+
+    pub async fn send(&self, request: &ControlRequest) -> io::Result<ControlResponse> {
+        let client = Endpoint::connect(self.sock_path.clone()).await?;
+        let (reader, mut writer) = tokio::io::split(client);
+        write_message(&mut writer, request).await?;
+        read_message(&mut BufReader::new(reader))
+            .await?
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Connection closed before a response was received",
+                )
+            })
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::BoxedError;
+
+type SetLogLevelFn = Box<dyn Fn(&str, Option<Duration>) -> Result<(), BoxedError> + Send + Sync>;
+
+static SET_LOG_LEVEL: RwLock<Option<SetLogLevelFn>> = RwLock::new(None);
+
+/// Registers the function that handles [`ControlRequest::SetLogLevel`](super::ControlRequest).
+/// The logging crate registers its reload handle here when the logger is built, so services don't
+/// need to forward the request themselves. Registering again replaces the previous function.
+pub fn register_log_level_handler(
+    set_log_level: impl Fn(&str, Option<Duration>) -> Result<(), BoxedError> + Send + Sync + 'static,
+) {
+    *SET_LOG_LEVEL.write().unwrap() = Some(Box::new(set_log_level));
+}
+
+/// Changes the log level with the registered handler. Fails if logging hasn't been configured.
+pub fn set_log_level(level: &str, revert_after: Option<Duration>) -> Result<(), BoxedError> {
+    match &*SET_LOG_LEVEL.read().unwrap() {
+        Some(set_log_level) => set_log_level(level, revert_after),
+        None => Err("The service doesn't support changing the log level".into()),
+    }
+}
+
+#[cfg(test)]
+#[path = "./log_level_test.rs"]
+mod log_level_test;
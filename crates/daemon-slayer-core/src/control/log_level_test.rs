@@ -0,0 +1,31 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{register_log_level_handler, set_log_level};
+
+#[test]
+fn test_set_log_level() {
+    assert!(set_log_level("debug", None).is_err());
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    register_log_level_handler({
+        let calls = calls.clone();
+        move |level, revert_after| {
+            if level == "app=loud" {
+                return Err("invalid filter directive".into());
+            }
+            calls
+                .lock()
+                .unwrap()
+                .push(format!("{level} {revert_after:?}"));
+            Ok(())
+        }
+    });
+
+    set_log_level("app=debug", Some(Duration::from_secs(600))).unwrap();
+    assert_eq!(
+        "invalid filter directive",
+        set_log_level("app=loud", None).unwrap_err().to_string()
+    );
+    assert_eq!(vec!["app=debug Some(600s)"], *calls.lock().unwrap());
+}
@@ -0,0 +1,63 @@
+#[cfg(feature = "control")]
+mod ipc;
+mod log_level;
+
+#[cfg(feature = "control")]
+pub use ipc::*;
+pub use log_level::*;
+use serde::{Deserialize, Serialize};
+
+/// Requests sent to a running service over its control channel.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ControlRequest {
+    Pause,
+    Resume,
+    /// Reloads the service's configuration.
+    Reload,
+    Command {
+        name: String,
+        args: Vec<String>,
+    },
+    Status,
+    ListServices,
+    /// Changes the log level or filter directives, optionally reverting after the given number of
+    /// seconds.
+    SetLogLevel {
+        level: String,
+        #[serde(default)]
+        revert_after_secs: Option<u64>,
+    },
+}
+
+impl ControlRequest {
+    /// Name of the request without any of its arguments, which may contain sensitive values.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Reload => "reload",
+            Self::Command { .. } => "command",
+            Self::Status => "status",
+            Self::ListServices => "list-services",
+            Self::SetLogLevel { .. } => "set-log-level",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum ControlResponse {
+    Ok { output: Option<String> },
+    Error { message: String },
+    ServerStatus(ServerStatus),
+    Services { names: Vec<String> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerStatus {
+    pub pid: u32,
+    pub uptime_secs: u64,
+    pub paused: bool,
+    pub background_services: usize,
+}
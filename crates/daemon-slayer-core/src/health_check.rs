@@ -1,6 +1,61 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
 use crate::BoxedError;
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "kebab-case")]
+pub enum HealthStatus {
+    Healthy,
+    /// Still functioning, but something needs attention.
+    Degraded(String),
+    Unhealthy(String),
+}
+
+impl HealthStatus {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+
+    pub fn is_unhealthy(&self) -> bool {
+        matches!(self, Self::Unhealthy(_))
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Self::Healthy => None,
+            Self::Degraded(reason) | Self::Unhealthy(reason) => Some(reason),
+        }
+    }
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Healthy => f.write_str("Healthy"),
+            Self::Degraded(reason) => write!(f, "Degraded: {reason}"),
+            Self::Unhealthy(reason) => write!(f, "Unhealthy: {reason}"),
+        }
+    }
+}
+
+impl From<Result<(), BoxedError>> for HealthStatus {
+    fn from(result: Result<(), BoxedError>) -> Self {
+        match result {
+            Ok(()) => Self::Healthy,
+            Err(e) => Self::Unhealthy(e.to_string()),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 pub trait HealthCheck {
     async fn invoke(&mut self) -> Result<(), BoxedError>;
+
+    /// Checks that can report a degraded state should override this. Defaults to the result of
+    /// [`HealthCheck::invoke`].
+    async fn status(&mut self) -> HealthStatus {
+        self.invoke().await.into()
+    }
 }
@@ -0,0 +1,54 @@
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+
+use crate::Label;
+use crate::runtime_dir::runtime_dir;
+
+/// Directory containing the lock and PID files. This is the [`runtime_dir`], so system-level
+/// services and instances started by a regular user are locked separately.
+pub fn instance_dir(label: &Label) -> PathBuf {
+    runtime_dir(label)
+}
+
+#[derive(Clone, Debug)]
+pub struct InstancePaths {
+    pub lock_file: PathBuf,
+    pub pid_file: PathBuf,
+}
+
+impl InstancePaths {
+    pub fn new(dir: &Path, label: &Label) -> Self {
+        let name = label.qualified_name();
+        Self {
+            lock_file: dir.join(format!("{name}.lock")),
+            pid_file: dir.join(format!("{name}.pid")),
+        }
+    }
+
+    pub fn from_label(label: &Label) -> Self {
+        Self::new(&instance_dir(label), label)
+    }
+
+    /// Whether a running instance currently holds the lock.
+    pub fn is_locked(&self) -> bool {
+        let Ok(file) = File::open(&self.lock_file) else {
+            return false;
+        };
+        matches!(file.try_lock_shared(), Err(TryLockError::WouldBlock))
+    }
+
+    pub fn read_pid(&self) -> Option<u32> {
+        fs::read_to_string(&self.pid_file).ok()?.trim().parse().ok()
+    }
+}
+
+/// Returns the PID of the running instance, ignoring any PID files left behind by a process that
+/// no longer holds the lock.
+pub fn read_pid(label: &Label) -> Option<u32> {
+    let paths = InstancePaths::from_label(label);
+    if paths.is_locked() {
+        paths.read_pid()
+    } else {
+        None
+    }
+}
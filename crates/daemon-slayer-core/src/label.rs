@@ -24,6 +24,11 @@ impl Label {
             self.qualifier, self.organization, self.application
         )
     }
+
+    /// Environment variable that's set for services installed at the system level.
+    pub fn admin_var(&self) -> String {
+        format!("{}_ADMIN", self.application.to_ascii_uppercase())
+    }
 }
 
 #[derive(Error, Debug)]
@@ -2,13 +2,18 @@
 pub mod cli;
 #[cfg(feature = "config")]
 pub mod config;
+pub mod control;
 #[cfg(feature = "health-check")]
 pub mod health_check;
+#[cfg(feature = "instance")]
+pub mod instance;
 mod label;
 #[cfg(feature = "notify")]
 pub mod notify;
 #[cfg(feature = "process")]
 pub mod process;
+#[cfg(any(feature = "control", feature = "instance"))]
+pub mod runtime_dir;
 #[cfg(feature = "server")]
 pub mod server;
 #[cfg(feature = "signal")]
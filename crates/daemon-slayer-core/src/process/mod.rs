@@ -16,5 +16,5 @@ pub mod platform {
 use crate::Label;
 
 pub fn get_admin_var(label: &Label) -> String {
-    format!("{}_ADMIN", label.application.to_ascii_uppercase())
+    label.admin_var()
 }
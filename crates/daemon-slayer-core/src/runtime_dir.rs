@@ -0,0 +1,92 @@
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+use crate::Label;
+
+/// Whether the current process is a system-level service, either because it was installed as one
+/// or because it's running as root.
+pub fn is_system_level(label: &Label) -> bool {
+    if let Ok("1" | "true") = env::var(label.admin_var())
+        .map(|v| v.to_lowercase())
+        .as_deref()
+    {
+        return true;
+    }
+    #[cfg(unix)]
+    // SAFETY: geteuid has no preconditions and can't fail
+    return unsafe { libc::geteuid() } == 0;
+    #[cfg(not(unix))]
+    return false;
+}
+
+/// Directory for sockets and other files that only live as long as the service does.
+///
+/// System-level services use `/run/<app>` on Linux, `/var/run/<app>` on other Unix platforms, and
+/// `%ProgramData%\<app>` on Windows. Everything else uses `$XDG_RUNTIME_DIR/<app>` or
+/// `%LOCALAPPDATA%\<app>`, falling back to a per-user directory under the temp dir if those aren't
+/// set.
+pub fn runtime_dir(label: &Label) -> PathBuf {
+    let app = &label.application;
+    if is_system_level(label) {
+        #[cfg(target_os = "linux")]
+        return PathBuf::from("/run").join(app);
+        #[cfg(all(unix, not(target_os = "linux")))]
+        return PathBuf::from("/var/run").join(app);
+        #[cfg(windows)]
+        return env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"))
+            .join(app);
+    }
+    #[cfg(unix)]
+    let user_dir = env::var_os("XDG_RUNTIME_DIR");
+    #[cfg(windows)]
+    let user_dir = env::var_os("LOCALAPPDATA");
+    match user_dir.filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join(app),
+        #[cfg(unix)]
+        // SAFETY: geteuid has no preconditions and can't fail
+        None => env::temp_dir().join(format!("{app}-{}", unsafe { libc::geteuid() })),
+        #[cfg(windows)]
+        None => env::temp_dir().join(app),
+    }
+}
+
+/// Creates the [`runtime_dir`] if it doesn't exist yet. On Unix, the directory is only accessible
+/// by its owner and an existing directory that belongs to a different user is rejected.
+pub fn create_runtime_dir(label: &Label) -> io::Result<PathBuf> {
+    let dir = runtime_dir(label);
+    #[cfg(unix)]
+    {
+        use std::fs::{self, DirBuilder, Permissions};
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+        match DirBuilder::new().recursive(true).mode(0o700).create(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        let metadata = fs::symlink_metadata(&dir)?;
+        // SAFETY: geteuid has no preconditions and can't fail
+        if !metadata.is_dir() || metadata.uid() != unsafe { libc::geteuid() } {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is not a directory owned by the current user",
+                    dir.display()
+                ),
+            ));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
+        }
+    }
+    #[cfg(windows)]
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+#[path = "./runtime_dir_test.rs"]
+mod runtime_dir_test;
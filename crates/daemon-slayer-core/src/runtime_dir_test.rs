@@ -0,0 +1,22 @@
+use super::{create_runtime_dir, runtime_dir};
+use crate::Label;
+
+#[cfg(unix)]
+#[test]
+fn test_create_runtime_dir() {
+    use std::fs::{self, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let label: Label = "com.test.daemon_slayer_runtime_dir_test".parse().unwrap();
+    let dir = create_runtime_dir(&label).unwrap();
+    assert_eq!(runtime_dir(&label), dir);
+    assert!(dir.ends_with("daemon_slayer_runtime_dir_test"));
+
+    // Permissions are restricted again if something else loosened them
+    fs::set_permissions(&dir, Permissions::from_mode(0o755)).unwrap();
+    create_runtime_dir(&label).unwrap();
+    assert_eq!(
+        0o700,
+        fs::metadata(&dir).unwrap().permissions().mode() & 0o777
+    );
+}
@@ -1,4 +1,5 @@
 mod event_store;
+pub mod tracked;
 
 pub use event_store::*;
 pub use futures::Stream;
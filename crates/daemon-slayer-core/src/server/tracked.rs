@@ -0,0 +1,191 @@
+//! Wraps the [`background_service`](::background_service) crate so every service spawned from a
+//! [`Manager`] is recorded. This lets the server report which services are still running, for
+//! example when they don't stop before the shutdown deadline. The crate itself is still
+//! re-exported unchanged from [`crate::server::background_service`].
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::Location;
+use std::sync::{Arc, Mutex};
+
+pub use ::background_service::{Settings, error};
+use tokio_util::sync::CancellationToken;
+
+use crate::BoxedError;
+
+pub trait BackgroundService: Send + 'static {
+    fn name(&self) -> &str;
+
+    fn run(self, context: ServiceContext) -> impl Future<Output = Result<(), BoxedError>> + Send;
+}
+
+pub struct Manager {
+    inner: ::background_service::Manager,
+    services: ServiceRegistry,
+}
+
+impl Manager {
+    pub fn new(cancellation_token: CancellationToken, settings: Settings) -> Self {
+        Self {
+            inner: ::background_service::Manager::new(cancellation_token, settings),
+            services: ServiceRegistry::default(),
+        }
+    }
+
+    pub fn get_context(&self) -> ServiceContext {
+        ServiceContext {
+            inner: self.inner.get_context(),
+            services: self.services.clone(),
+        }
+    }
+
+    pub fn services(&self) -> ServiceRegistry {
+        self.services.clone()
+    }
+
+    pub async fn cancel(self) -> Result<(), error::BackgroundServiceErrors> {
+        self.inner.cancel().await
+    }
+}
+
+#[derive(Clone)]
+pub struct ServiceContext {
+    inner: ::background_service::ServiceContext,
+    services: ServiceRegistry,
+}
+
+impl ServiceContext {
+    /// Services are identified by their name and the location they were spawned from, so a
+    /// service spawned again from the same place after it stops is counted as a restart.
+    #[track_caller]
+    pub fn spawn<S: BackgroundService>(&self, service: S) {
+        let guard = self.services.start(service.name(), Location::caller());
+        self.inner.spawn(Registered { service, guard });
+    }
+
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        self.inner.cancellation_token()
+    }
+
+    pub fn cancel_all(&self) {
+        self.inner.cancel_all()
+    }
+
+    pub fn services(&self) -> ServiceRegistry {
+        self.services.clone()
+    }
+}
+
+struct Registered<S> {
+    service: S,
+    guard: RunningGuard,
+}
+
+impl<S: BackgroundService> ::background_service::BackgroundService for Registered<S> {
+    fn name(&self) -> &str {
+        self.service.name()
+    }
+
+    async fn run(self, context: ::background_service::ServiceContext) -> Result<(), BoxedError> {
+        let services = self.guard.services.clone();
+        let _guard = self.guard;
+        self.service
+            .run(ServiceContext {
+                inner: context,
+                services,
+            })
+            .await
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServiceInfo {
+    pub name: String,
+    /// Number of instances that haven't finished yet.
+    pub running: usize,
+    /// Number of times the service was started again after a previous instance finished.
+    pub restarts: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ServiceId {
+    name: String,
+    location: &'static Location<'static>,
+}
+
+#[derive(Default)]
+struct ServiceState {
+    running: usize,
+    stopped: usize,
+    restarts: usize,
+}
+
+/// Every service spawned from a [`Manager`] or any of its contexts.
+#[derive(Clone, Default)]
+pub struct ServiceRegistry {
+    services: Arc<Mutex<BTreeMap<ServiceId, ServiceState>>>,
+}
+
+impl ServiceRegistry {
+    /// Names of the services that haven't finished yet. A name is repeated for each running
+    /// instance.
+    pub fn running(&self) -> Vec<String> {
+        self.services
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|(id, state)| std::iter::repeat_n(id.name.clone(), state.running))
+            .collect()
+    }
+
+    pub fn info(&self) -> Vec<ServiceInfo> {
+        self.services
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, state)| ServiceInfo {
+                name: id.name.clone(),
+                running: state.running,
+                restarts: state.restarts,
+            })
+            .collect()
+    }
+
+    fn start(&self, name: &str, location: &'static Location<'static>) -> RunningGuard {
+        let id = ServiceId {
+            name: name.to_owned(),
+            location,
+        };
+        let mut services = self.services.lock().unwrap();
+        let state = services.entry(id.clone()).or_default();
+        if state.stopped > 0 {
+            state.stopped -= 1;
+            state.restarts += 1;
+        }
+        state.running += 1;
+        RunningGuard {
+            id,
+            services: self.clone(),
+        }
+    }
+}
+
+// Held until the service finishes, or dropped without running if the manager is cancelled first
+struct RunningGuard {
+    id: ServiceId,
+    services: ServiceRegistry,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        let mut services = self.services.services.lock().unwrap();
+        if let Some(state) = services.get_mut(&self.id) {
+            state.running -= 1;
+            state.stopped += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tracked_test.rs"]
+mod tracked_test;
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use super::{BackgroundService, Manager, ServiceContext, ServiceInfo, Settings};
+use crate::BoxedError;
+
+struct TestService {
+    name: &'static str,
+    finished: Option<oneshot::Receiver<()>>,
+    child: Option<Box<TestService>>,
+}
+
+impl TestService {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            finished: None,
+            child: None,
+        }
+    }
+
+    fn until(mut self, finished: oneshot::Receiver<()>) -> Self {
+        self.finished = Some(finished);
+        self
+    }
+
+    fn with_child(mut self, child: TestService) -> Self {
+        self.child = Some(Box::new(child));
+        self
+    }
+}
+
+impl BackgroundService for TestService {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        if let Some(child) = self.child {
+            context.spawn(*child);
+        }
+        match self.finished {
+            Some(finished) => {
+                tokio::select! {
+                    _ = finished => {}
+                    _ = context.cancellation_token().cancelled() => {}
+                }
+            }
+            None => context.cancellation_token().cancelled().await,
+        }
+        Ok(())
+    }
+}
+
+fn manager() -> Manager {
+    Manager::new(CancellationToken::new(), Settings::default())
+}
+
+async fn wait() {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_running() {
+    let manager = manager();
+    let services = manager.services();
+    manager
+        .get_context()
+        .spawn(TestService::new("parent").with_child(TestService::new("child")));
+    wait().await;
+    assert_eq!(vec!["child", "parent"], services.running());
+
+    manager.cancel().await.unwrap();
+    assert!(services.running().is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restarts() {
+    let manager = manager();
+    let context = manager.get_context();
+    let services = manager.services();
+
+    for _ in 0..3 {
+        let (finished_tx, finished_rx) = oneshot::channel();
+        context.spawn(TestService::new("restarted").until(finished_rx));
+        wait().await;
+        finished_tx.send(()).unwrap();
+        wait().await;
+    }
+    // Same name, but a different service
+    context.spawn(TestService::new("restarted"));
+    wait().await;
+
+    assert_eq!(
+        vec![
+            ServiceInfo {
+                name: "restarted".to_owned(),
+                running: 0,
+                restarts: 2,
+            },
+            ServiceInfo {
+                name: "restarted".to_owned(),
+                running: 1,
+                restarts: 0,
+            }
+        ],
+        services.info()
+    );
+    manager.cancel().await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrent_instances() {
+    let manager = manager();
+    let context = manager.get_context();
+    let services = manager.services();
+
+    let mut senders = Vec::new();
+    for _ in 0..2 {
+        let (finished_tx, finished_rx) = oneshot::channel();
+        senders.push(finished_tx);
+        context.spawn(TestService::new("worker").until(finished_rx));
+    }
+    wait().await;
+    assert_eq!(vec!["worker", "worker"], services.running());
+
+    senders.pop().unwrap().send(()).unwrap();
+    wait().await;
+    assert_eq!(vec!["worker"], services.running());
+    assert_eq!(0, services.info()[0].restarts);
+
+    manager.cancel().await.unwrap();
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::server::BroadcastEventStore;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use notify::RecommendedWatcher;
 use notify_debouncer_mini::{DebouncedEvent, Debouncer, new_debouncer};
 use tap::TapFallible;
@@ -41,10 +41,9 @@ impl<H: HealthCheck + Clone + Send + 'static> CommandProvider for HealthCheckCli
 
     async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
         if self.matched {
-            Ok(match self.health_check.invoke().await {
-                Ok(()) => CommandOutput::handled("Healthy".to_owned()),
-                Err(e) => CommandOutput::handled(format!("Unhealthy: {e:?}")),
-            })
+            Ok(CommandOutput::handled(
+                self.health_check.status().await.to_string(),
+            ))
         } else {
             Ok(CommandOutput::unhandled())
         }
@@ -0,0 +1,213 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+use tokio::sync::Mutex;
+
+type SharedCheck = Arc<Mutex<Box<dyn HealthCheck + Send>>>;
+
+async fn invoke_status(check: &mut (impl HealthCheck + Send)) -> Result<(), BoxedError> {
+    match check.status().await {
+        HealthStatus::Unhealthy(reason) => Err(reason)?,
+        // Degraded services are still functioning so they're considered healthy here
+        HealthStatus::Healthy | HealthStatus::Degraded(_) => Ok(()),
+    }
+}
+
+fn shared(checks: impl IntoIterator<Item = Box<dyn HealthCheck + Send>>) -> Vec<SharedCheck> {
+    checks
+        .into_iter()
+        .map(|check| Arc::new(Mutex::new(check)))
+        .collect()
+}
+
+async fn statuses(checks: &[SharedCheck]) -> Vec<HealthStatus> {
+    futures::future::join_all(
+        checks
+            .iter()
+            .map(|check| async move { check.lock().await.status().await }),
+    )
+    .await
+}
+
+fn reasons<'a>(statuses: impl Iterator<Item = &'a HealthStatus>) -> String {
+    statuses
+        .filter_map(HealthStatus::reason)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Healthy only if every check is healthy. Any unhealthy check makes the result unhealthy.
+pub fn all(checks: impl IntoIterator<Item = Box<dyn HealthCheck + Send>>) -> All {
+    All {
+        checks: shared(checks),
+    }
+}
+
+/// Healthy if every check is healthy, degraded if at least one check is still functioning, and
+/// unhealthy otherwise.
+pub fn any(checks: impl IntoIterator<Item = Box<dyn HealthCheck + Send>>) -> Any {
+    Any {
+        checks: shared(checks),
+    }
+}
+
+#[derive(Clone)]
+pub struct All {
+    checks: Vec<SharedCheck>,
+}
+
+#[async_trait]
+impl HealthCheck for All {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        invoke_status(self).await
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        let statuses = statuses(&self.checks).await;
+        if statuses.iter().any(HealthStatus::is_unhealthy) {
+            HealthStatus::Unhealthy(reasons(statuses.iter().filter(|s| s.is_unhealthy())))
+        } else if statuses.iter().all(HealthStatus::is_healthy) {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded(reasons(statuses.iter()))
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Any {
+    checks: Vec<SharedCheck>,
+}
+
+#[async_trait]
+impl HealthCheck for Any {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        invoke_status(self).await
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        let statuses = statuses(&self.checks).await;
+        if statuses.iter().all(HealthStatus::is_healthy) {
+            HealthStatus::Healthy
+        } else if statuses.iter().all(HealthStatus::is_unhealthy) {
+            HealthStatus::Unhealthy(reasons(statuses.iter()))
+        } else {
+            HealthStatus::Degraded(reasons(statuses.iter()))
+        }
+    }
+}
+
+pub trait HealthCheckExt: HealthCheck + Send + Sized {
+    /// Fails the check if it doesn't complete within `timeout`.
+    fn with_timeout(self, timeout: Duration) -> Timeout<Self> {
+        Timeout {
+            inner: self,
+            timeout,
+        }
+    }
+
+    /// Runs the check up to `retries` more times before reporting a failure.
+    fn with_retries(self, retries: usize) -> Retries<Self> {
+        Retries {
+            inner: self,
+            retries,
+        }
+    }
+
+    /// Reports failures as degraded until the check fails `threshold` times in a row.
+    fn with_threshold(self, threshold: usize) -> Threshold<Self> {
+        Threshold {
+            inner: self,
+            threshold,
+            failures: 0,
+        }
+    }
+}
+
+impl<T: HealthCheck + Send> HealthCheckExt for T {}
+
+#[derive(Clone)]
+pub struct Timeout<H> {
+    inner: H,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl<H: HealthCheck + Send> HealthCheck for Timeout<H> {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        invoke_status(self).await
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        tokio::time::timeout(self.timeout, self.inner.status())
+            .await
+            .unwrap_or_else(|_| {
+                HealthStatus::Unhealthy(format!("Timed out after {:?}", self.timeout))
+            })
+    }
+}
+
+#[derive(Clone)]
+pub struct Retries<H> {
+    inner: H,
+    retries: usize,
+}
+
+#[async_trait]
+impl<H: HealthCheck + Send> HealthCheck for Retries<H> {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        invoke_status(self).await
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        let mut status = self.inner.status().await;
+        for _ in 0..self.retries {
+            if !status.is_unhealthy() {
+                break;
+            }
+            status = self.inner.status().await;
+        }
+        status
+    }
+}
+
+#[derive(Clone)]
+pub struct Threshold<H> {
+    inner: H,
+    threshold: usize,
+    failures: usize,
+}
+
+#[async_trait]
+impl<H: HealthCheck + Send> HealthCheck for Threshold<H> {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        invoke_status(self).await
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        match self.inner.status().await {
+            HealthStatus::Unhealthy(reason) => {
+                self.failures += 1;
+                if self.failures >= self.threshold {
+                    HealthStatus::Unhealthy(reason)
+                } else {
+                    HealthStatus::Degraded(format!(
+                        "{reason} ({}/{} failures)",
+                        self.failures, self.threshold
+                    ))
+                }
+            }
+            status => {
+                self.failures = 0;
+                status
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./combinators_test.rs"]
+mod combinators_test;
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+
+use crate::{HealthCheckExt, all, any};
+
+fn healthy() -> HealthStatus {
+    HealthStatus::Healthy
+}
+
+fn degraded(reason: &str) -> HealthStatus {
+    HealthStatus::Degraded(reason.to_owned())
+}
+
+fn unhealthy(reason: &str) -> HealthStatus {
+    HealthStatus::Unhealthy(reason.to_owned())
+}
+
+// Returns each status in order, repeating the last one once the rest are used up
+struct Sequence {
+    statuses: VecDeque<HealthStatus>,
+    calls: usize,
+}
+
+impl Sequence {
+    fn new(statuses: impl IntoIterator<Item = HealthStatus>) -> Self {
+        Self {
+            statuses: statuses.into_iter().collect(),
+            calls: 0,
+        }
+    }
+
+    fn boxed(statuses: impl IntoIterator<Item = HealthStatus>) -> Box<dyn HealthCheck + Send> {
+        Box::new(Self::new(statuses))
+    }
+}
+
+#[async_trait]
+impl HealthCheck for Sequence {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        unreachable!()
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        self.calls += 1;
+        if self.statuses.len() > 1 {
+            self.statuses.pop_front().unwrap()
+        } else {
+            self.statuses[0].clone()
+        }
+    }
+}
+
+struct Slow;
+
+#[async_trait]
+impl HealthCheck for Slow {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_all() {
+    let mut check = all([Sequence::boxed([healthy()]), Sequence::boxed([healthy()])]);
+    assert_eq!(healthy(), check.status().await);
+
+    let mut check = all([
+        Sequence::boxed([healthy()]),
+        Sequence::boxed([degraded("slow")]),
+    ]);
+    assert_eq!(degraded("slow"), check.status().await);
+    check.invoke().await.unwrap();
+
+    let mut check = all([
+        Sequence::boxed([degraded("slow")]),
+        Sequence::boxed([unhealthy("down")]),
+    ]);
+    assert_eq!(unhealthy("down"), check.status().await);
+    assert_eq!("down", check.invoke().await.unwrap_err().to_string());
+}
+
+#[tokio::test]
+async fn test_any() {
+    let mut check = any([Sequence::boxed([healthy()]), Sequence::boxed([healthy()])]);
+    assert_eq!(healthy(), check.status().await);
+
+    let mut check = any([
+        Sequence::boxed([healthy()]),
+        Sequence::boxed([unhealthy("down")]),
+    ]);
+    assert_eq!(degraded("down"), check.status().await);
+    check.invoke().await.unwrap();
+
+    let mut check = any([
+        Sequence::boxed([unhealthy("a")]),
+        Sequence::boxed([unhealthy("b")]),
+    ]);
+    assert_eq!(unhealthy("a, b"), check.status().await);
+    check.invoke().await.unwrap_err();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_timeout() {
+    let mut check = Slow.with_timeout(Duration::from_secs(1));
+    assert_eq!(unhealthy("Timed out after 1s"), check.status().await);
+
+    let mut check = Sequence::new([healthy()]).with_timeout(Duration::from_secs(1));
+    assert_eq!(healthy(), check.status().await);
+}
+
+#[tokio::test]
+async fn test_retries() {
+    let mut check = Sequence::new([unhealthy("a"), unhealthy("b"), healthy()]).with_retries(2);
+    assert_eq!(healthy(), check.status().await);
+    assert_eq!(3, check.inner.calls);
+
+    let mut check = Sequence::new([unhealthy("a"), unhealthy("b"), healthy()]).with_retries(1);
+    assert_eq!(unhealthy("b"), check.status().await);
+
+    // Degraded checks are still functioning, so they aren't retried
+    let mut check = Sequence::new([degraded("slow"), healthy()]).with_retries(3);
+    assert_eq!(degraded("slow"), check.status().await);
+    assert_eq!(1, check.inner.calls);
+}
+
+#[tokio::test]
+async fn test_threshold() {
+    let mut check = Sequence::new([
+        unhealthy("down"),
+        unhealthy("down"),
+        healthy(),
+        unhealthy("down"),
+        unhealthy("down"),
+        unhealthy("down"),
+    ])
+    .with_threshold(2);
+    assert_eq!(degraded("down (1/2 failures)"), check.status().await);
+    assert_eq!(unhealthy("down"), check.status().await);
+    // A success resets the count
+    assert_eq!(healthy(), check.status().await);
+    assert_eq!(degraded("down (1/2 failures)"), check.status().await);
+    assert_eq!(unhealthy("down"), check.status().await);
+    assert_eq!(unhealthy("down"), check.status().await);
+}
@@ -0,0 +1,56 @@
+use std::ffi::OsString;
+use std::process::Stdio;
+
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::HealthCheck;
+use tokio::process::Command;
+
+#[derive(Clone)]
+pub struct CommandHealthCheck {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl CommandHealthCheck {
+    /// Passes if `program` exits successfully.
+    pub fn new(program: impl Into<OsString>) -> Self {
+        Self {
+            program: program.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for CommandHealthCheck {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::null())
+            // Make sure the process doesn't outlive a check that gets cancelled or times out
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            Err(format!("Command exited with {}", output.status))?
+        } else {
+            Err(format!("Command exited with {}: {stderr}", output.status))?
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./command_test.rs"]
+mod command_test;
@@ -0,0 +1,35 @@
+use daemon_slayer_core::health_check::HealthCheck;
+
+use crate::CommandHealthCheck;
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_success() {
+    CommandHealthCheck::new("true").invoke().await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_failure() {
+    let error = CommandHealthCheck::new("false").invoke().await.unwrap_err();
+    assert!(error.to_string().starts_with("Command exited with"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stderr() {
+    let error = CommandHealthCheck::new("sh")
+        .with_args(["-c", "echo 'not ready' >&2; exit 3"])
+        .invoke()
+        .await
+        .unwrap_err();
+    assert!(error.to_string().ends_with(": not ready"));
+}
+
+#[tokio::test]
+async fn test_missing_program() {
+    CommandHealthCheck::new("daemon-slayer-missing-program")
+        .invoke()
+        .await
+        .unwrap_err();
+}
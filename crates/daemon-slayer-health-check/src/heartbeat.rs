@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::HealthCheck;
+
+#[derive(Clone)]
+pub struct HeartbeatFileHealthCheck {
+    path: PathBuf,
+    max_age: Duration,
+}
+
+impl HeartbeatFileHealthCheck {
+    /// Passes if the file at `path` was modified within the last `max_age`.
+    pub fn new(path: impl Into<PathBuf>, max_age: Duration) -> Self {
+        Self {
+            path: path.into(),
+            max_age,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for HeartbeatFileHealthCheck {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        let modified = tokio::fs::metadata(&self.path)
+            .await
+            .map_err(|e| format!("Error reading {}: {e}", self.path.display()))?
+            .modified()?;
+        // A modification time in the future means the file was just touched
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        if age > self.max_age {
+            return Err(format!(
+                "{} was last modified {}s ago",
+                self.path.display(),
+                age.as_secs()
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./heartbeat_test.rs"]
+mod heartbeat_test;
@@ -0,0 +1,41 @@
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+use daemon_slayer_core::health_check::HealthCheck;
+
+use crate::HeartbeatFileHealthCheck;
+
+#[tokio::test]
+async fn test_recent() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("heartbeat");
+    File::create(&path).unwrap();
+    HeartbeatFileHealthCheck::new(path, Duration::from_secs(60))
+        .invoke()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("heartbeat");
+    File::create(&path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(120))
+        .unwrap();
+    let error = HeartbeatFileHealthCheck::new(&path, Duration::from_secs(60))
+        .invoke()
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("was last modified 120s ago"));
+}
+
+#[tokio::test]
+async fn test_missing() {
+    let dir = tempfile::tempdir().unwrap();
+    HeartbeatFileHealthCheck::new(dir.path().join("missing"), Duration::from_secs(60))
+        .invoke()
+        .await
+        .unwrap_err();
+}
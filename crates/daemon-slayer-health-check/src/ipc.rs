@@ -3,36 +3,98 @@ use std::path::PathBuf;
 
 use async_trait::async_trait;
 use daemon_slayer_core::BoxedError;
-use daemon_slayer_core::health_check::HealthCheck;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
 use tipsy::{Endpoint, IntoIpcPath, ServerId};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+use crate::{CheckKind, HealthReport};
+
+pub fn health_socket_path(app_name: impl Into<String>) -> io::Result<PathBuf> {
+    ServerId::new(format!("{}_health", app_name.into()))
+        .parent_folder("/tmp")
+        .into_ipc_path()
+}
+
+// The request is a single byte selecting which checks to run
+pub(crate) fn kind_to_byte(kind: Option<CheckKind>) -> u8 {
+    match kind {
+        None => 0,
+        Some(CheckKind::Readiness) => 1,
+        Some(CheckKind::Liveness) => 2,
+    }
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn byte_to_kind(byte: u8) -> Option<CheckKind> {
+    match byte {
+        1 => Some(CheckKind::Readiness),
+        2 => Some(CheckKind::Liveness),
+        _ => None,
+    }
+}
+
 #[derive(Clone)]
 pub struct IpcHealthCheck {
     sock_path: PathBuf,
-    read_buf: [u8; 256],
+    kind: Option<CheckKind>,
 }
 
 impl IpcHealthCheck {
     pub fn new(app_name: impl Into<String>) -> io::Result<Self> {
-        let sock_path = ServerId::new(format!("{}_health", app_name.into()))
-            .parent_folder("/tmp")
-            .into_ipc_path()?;
-
         Ok(Self {
-            sock_path,
-            read_buf: [0u8; 256],
+            sock_path: health_socket_path(app_name)?,
+            kind: None,
         })
     }
+
+    /// Only runs checks of the given kind when connected to a `HealthServer`. All checks are run
+    /// by default.
+    pub fn with_check_kind(mut self, kind: CheckKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+}
+
+impl IpcHealthCheck {
+    async fn request_status(&self) -> Result<HealthStatus, BoxedError> {
+        let mut client = Endpoint::connect(self.sock_path.clone()).await?;
+        client.write_u8(kind_to_byte(self.kind)).await?;
+
+        let mut response = Vec::new();
+        let mut read_buf = [0u8; 256];
+        loop {
+            let read = client.read(&mut read_buf).await?;
+            if read == 0 {
+                break;
+            }
+            response.extend_from_slice(&read_buf[..read]);
+            // Responders that don't send a report are considered healthy as long as they reply.
+            // They may not close the connection, so don't wait for the rest of the response.
+            if response[0] != b'{' {
+                return Ok(HealthStatus::Healthy);
+            }
+        }
+        if response.is_empty() {
+            return Ok(HealthStatus::Healthy);
+        }
+        let report: HealthReport = serde_json::from_slice(&response)?;
+        Ok(report.status)
+    }
 }
 
 #[async_trait]
 impl HealthCheck for IpcHealthCheck {
     async fn invoke(&mut self) -> Result<(), BoxedError> {
-        let mut client = Endpoint::connect(self.sock_path.clone()).await?;
-        let _ = client.write_u8(0).await?;
+        match self.request_status().await? {
+            HealthStatus::Unhealthy(reason) => Err(format!("Unhealthy components: {reason}"))?,
+            HealthStatus::Healthy | HealthStatus::Degraded(_) => Ok(()),
+        }
+    }
 
-        let _ = client.read(&mut self.read_buf).await?;
-        Ok(())
+    async fn status(&mut self) -> HealthStatus {
+        match self.request_status().await {
+            Ok(status) => status,
+            Err(e) => HealthStatus::Unhealthy(e.to_string()),
+        }
     }
 }
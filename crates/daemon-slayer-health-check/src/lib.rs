@@ -1,16 +1,34 @@
 #[cfg(feature = "cli")]
 pub mod cli;
+mod combinators;
+#[cfg(feature = "command-health-check")]
+mod command;
 #[cfg(feature = "grpc-health-check")]
 mod grpc;
+#[cfg(feature = "heartbeat-health-check")]
+mod heartbeat;
 #[cfg(feature = "http-health-check")]
 mod http;
 #[cfg(feature = "ipc-health-check")]
 mod ipc;
+mod report;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tcp-health-check")]
+mod tcp;
 
-pub use daemon_slayer_core::health_check::HealthCheck;
+pub use combinators::*;
+#[cfg(feature = "command-health-check")]
+pub use command::*;
+pub use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
 #[cfg(feature = "grpc-health-check")]
 pub use grpc::*;
+#[cfg(feature = "heartbeat-health-check")]
+pub use heartbeat::*;
 #[cfg(feature = "http-health-check")]
 pub use http::*;
 #[cfg(feature = "ipc-health-check")]
 pub use ipc::*;
+pub use report::*;
+#[cfg(feature = "tcp-health-check")]
+pub use tcp::*;
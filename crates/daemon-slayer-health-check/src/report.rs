@@ -0,0 +1,56 @@
+use daemon_slayer_core::health_check::HealthStatus;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckKind {
+    /// Whether the component is able to serve requests.
+    Readiness,
+    /// Whether the component is functioning. A failed liveness check usually means the process
+    /// should be restarted.
+    Liveness,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub kind: CheckKind,
+    #[serde(flatten)]
+    pub status: HealthStatus,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    #[serde(flatten)]
+    pub status: HealthStatus,
+    pub components: Vec<ComponentHealth>,
+}
+
+impl HealthReport {
+    pub fn new(components: Vec<ComponentHealth>) -> Self {
+        let status = if components.iter().all(|c| c.status.is_healthy()) {
+            HealthStatus::Healthy
+        } else {
+            let reasons: Vec<_> = components
+                .iter()
+                .filter(|c| !c.status.is_healthy())
+                .map(|c| format!("{}: {}", c.name, c.status.reason().unwrap_or_default()))
+                .collect();
+            let reasons = reasons.join(", ");
+            if components.iter().any(|c| c.status.is_unhealthy()) {
+                HealthStatus::Unhealthy(reasons)
+            } else {
+                HealthStatus::Degraded(reasons)
+            }
+        };
+        Self { status, components }
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &ComponentHealth> {
+        self.components.iter().filter(|c| !c.status.is_healthy())
+    }
+}
+
+#[cfg(test)]
+#[path = "./report_test.rs"]
+mod report_test;
@@ -0,0 +1,62 @@
+use daemon_slayer_core::health_check::HealthStatus;
+
+use crate::{CheckKind, ComponentHealth, HealthReport};
+
+fn component(name: &str, status: HealthStatus) -> ComponentHealth {
+    ComponentHealth {
+        name: name.to_owned(),
+        kind: CheckKind::Readiness,
+        status,
+    }
+}
+
+#[test]
+fn test_healthy() {
+    let report = HealthReport::new(vec![component("a", HealthStatus::Healthy)]);
+    assert_eq!(HealthStatus::Healthy, report.status);
+    assert_eq!(0, report.failures().count());
+}
+
+#[test]
+fn test_degraded() {
+    let report = HealthReport::new(vec![
+        component("a", HealthStatus::Healthy),
+        component("b", HealthStatus::Degraded("slow".to_owned())),
+    ]);
+    assert_eq!(HealthStatus::Degraded("b: slow".to_owned()), report.status);
+}
+
+#[test]
+fn test_unhealthy() {
+    let report = HealthReport::new(vec![
+        component("a", HealthStatus::Degraded("slow".to_owned())),
+        component("b", HealthStatus::Unhealthy("down".to_owned())),
+    ]);
+    assert_eq!(
+        HealthStatus::Unhealthy("a: slow, b: down".to_owned()),
+        report.status
+    );
+}
+
+#[test]
+fn test_serialize() {
+    let report = HealthReport::new(vec![component(
+        "a",
+        HealthStatus::Unhealthy("down".to_owned()),
+    )]);
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "status": "unhealthy",
+            "reason": "a: down",
+            "components": [{
+                "name": "a",
+                "kind": "readiness",
+                "status": "unhealthy",
+                "reason": "down",
+            }],
+        }),
+        json
+    );
+    assert_eq!(report, serde_json::from_value(json).unwrap());
+}
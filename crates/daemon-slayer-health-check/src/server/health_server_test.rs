@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use daemon_slayer_core::health_check::{HealthCheck, HealthStatus};
+use daemon_slayer_core::server::tracked::{Manager, Settings};
+use daemon_slayer_core::{BoxedError, CancellationToken};
+
+use super::{HealthRegistry, HealthServer};
+use crate::{CheckKind, IpcHealthCheck};
+
+struct Fixed(HealthStatus);
+
+#[async_trait]
+impl HealthCheck for Fixed {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        unreachable!()
+    }
+
+    async fn status(&mut self) -> HealthStatus {
+        self.0.clone()
+    }
+}
+
+fn registry() -> HealthRegistry {
+    let registry = HealthRegistry::default();
+    registry.register_readiness("db", Fixed(HealthStatus::Degraded("slow".to_owned())));
+    registry.register_liveness("worker", Fixed(HealthStatus::Unhealthy("stuck".to_owned())));
+    registry
+}
+
+#[tokio::test]
+async fn test_report() {
+    let report = registry().report(None).await;
+    assert_eq!(
+        HealthStatus::Unhealthy("db: slow, worker: stuck".to_owned()),
+        report.status
+    );
+    assert_eq!(
+        vec!["db", "worker"],
+        report
+            .failures()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_report_kind() {
+    let readiness = registry().report(Some(CheckKind::Readiness)).await;
+    assert_eq!(
+        HealthStatus::Degraded("db: slow".to_owned()),
+        readiness.status
+    );
+    assert_eq!(1, readiness.components.len());
+
+    let liveness = registry().report(Some(CheckKind::Liveness)).await;
+    assert_eq!(
+        HealthStatus::Unhealthy("worker: stuck".to_owned()),
+        liveness.status
+    );
+}
+
+#[tokio::test]
+async fn test_empty_report() {
+    let report = HealthRegistry::default().report(None).await;
+    assert_eq!(HealthStatus::Healthy, report.status);
+    assert!(report.components.is_empty());
+}
+
+#[tokio::test]
+async fn test_ipc() {
+    let app_name = "daemon_slayer_health_server_test";
+    let server = HealthServer::new(app_name);
+    let registry = server.get_registry();
+    registry.register_readiness("db", Fixed(HealthStatus::Healthy));
+    registry.register_liveness("worker", Fixed(HealthStatus::Unhealthy("stuck".to_owned())));
+
+    let manager = Manager::new(CancellationToken::new(), Settings::default());
+    manager.get_context().spawn(server);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let mut readiness = IpcHealthCheck::new(app_name)
+        .unwrap()
+        .with_check_kind(CheckKind::Readiness);
+    assert_eq!(HealthStatus::Healthy, readiness.status().await);
+    readiness.invoke().await.unwrap();
+
+    let mut all = IpcHealthCheck::new(app_name).unwrap();
+    assert_eq!(
+        HealthStatus::Unhealthy("worker: stuck".to_owned()),
+        all.status().await
+    );
+    assert!(all.invoke().await.is_err());
+
+    manager.cancel().await.unwrap();
+}
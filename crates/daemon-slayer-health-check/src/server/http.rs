@@ -0,0 +1,65 @@
+use std::net::SocketAddr;
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+
+use super::HealthRegistry;
+use crate::CheckKind;
+
+pub(super) struct HttpHealthServer {
+    addr: SocketAddr,
+    registry: HealthRegistry,
+}
+
+impl HttpHealthServer {
+    pub(super) fn new(addr: SocketAddr, registry: HealthRegistry) -> Self {
+        Self { addr, registry }
+    }
+}
+
+impl BackgroundService for HttpHealthServer {
+    fn name(&self) -> &str {
+        "http_health_server"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        let app = Router::new()
+            .route(
+                "/health",
+                get(|registry: State<HealthRegistry>| report(registry, None)),
+            )
+            .route(
+                "/health/ready",
+                get(|registry: State<HealthRegistry>| report(registry, Some(CheckKind::Readiness))),
+            )
+            .route(
+                "/health/live",
+                get(|registry: State<HealthRegistry>| report(registry, Some(CheckKind::Liveness))),
+            )
+            .with_state(self.registry);
+        let listener = tokio::net::TcpListener::bind(self.addr).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(context.cancellation_token().clone().cancelled_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+async fn report(
+    State(registry): State<HealthRegistry>,
+    kind: Option<CheckKind>,
+) -> impl IntoResponse {
+    let report = registry.report(kind).await;
+    // Degraded components can still serve requests
+    let status = if report.status.is_unhealthy() {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (status, axum::Json(report))
+}
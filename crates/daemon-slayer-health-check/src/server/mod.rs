@@ -0,0 +1,174 @@
+#[cfg(feature = "http-health-server")]
+mod http;
+
+#[cfg(feature = "http-health-server")]
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::HealthCheck;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+use futures::StreamExt;
+use tap::TapFallible;
+use tipsy::{Endpoint, OnConflict};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::error;
+
+use crate::ipc::{byte_to_kind, health_socket_path};
+use crate::{CheckKind, ComponentHealth, HealthReport};
+
+type SharedCheck = Arc<tokio::sync::Mutex<Box<dyn HealthCheck + Send>>>;
+
+#[derive(Clone)]
+struct RegisteredCheck {
+    name: String,
+    kind: CheckKind,
+    check: SharedCheck,
+}
+
+/// Collection of named checks served by a [`HealthServer`]. It can be cloned and handed to each
+/// component that needs to register its own checks.
+#[derive(Clone, Default)]
+pub struct HealthRegistry {
+    checks: Arc<Mutex<Vec<RegisteredCheck>>>,
+}
+
+impl HealthRegistry {
+    pub fn register_readiness(
+        &self,
+        name: impl Into<String>,
+        check: impl HealthCheck + Send + 'static,
+    ) {
+        self.register(name.into(), CheckKind::Readiness, check);
+    }
+
+    pub fn register_liveness(
+        &self,
+        name: impl Into<String>,
+        check: impl HealthCheck + Send + 'static,
+    ) {
+        self.register(name.into(), CheckKind::Liveness, check);
+    }
+
+    fn register(&self, name: String, kind: CheckKind, check: impl HealthCheck + Send + 'static) {
+        self.checks.lock().unwrap().push(RegisteredCheck {
+            name,
+            kind,
+            check: Arc::new(tokio::sync::Mutex::new(Box::new(check))),
+        });
+    }
+
+    /// Runs all registered checks of the given kind, or every check if `kind` is `None`.
+    pub async fn report(&self, kind: Option<CheckKind>) -> HealthReport {
+        let checks: Vec<_> = self
+            .checks
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| kind.is_none_or(|kind| c.kind == kind))
+            .cloned()
+            .collect();
+        let components = futures::future::join_all(checks.into_iter().map(|c| async move {
+            let status = c.check.lock().await.status().await;
+            ComponentHealth {
+                name: c.name,
+                kind: c.kind,
+                status,
+            }
+        }))
+        .await;
+        HealthReport::new(components)
+    }
+}
+
+/// Serves the results of the registered checks as JSON over the socket used by
+/// [`IpcHealthCheck`](crate::IpcHealthCheck) and, optionally, over HTTP.
+pub struct HealthServer {
+    app_name: String,
+    registry: HealthRegistry,
+    #[cfg(feature = "http-health-server")]
+    http_addr: Option<SocketAddr>,
+}
+
+impl HealthServer {
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+            registry: HealthRegistry::default(),
+            #[cfg(feature = "http-health-server")]
+            http_addr: None,
+        }
+    }
+
+    /// Also serves the checks over HTTP at `/health`, `/health/ready`, and `/health/live`. Unhealthy
+    /// checks return a 503 status.
+    #[cfg(feature = "http-health-server")]
+    pub fn with_http_addr(mut self, http_addr: impl Into<SocketAddr>) -> Self {
+        self.http_addr = Some(http_addr.into());
+        self
+    }
+
+    pub fn get_registry(&self) -> HealthRegistry {
+        self.registry.clone()
+    }
+}
+
+impl BackgroundService for HealthServer {
+    fn name(&self) -> &str {
+        "health_server"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        #[cfg(feature = "http-health-server")]
+        if let Some(http_addr) = self.http_addr {
+            context.spawn(http::HttpHealthServer::new(
+                http_addr,
+                self.registry.clone(),
+            ));
+        }
+
+        let incoming =
+            Endpoint::new(health_socket_path(self.app_name)?, OnConflict::Overwrite)?.incoming()?;
+        futures::pin_mut!(incoming);
+        let cancellation_token = context.cancellation_token().clone();
+
+        loop {
+            let conn = tokio::select! {
+                _ = cancellation_token.cancelled() => return Ok(()),
+                conn = incoming.next() => conn,
+            };
+            let Some(conn) = conn else {
+                return Ok(());
+            };
+            let Ok(mut conn) = conn.tap_err(|e| error!("Error accepting health connection: {e:?}"))
+            else {
+                continue;
+            };
+            let registry = self.registry.clone();
+            tokio::spawn(async move {
+                let Ok(request) = conn
+                    .read_u8()
+                    .await
+                    .tap_err(|e| error!("Error reading health request: {e:?}"))
+                else {
+                    return;
+                };
+                let report = registry.report(byte_to_kind(request)).await;
+                let Ok(payload) = serde_json::to_vec(&report)
+                    .tap_err(|e| error!("Error serializing health report: {e:?}"))
+                else {
+                    return;
+                };
+                conn.write_all(&payload)
+                    .await
+                    .tap_err(|e| error!("Error writing health response: {e:?}"))
+                    .ok();
+                conn.shutdown().await.ok();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./health_server_test.rs"]
+mod health_server_test;
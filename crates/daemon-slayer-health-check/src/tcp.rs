@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::health_check::HealthCheck;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[derive(Clone)]
+pub struct TcpHealthCheck {
+    addr: String,
+    send: Option<Vec<u8>>,
+    expect: Option<Vec<u8>>,
+}
+
+impl TcpHealthCheck {
+    /// Passes if a connection can be established to `addr`.
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            send: None,
+            expect: None,
+        }
+    }
+
+    /// Writes `payload` to the connection once it's established.
+    pub fn with_send(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.send = Some(payload.into());
+        self
+    }
+
+    /// Requires the start of the response to match `expected`.
+    pub fn with_expect(mut self, expected: impl Into<Vec<u8>>) -> Self {
+        self.expect = Some(expected.into());
+        self
+    }
+}
+
+#[async_trait]
+impl HealthCheck for TcpHealthCheck {
+    async fn invoke(&mut self) -> Result<(), BoxedError> {
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        if let Some(send) = &self.send {
+            stream.write_all(send).await?;
+        }
+        if let Some(expect) = &self.expect {
+            let mut response = vec![0; expect.len()];
+            stream.read_exact(&mut response).await?;
+            if &response != expect {
+                return Err(format!(
+                    "Unexpected response: {}",
+                    String::from_utf8_lossy(&response)
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./tcp_test.rs"]
+mod tcp_test;
@@ -0,0 +1,53 @@
+use daemon_slayer_core::health_check::HealthCheck;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::TcpHealthCheck;
+
+// Replies with `response` after reading a four byte request
+async fn serve(response: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0; 4];
+            stream.read_exact(&mut request).await.ok();
+            stream.write_all(response).await.ok();
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_connect() {
+    let addr = serve(b"").await;
+    TcpHealthCheck::new(addr).invoke().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_connection_refused() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    drop(listener);
+    TcpHealthCheck::new(addr).invoke().await.unwrap_err();
+}
+
+#[tokio::test]
+async fn test_expect() {
+    let addr = serve(b"PONG").await;
+    TcpHealthCheck::new(&addr)
+        .with_send("PING")
+        .with_expect("PONG")
+        .invoke()
+        .await
+        .unwrap();
+
+    let error = TcpHealthCheck::new(&addr)
+        .with_send("PING")
+        .with_expect("OK")
+        .invoke()
+        .await
+        .unwrap_err();
+    assert_eq!("Unexpected response: PO", error.to_string());
+}
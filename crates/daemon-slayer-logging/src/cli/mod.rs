@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use daemon_slayer_core::BoxedError;
+#[cfg(feature = "file")]
+use daemon_slayer_core::cli::clap::{FromArgMatches, Subcommand};
 use daemon_slayer_core::cli::{
-    Action, ActionType, ClientAction, CommandMatch, CommandOutput, CommandProvider, clap,
+    Action, ActionType, ClientAction, CommandMatch, CommandOutput, CommandProvider, ServerAction,
+    clap,
 };
 use daemon_slayer_core::config::Accessor;
 use time::formatting::Formattable;
@@ -19,9 +22,27 @@ pub enum LoggerInitializationError {
     AlreadyCreated,
 }
 
+#[cfg(feature = "file")]
+#[derive(Subcommand, Debug, Clone)]
+enum LogsCommands {
+    /// Print the directory containing the log files
+    Path,
+}
+
+#[cfg(feature = "file")]
+#[derive(Subcommand, Debug, Clone)]
+enum CliCommands {
+    Logs {
+        #[command(subcommand)]
+        command: LogsCommands,
+    },
+}
+
 #[derive(Debug)]
 pub struct LoggingCliProvider<T> {
     pub builder: Option<LoggerBuilder<T>>,
+    #[cfg(feature = "file")]
+    log_dir: Option<Result<std::path::PathBuf, LoggerCreationError>>,
 }
 
 impl<T> LoggingCliProvider<T>
@@ -31,6 +52,8 @@ where
     pub fn new(builder: LoggerBuilder<T>) -> Self {
         Self {
             builder: Some(builder),
+            #[cfg(feature = "file")]
+            log_dir: None,
         }
     }
 
@@ -73,10 +96,31 @@ impl<T> CommandProvider for LoggingCliProvider<T>
 where
     T: Formattable + Clone + Send + Sync + 'static,
 {
+    #[cfg(feature = "file")]
+    fn get_commands(&self, command: clap::Command) -> clap::Command {
+        CliCommands::augment_subcommands(command)
+    }
+
+    #[cfg(not(feature = "file"))]
     fn get_commands(&self, command: clap::Command) -> clap::Command {
         command
     }
 
+    #[cfg(feature = "file")]
+    fn matches(&mut self, matches: &clap::ArgMatches) -> Option<CommandMatch> {
+        let CliCommands::Logs {
+            command: LogsCommands::Path,
+        } = CliCommands::from_arg_matches(matches).ok()?;
+        // The builder is consumed once the logger is created so the path needs to be resolved
+        // ahead of time
+        self.log_dir = Some(self.builder.as_ref()?.log_dir());
+        Some(CommandMatch {
+            action_type: ActionType::Client,
+            action: None,
+        })
+    }
+
+    #[cfg(not(feature = "file"))]
     fn matches(&mut self, _matches: &clap::ArgMatches) -> Option<CommandMatch> {
         None
     }
@@ -104,6 +148,16 @@ where
                         res
                     }
                     ActionType::Server => {
+                        // Running directly is meant for development, so show pretty logs in the
+                        // terminal
+                        let current_builder =
+                            if matched.action == Some(Action::Server(ServerAction::Direct)) {
+                                current_builder
+                                    .with_log_to_stdout(true)
+                                    .with_log_to_stderr(false)
+                            } else {
+                                current_builder
+                            };
                         #[cfg(feature = "ipc")]
                         let res = current_builder.with_ipc_logger(true);
                         #[cfg(not(feature = "ipc"))]
@@ -120,6 +174,12 @@ where
     }
 
     async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
+        #[cfg(feature = "file")]
+        if let Some(log_dir) = self.log_dir.take() {
+            return Ok(CommandOutput::handled(
+                log_dir?.to_string_lossy().to_string(),
+            ));
+        }
         Ok(CommandOutput::unhandled())
     }
 }
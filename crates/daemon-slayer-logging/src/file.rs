@@ -0,0 +1,404 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use daemon_slayer_core::Label;
+use time::OffsetDateTime;
+use tracing_appender::rolling::Rotation;
+
+use crate::LoggerCreationError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogDir {
+    /// The user's cache directory for the application, ex: `~/.cache/<app>` on Linux.
+    ProjectDir,
+    /// A machine-wide directory meant for system services. `/var/log/<app>` on Linux,
+    /// `/Library/Logs/<app>` on macOS, and `%ProgramData%\<org>\<app>\logs` on Windows.
+    System,
+    Custom(PathBuf),
+}
+
+impl LogDir {
+    /// [`LogDir::System`] for services installed at the system level and [`LogDir::ProjectDir`]
+    /// for everything else.
+    pub(crate) fn default_for(label: &Label) -> Self {
+        match std::env::var(label.admin_var())
+            .map(|v| v.to_lowercase())
+            .as_deref()
+        {
+            Ok("1" | "true") => LogDir::System,
+            _ => LogDir::ProjectDir,
+        }
+    }
+
+    pub(crate) fn resolve(&self, label: &Label) -> Result<PathBuf, LoggerCreationError> {
+        match self {
+            LogDir::ProjectDir => Ok(directories::ProjectDirs::from(
+                &label.qualifier,
+                &label.organization,
+                &label.application,
+            )
+            .ok_or(LoggerCreationError::NoHomeDir)?
+            .cache_dir()
+            .to_owned()),
+            LogDir::System => Ok(system_log_dir(label)),
+            LogDir::Custom(path) => Ok(path.to_owned()),
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn system_log_dir(label: &Label) -> PathBuf {
+    Path::new("/var/log").join(&label.application)
+}
+
+#[cfg(target_os = "macos")]
+fn system_log_dir(label: &Label) -> PathBuf {
+    Path::new("/Library/Logs").join(&label.application)
+}
+
+#[cfg(windows)]
+fn system_log_dir(label: &Label) -> PathBuf {
+    let program_data = std::env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into());
+    Path::new(&program_data)
+        .join(&label.organization)
+        .join(&label.application)
+        .join("logs")
+}
+
+/// An error that occurred while maintaining the log files after the logger was created.
+#[derive(thiserror::Error, Debug)]
+pub enum LogFileError {
+    #[error("Failed to rotate log file: {0}")]
+    Rotate(io::Error),
+    #[error("Failed to compress log file {0:?}: {1}")]
+    Compress(PathBuf, io::Error),
+    #[error("Failed to read log directory {0:?}: {1}")]
+    ReadDir(PathBuf, io::Error),
+    #[error("Failed to remove old log file {0:?}: {1}")]
+    Remove(PathBuf, io::Error),
+}
+
+#[derive(Clone)]
+pub(crate) struct FileErrorHandler(Arc<dyn Fn(LogFileError) + Send + Sync>);
+
+impl FileErrorHandler {
+    pub(crate) fn new(handler: impl Fn(LogFileError) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+}
+
+impl fmt::Debug for FileErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FileErrorHandler").finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FileOptions {
+    pub(crate) rotation: Rotation,
+    pub(crate) max_file_size: Option<u64>,
+    pub(crate) max_files: Option<usize>,
+    pub(crate) max_age: Option<Duration>,
+    pub(crate) gzip: bool,
+    pub(crate) on_error: Option<FileErrorHandler>,
+}
+
+impl FileOptions {
+    // Errors can't be logged since they come from the logger itself
+    fn report(&self, error: LogFileError) {
+        if let Some(on_error) = &self.on_error {
+            (on_error.0)(error);
+        }
+    }
+}
+
+impl Default for FileOptions {
+    fn default() -> Self {
+        Self {
+            rotation: Rotation::HOURLY,
+            max_file_size: None,
+            max_files: None,
+            max_age: None,
+            gzip: false,
+            on_error: None,
+        }
+    }
+}
+
+/// Writes to `<name>.log` and moves it to `<name>.<timestamp>.log` once the rotation period
+/// elapses or the size limit is reached.
+pub(crate) struct RotatingFileWriter {
+    dir: PathBuf,
+    name: String,
+    options: FileOptions,
+    file: Option<File>,
+    size: u64,
+    next_rotation: Option<OffsetDateTime>,
+    // Compressing large files can take a while so archives are compressed and pruned on a
+    // separate thread to avoid holding up the log writer
+    archive_tx: Option<mpsc::Sender<PathBuf>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl RotatingFileWriter {
+    pub(crate) fn new(dir: PathBuf, name: String, options: FileOptions) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let (archive_tx, archive_rx) = mpsc::channel::<PathBuf>();
+        let worker = {
+            let dir = dir.clone();
+            let name = name.clone();
+            let options = options.clone();
+            thread::Builder::new()
+                .name("log-file-maintenance".to_owned())
+                .spawn(move || {
+                    for archived in archive_rx {
+                        if options.gzip
+                            && let Err(e) = compress(&archived)
+                        {
+                            options.report(LogFileError::Compress(archived, e));
+                        }
+                        prune(&dir, &name, &options);
+                    }
+                })?
+        };
+        let mut writer = Self {
+            dir,
+            name,
+            options,
+            file: None,
+            size: 0,
+            next_rotation: None,
+            archive_tx: Some(archive_tx),
+            worker: Some(worker),
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let active_path = writer.active_path();
+        if let Ok(metadata) = fs::metadata(&active_path) {
+            // Leftover logs from a previous period shouldn't get mixed with the new ones
+            let period_start = period_bounds(&writer.options.rotation, now).map(|(start, _)| start);
+            if let (Some(period_start), Ok(modified)) = (period_start, metadata.modified())
+                && OffsetDateTime::from(modified) < period_start
+                && metadata.len() > 0
+            {
+                writer.archive_active_file(now)?;
+            }
+        }
+        writer.open(now)?;
+        prune(&writer.dir, &writer.name, &writer.options);
+        Ok(writer)
+    }
+
+    fn active_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.log", self.name))
+    }
+
+    fn open(&mut self, now: OffsetDateTime) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.active_path())?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        self.next_rotation =
+            period_bounds(&self.options.rotation, now).map(|(start, period)| start + period);
+        Ok(())
+    }
+
+    fn should_rotate(&self, now: OffsetDateTime, incoming: usize) -> bool {
+        let size_exceeded = self
+            .options
+            .max_file_size
+            .is_some_and(|max| self.size > 0 && self.size + incoming as u64 > max);
+        let period_elapsed = self.next_rotation.is_some_and(|next| now >= next);
+        size_exceeded || period_elapsed
+    }
+
+    fn archive_active_file(&mut self, now: OffsetDateTime) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let timestamp = format!(
+            "{:04}-{:02}-{:02}-{:02}-{:02}-{:02}",
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        let mut archived = self.dir.join(format!("{}.{timestamp}.log", self.name));
+        let mut suffix = 1;
+        while archived.exists() || gz_path(&archived).exists() {
+            archived = self
+                .dir
+                .join(format!("{}.{timestamp}.{suffix}.log", self.name));
+            suffix += 1;
+        }
+        fs::rename(self.active_path(), &archived)?;
+
+        if let Some(archive_tx) = &self.archive_tx {
+            // The worker only stops once the sender is dropped
+            archive_tx.send(archived).ok();
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self, now: OffsetDateTime) -> io::Result<()> {
+        self.archive_active_file(now)?;
+        self.open(now)
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let now = OffsetDateTime::now_utc();
+        if self.should_rotate(now, buf.len())
+            && let Err(e) = self.rotate(now)
+        {
+            // Keep writing to the current file if possible rather than dropping logs
+            self.options.report(LogFileError::Rotate(e));
+        }
+        if self.file.is_none() {
+            self.open(now)?;
+        }
+        let written = self.file.as_mut().expect("file opened").write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RotatingFileWriter {
+    fn drop(&mut self) {
+        // Let the worker finish compressing any pending archives so they aren't left half-written
+        self.archive_tx.take();
+        if let Some(worker) = self.worker.take() {
+            worker.join().ok();
+        }
+    }
+}
+
+fn period_bounds(
+    rotation: &Rotation,
+    now: OffsetDateTime,
+) -> Option<(OffsetDateTime, time::Duration)> {
+    let date = now.date();
+    match *rotation {
+        Rotation::MINUTELY => Some((
+            date.with_hms(now.hour(), now.minute(), 0)
+                .ok()?
+                .assume_utc(),
+            time::Duration::minutes(1),
+        )),
+        Rotation::HOURLY => Some((
+            date.with_hms(now.hour(), 0, 0).ok()?.assume_utc(),
+            time::Duration::hours(1),
+        )),
+        Rotation::DAILY => Some((date.midnight().assume_utc(), time::Duration::days(1))),
+        Rotation::WEEKLY => {
+            let days_since_sunday = date.weekday().number_days_from_sunday();
+            Some((
+                (date - time::Duration::days(days_since_sunday.into()))
+                    .midnight()
+                    .assume_utc(),
+                time::Duration::weeks(1),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut gz_path = path.as_os_str().to_owned();
+    gz_path.push(".gz");
+    PathBuf::from(gz_path)
+}
+
+fn compress(path: &Path) -> io::Result<()> {
+    let mut input = BufReader::new(File::open(path)?);
+    let output = File::create(gz_path(path))?;
+    let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)
+}
+
+// Matches `<name>.YYYY-MM-DD-HH-MM-SS[.N].log[.gz]` and returns whether the archive is
+// compressed. Anything else in the directory isn't ours to remove.
+fn parse_archive_name(file_name: &str, name: &str) -> Option<bool> {
+    let rest = file_name.strip_prefix(name)?.strip_prefix('.')?;
+    let (rest, gzip) = match rest.strip_suffix(".gz") {
+        Some(rest) => (rest, true),
+        None => (rest, false),
+    };
+    let rest = rest.strip_suffix(".log")?;
+    let (timestamp, suffix) = match rest.split_once('.') {
+        Some((timestamp, suffix)) => (timestamp, Some(suffix)),
+        None => (rest, None),
+    };
+    let is_number = |value: &str| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+    let widths = [4, 2, 2, 2, 2, 2];
+    let parts: Vec<_> = timestamp.split('-').collect();
+    let valid_timestamp = parts.len() == widths.len()
+        && parts
+            .iter()
+            .zip(widths)
+            .all(|(part, width)| part.len() == width && is_number(part));
+    (valid_timestamp && suffix.is_none_or(is_number)).then_some(gzip)
+}
+
+fn prune(dir: &Path, name: &str, options: &FileOptions) {
+    if options.max_files.is_none() && options.max_age.is_none() {
+        return;
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            options.report(LogFileError::ReadDir(dir.to_owned(), e));
+            return;
+        }
+    };
+    let mut archived: Vec<_> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name();
+            let compressed = parse_archive_name(file_name.to_str()?, name)?;
+            // Skip archives that are still being compressed
+            if options.gzip && !compressed {
+                return None;
+            }
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect();
+    archived.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let now = SystemTime::now();
+    for (i, (path, modified)) in archived.into_iter().enumerate() {
+        let too_many = options.max_files.is_some_and(|max| i >= max);
+        let too_old = options
+            .max_age
+            .is_some_and(|max_age| now.duration_since(modified).is_ok_and(|age| age > max_age));
+        if (too_many || too_old)
+            && let Err(e) = fs::remove_file(&path)
+        {
+            options.report(LogFileError::Remove(path, e));
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./file_test.rs"]
+mod file_test;
@@ -0,0 +1,397 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing_appender::rolling::Rotation;
+
+use std::sync::{Arc, Mutex};
+
+use super::{
+    FileErrorHandler, FileOptions, LogFileError, RotatingFileWriter, parse_archive_name, prune,
+};
+
+fn options() -> FileOptions {
+    FileOptions {
+        rotation: Rotation::NEVER,
+        ..Default::default()
+    }
+}
+
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+fn archived_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.file_name().unwrap() != "app.log")
+        .collect();
+    files.sort();
+    files
+}
+
+fn create_archive(dir: &Path, name: &str, age: Duration) -> PathBuf {
+    let path = dir.join(name);
+    let file = File::create(&path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+    path
+}
+
+// Archives are compressed and pruned on a background thread
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "timed out waiting for condition"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_size_rotation() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            max_file_size: Some(10),
+            ..options()
+        },
+    )
+    .unwrap();
+
+    writer.write_all(b"line one\n").unwrap();
+    assert!(archived_files(dir.path()).is_empty());
+    writer.write_all(b"line two\n").unwrap();
+    writer.flush().unwrap();
+
+    let archived = archived_files(dir.path());
+    assert_eq!(1, archived.len());
+    assert_eq!("line one\n", fs::read_to_string(&archived[0]).unwrap());
+    assert_eq!(
+        "line two\n",
+        fs::read_to_string(dir.path().join("app.log")).unwrap()
+    );
+}
+
+#[test]
+fn test_oversized_write_not_rotated_into_empty_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            max_file_size: Some(4),
+            ..options()
+        },
+    )
+    .unwrap();
+
+    writer.write_all(b"longer than the limit\n").unwrap();
+    writer.flush().unwrap();
+
+    assert_eq!(vec!["app.log"], file_names(dir.path()));
+}
+
+#[test]
+fn test_archive_names_unique() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            max_file_size: Some(1),
+            ..options()
+        },
+    )
+    .unwrap();
+
+    for line in ["a\n", "b\n", "c\n"] {
+        writer.write_all(line.as_bytes()).unwrap();
+    }
+    writer.flush().unwrap();
+
+    let contents: Vec<_> = archived_files(dir.path())
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    assert_eq!(2, contents.len());
+    assert!(contents.contains(&"a\n".to_owned()));
+    assert!(contents.contains(&"b\n".to_owned()));
+}
+
+#[test]
+fn test_previous_period_archived_on_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let active = dir.path().join("app.log");
+    fs::write(&active, "old\n").unwrap();
+    File::options()
+        .write(true)
+        .open(&active)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(60 * 60 * 48))
+        .unwrap();
+
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            rotation: Rotation::DAILY,
+            ..options()
+        },
+    )
+    .unwrap();
+    writer.write_all(b"new\n").unwrap();
+    writer.flush().unwrap();
+
+    let archived = archived_files(dir.path());
+    assert_eq!(1, archived.len());
+    assert_eq!("old\n", fs::read_to_string(&archived[0]).unwrap());
+    assert_eq!("new\n", fs::read_to_string(&active).unwrap());
+}
+
+#[test]
+fn test_prune_max_files() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.log"), "").unwrap();
+    fs::write(dir.path().join("other.log"), "").unwrap();
+    // Files that only share the prefix weren't created by the writer
+    create_archive(dir.path(), "app.backup.log", Duration::from_secs(400));
+    create_archive(dir.path(), "app.2024-01-01.log", Duration::from_secs(400));
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-00-00-00.log",
+        Duration::from_secs(300),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.log",
+        Duration::from_secs(200),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.1.log",
+        Duration::from_secs(100),
+    );
+
+    prune(
+        dir.path(),
+        "app",
+        &FileOptions {
+            max_files: Some(2),
+            ..options()
+        },
+    );
+
+    assert_eq!(
+        vec![
+            "app.2024-01-01-01-00-00.1.log",
+            "app.2024-01-01-01-00-00.log",
+            "app.2024-01-01.log",
+            "app.backup.log",
+            "app.log",
+            "other.log"
+        ],
+        file_names(dir.path())
+    );
+}
+
+#[test]
+fn test_prune_max_age() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("app.log"), "").unwrap();
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-00-00-00.log",
+        Duration::from_secs(60 * 60 * 24 * 3),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.log",
+        Duration::from_secs(60),
+    );
+
+    prune(
+        dir.path(),
+        "app",
+        &FileOptions {
+            max_age: Some(Duration::from_secs(60 * 60 * 24)),
+            ..options()
+        },
+    );
+
+    assert_eq!(
+        vec!["app.2024-01-01-01-00-00.log", "app.log"],
+        file_names(dir.path())
+    );
+}
+
+#[test]
+fn test_prune_skips_uncompressed_archives() {
+    let dir = tempfile::tempdir().unwrap();
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-00-00-00.log",
+        Duration::from_secs(300),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.log.gz",
+        Duration::from_secs(200),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.1.log.gz",
+        Duration::from_secs(100),
+    );
+
+    prune(
+        dir.path(),
+        "app",
+        &FileOptions {
+            max_files: Some(1),
+            gzip: true,
+            ..options()
+        },
+    );
+
+    assert_eq!(
+        vec![
+            "app.2024-01-01-00-00-00.log",
+            "app.2024-01-01-01-00-00.1.log.gz"
+        ],
+        file_names(dir.path())
+    );
+}
+
+#[test]
+fn test_gzip_rotated_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            max_file_size: Some(10),
+            gzip: true,
+            ..options()
+        },
+    )
+    .unwrap();
+
+    writer.write_all(b"line one\n").unwrap();
+    writer.write_all(b"line two\n").unwrap();
+    writer.flush().unwrap();
+
+    wait_for(|| {
+        let archived = archived_files(dir.path());
+        archived.len() == 1 && archived[0].extension().unwrap() == "gz"
+    });
+    let mut decoded = String::new();
+    flate2::read::GzDecoder::new(File::open(&archived_files(dir.path())[0]).unwrap())
+        .read_to_string(&mut decoded)
+        .unwrap();
+    assert_eq!("line one\n", decoded);
+}
+
+#[test]
+fn test_rotation_prunes_old_files() {
+    let dir = tempfile::tempdir().unwrap();
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-00-00-00.log",
+        Duration::from_secs(300),
+    );
+    create_archive(
+        dir.path(),
+        "app.2024-01-01-01-00-00.log",
+        Duration::from_secs(200),
+    );
+    let mut writer = RotatingFileWriter::new(
+        dir.path().to_owned(),
+        "app".to_owned(),
+        FileOptions {
+            max_file_size: Some(1),
+            max_files: Some(2),
+            ..options()
+        },
+    )
+    .unwrap();
+
+    writer.write_all(b"a\n").unwrap();
+    writer.write_all(b"b\n").unwrap();
+
+    wait_for(|| !dir.path().join("app.2024-01-01-00-00-00.log").exists());
+    assert_eq!(2, archived_files(dir.path()).len());
+    assert!(dir.path().join("app.2024-01-01-01-00-00.log").exists());
+}
+
+#[test]
+fn test_parse_archive_name() {
+    assert_eq!(
+        Some(false),
+        parse_archive_name("app.2024-01-02-03-04-05.log", "app")
+    );
+    assert_eq!(
+        Some(true),
+        parse_archive_name("app.2024-01-02-03-04-05.log.gz", "app")
+    );
+    assert_eq!(
+        Some(false),
+        parse_archive_name("app.2024-01-02-03-04-05.12.log", "app")
+    );
+    assert_eq!(
+        Some(false),
+        parse_archive_name("my.app.2024-01-02-03-04-05.log", "my.app")
+    );
+    assert_eq!(None, parse_archive_name("app.log", "app"));
+    assert_eq!(None, parse_archive_name("app.backup.log", "app"));
+    assert_eq!(None, parse_archive_name("app.2024-01-02-03-04.log", "app"));
+    assert_eq!(
+        None,
+        parse_archive_name("app.2024-01-02-03-04-05..log", "app")
+    );
+    assert_eq!(
+        None,
+        parse_archive_name("app.2024-01-02-03-04-05.log.bak", "app")
+    );
+    assert_eq!(
+        None,
+        parse_archive_name("other.2024-01-02-03-04-05.log", "app")
+    );
+}
+
+#[test]
+fn test_prune_reports_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    // Directories can't be removed with remove_file
+    fs::create_dir(dir.path().join("app.2024-01-01-00-00-00.log")).unwrap();
+    let errors = Arc::new(Mutex::new(Vec::new()));
+
+    prune(
+        dir.path(),
+        "app",
+        &FileOptions {
+            max_files: Some(0),
+            on_error: Some(FileErrorHandler::new({
+                let errors = errors.clone();
+                move |e| errors.lock().unwrap().push(e)
+            })),
+            ..options()
+        },
+    );
+
+    let errors = errors.lock().unwrap();
+    assert_eq!(1, errors.len());
+    assert!(
+        matches!(&errors[0], LogFileError::Remove(path, _) if path.ends_with("app.2024-01-01-00-00-00.log"))
+    );
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize, schemars::JsonSchema),
+    serde(rename_all = "kebab-case")
+)]
+pub enum LogFormat {
+    /// Multi-line output meant for reading in a terminal.
+    Pretty,
+    /// Single-line output with span names before the event and span fields after it.
+    Compact,
+    /// Single-line output with each span's name and fields before the event.
+    Full,
+    /// One JSON object per line. Fields from the event and all of its parent spans are merged
+    /// into the top level of the object.
+    Json,
+}
+
+/// Formats each event as a JSON object with the span fields flattened alongside the event fields.
+/// Span fields must be recorded with [`JsonFields`](tracing_subscriber::fmt::format::JsonFields).
+pub(crate) struct FlattenedJson<T> {
+    timer: T,
+}
+
+impl<T> FlattenedJson<T> {
+    pub(crate) fn new(timer: T) -> Self {
+        Self { timer }
+    }
+}
+
+impl<S, N, T> FormatEvent<S, N> for FlattenedJson<T>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    T: FormatTime,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut fields = Map::new();
+
+        let mut timestamp = String::new();
+        self.timer.format_time(&mut Writer::new(&mut timestamp))?;
+        fields.insert("timestamp".to_owned(), timestamp.into());
+        let metadata = event.metadata();
+        fields.insert("level".to_owned(), metadata.level().as_str().into());
+        fields.insert("target".to_owned(), metadata.target().into());
+        let thread = std::thread::current();
+        if let Some(name) = thread.name() {
+            fields.insert("threadName".to_owned(), name.into());
+        }
+        fields.insert("threadId".to_owned(), format!("{:?}", thread.id()).into());
+
+        if let Some(scope) = ctx.event_scope() {
+            let mut span_names = Vec::new();
+            // Iterating from the root lets fields on inner spans override the outer ones
+            for span in scope.from_root() {
+                span_names.push(Value::from(span.name()));
+                let extensions = span.extensions();
+                if let Some(span_fields) = extensions.get::<FormattedFields<N>>()
+                    && let Ok(Value::Object(span_fields)) =
+                        serde_json::from_str::<Value>(span_fields)
+                {
+                    fields.extend(span_fields);
+                }
+            }
+            fields.insert("spans".to_owned(), span_names.into());
+        }
+
+        event.record(&mut JsonVisitor(&mut fields));
+
+        writeln!(writer, "{}", Value::Object(fields))
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{value:?}").into());
+    }
+}
+
+#[cfg(test)]
+#[path = "./format_test.rs"]
+mod format_test;
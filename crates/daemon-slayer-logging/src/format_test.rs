@@ -0,0 +1,108 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::JsonFields;
+use tracing_subscriber::prelude::*;
+
+use super::FlattenedJson;
+
+#[derive(Clone, Default)]
+struct TestWriter(Arc<Mutex<Vec<u8>>>);
+
+impl TestWriter {
+    fn lines(&self) -> Vec<Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+impl io::Write for TestWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for TestWriter {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn log_json(f: impl FnOnce()) -> Vec<Value> {
+    let writer = TestWriter::default();
+    let subscriber = tracing_subscriber::registry().with(
+        tracing_subscriber::fmt::layer()
+            .fmt_fields(JsonFields::new())
+            .event_format(FlattenedJson::new(()))
+            .with_writer(writer.clone()),
+    );
+    tracing::subscriber::with_default(subscriber, f);
+    writer.lines()
+}
+
+#[test]
+fn test_event_fields() {
+    let lines = log_json(|| {
+        tracing::warn!(count = 3, ratio = 0.5, ok = true, name = "test", "message");
+    });
+
+    assert_eq!(1, lines.len());
+    let line = &lines[0];
+    assert_eq!(json!("WARN"), line["level"]);
+    assert_eq!(json!(module_path!()), line["target"]);
+    assert_eq!(json!("message"), line["message"]);
+    assert_eq!(json!(3), line["count"]);
+    assert_eq!(json!(0.5), line["ratio"]);
+    assert_eq!(json!(true), line["ok"]);
+    assert_eq!(json!("test"), line["name"]);
+    assert!(line.get("spans").is_none());
+}
+
+#[test]
+fn test_span_fields_flattened() {
+    let lines = log_json(|| {
+        let _outer = tracing::info_span!("outer", request_id = 1, user = "outer").entered();
+        let _inner = tracing::info_span!("inner", user = "inner").entered();
+        tracing::info!("message");
+    });
+
+    let line = &lines[0];
+    assert_eq!(json!(["outer", "inner"]), line["spans"]);
+    assert_eq!(json!(1), line["request_id"]);
+    // Inner spans take precedence over outer spans
+    assert_eq!(json!("inner"), line["user"]);
+}
+
+#[test]
+fn test_event_fields_override_span_fields() {
+    let lines = log_json(|| {
+        let _span = tracing::info_span!("span", user = "span").entered();
+        tracing::info!(user = "event", "message");
+    });
+
+    assert_eq!(json!("event"), lines[0]["user"]);
+}
+
+#[test]
+fn test_span_fields_recorded_later() {
+    let lines = log_json(|| {
+        let span = tracing::info_span!("span", status = tracing::field::Empty);
+        span.record("status", "done");
+        let _span = span.entered();
+        tracing::info!("message");
+    });
+
+    assert_eq!(json!("done"), lines[0]["status"]);
+}
@@ -1,11 +1,38 @@
 #[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "file")]
+mod file;
+mod format;
 mod logger_builder;
 mod logger_guard;
+#[cfg(feature = "otlp")]
+mod otlp;
+#[cfg(feature = "rate-limit")]
+mod rate_limit;
+#[cfg(any(feature = "redaction", feature = "rate-limit"))]
+mod recorded;
+#[cfg(feature = "redaction")]
+mod redact;
 mod reload_handle;
+#[cfg(feature = "ipc")]
+mod replay;
 #[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "syslog")]
+mod syslog;
+mod target_filters;
 
+#[cfg(feature = "file")]
+pub use file::{LogDir, LogFileError};
+pub use format::LogFormat;
 pub use logger_builder::*;
+#[cfg(feature = "otlp")]
+pub use otlp::{OtlpConfig, OtlpProtocol};
+#[cfg(feature = "rate-limit")]
+pub use rate_limit::RateLimitConfig;
+#[cfg(feature = "redaction")]
+pub use redact::RedactionConfig;
 pub use reload_handle::*;
+#[cfg(feature = "syslog")]
+pub use syslog::{SyslogConfig, SyslogFacility, SyslogFormat, SyslogTransport};
 pub use {time, tracing_subscriber};
@@ -10,16 +10,21 @@ use time::formatting::Formattable;
 use tracing::metadata::LevelFilter;
 use tracing::{Level, Subscriber, debug};
 use tracing_appender::non_blocking::NonBlockingBuilder;
-use tracing_subscriber::filter::Directive;
-use tracing_subscriber::fmt::Layer;
+use tracing_subscriber::filter::{Directive, ParseError};
+use tracing_subscriber::fmt::format::JsonFields;
 use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::fmt::{Layer, MakeWriter};
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, Layer as SubscriberLayer, reload};
 
 use super::logger_guard::LoggerGuard;
-use crate::ReloadHandle;
+use crate::format::FlattenedJson;
+use crate::target_filters::TargetFilters;
+use crate::{LogFormat, ReloadHandle};
+
+type ReloadFns = Vec<(LogTarget, Box<dyn Fn(EnvFilter) + Send + Sync>)>;
 
 static LOGGER_GUARD: OnceLock<Mutex<Option<LoggerGuard>>> = OnceLock::new();
 
@@ -50,9 +55,23 @@ pub enum LoggerCreationError {
     #[cfg(feature = "file")]
     #[error("Error creating file logging layer: Unable to locate a home directory")]
     NoHomeDir,
+    #[cfg(feature = "file")]
+    #[error("Error creating log file in {0:?}: {1}")]
+    FileFailure(std::path::PathBuf, io::Error),
+    #[cfg(feature = "otlp")]
+    #[error("Error creating OTLP exporter: {0}")]
+    OtlpFailure(opentelemetry_otlp::ExporterBuildError),
+    #[cfg(feature = "syslog")]
+    #[error("Error connecting to syslog: {0}")]
+    SyslogFailure(io::Error),
 }
 
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(
+    feature = "config",
+    derive(serde::Deserialize, schemars::JsonSchema),
+    serde(rename_all = "lowercase")
+)]
 pub enum LogTarget {
     File,
     EventLog,
@@ -61,6 +80,8 @@ pub enum LogTarget {
     Stdout,
     Stderr,
     Ipc,
+    Otlp,
+    Syslog,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -98,17 +119,103 @@ impl<'de> serde::Deserialize<'de> for LogLevel {
     }
 }
 
+#[cfg(feature = "config")]
+impl schemars::JsonSchema for LogLevel {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "LogLevel".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "enum": ["trace", "debug", "info", "warn", "error"]
+        })
+    }
+}
+
+/// Filter directives in the same format as `RUST_LOG`, ex: `my_crate::db=trace,hyper=warn`.
+/// Directives are validated when parsed so invalid ones are rejected along with the rest of the
+/// config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter(String);
+
+impl LogFilter {
+    fn split(directives: &str) -> impl Iterator<Item = &str> {
+        directives
+            .split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+    }
+
+    pub(crate) fn directives(&self) -> Vec<Directive> {
+        Self::split(&self.0)
+            .map(|directive| directive.parse().expect("directives are validated"))
+            .collect()
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        for directive in Self::split(s) {
+            directive.parse::<Directive>()?;
+        }
+        Ok(LogFilter(s.to_owned()))
+    }
+}
+
+impl std::fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "config")]
+impl<'de> serde::Deserialize<'de> for LogFilter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let val = String::deserialize(deserializer)?;
+        LogFilter::from_str(&val).map_err(|e| {
+            serde::de::Error::custom(format!("invalid filter directives '{val}': {e}"))
+        })
+    }
+}
+
+#[cfg(feature = "config")]
+impl schemars::JsonSchema for LogFilter {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "LogFilter".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({ "type": "string" })
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Mergeable)]
-#[cfg_attr(feature = "config", derive(confique::Config, serde::Deserialize))]
+#[cfg_attr(
+    feature = "config",
+    derive(confique::Config, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct UserConfig {
     #[cfg_attr(feature = "config", config(default = "info"))]
     pub log_level: LogLevel,
+    /// Filter directives for each target that are applied on top of the log level,
+    /// ex: `log_filters = { file = "my_crate::db=trace,hyper=warn" }`.
+    #[cfg_attr(feature = "config", config(default = {}))]
+    pub log_filters: HashMap<LogTarget, LogFilter>,
+    /// Overrides the format used for each target, ex: `log_formats = { file = "json" }`.
+    #[cfg_attr(feature = "config", config(default = {}))]
+    pub log_formats: HashMap<LogTarget, LogFormat>,
 }
 
 #[derive(Debug, Clone)]
 pub struct EnvConfig {
-    var_name: String,
-    default_if_missing: LevelFilter,
+    pub(crate) var_name: String,
+    pub(crate) default_if_missing: LevelFilter,
 }
 
 impl EnvConfig {
@@ -130,16 +237,29 @@ pub struct LoggerBuilder<T> {
     #[allow(dead_code)]
     label: Label,
     #[cfg(feature = "file")]
-    file_rotation_period: tracing_appender::rolling::Rotation,
+    log_dir: crate::LogDir,
+    #[cfg(feature = "file")]
+    file_options: crate::file::FileOptions,
     offset_time: OffsetTime<T>,
     output_buffer_limit: usize,
-    target_directives: HashMap<LogTarget, Vec<Directive>>,
-    env_filter_directives: Vec<Directive>,
+    filters: TargetFilters,
+    target_formats: HashMap<LogTarget, LogFormat>,
     log_to_stdout: bool,
     log_to_stderr: bool,
     #[cfg(feature = "ipc")]
     enable_ipc_logger: bool,
-    env_config: Option<EnvConfig>,
+    #[cfg(feature = "ipc")]
+    ipc_replay_buffer_size: usize,
+    #[cfg(feature = "otlp")]
+    otlp_config: Option<crate::OtlpConfig>,
+    #[cfg(feature = "syslog")]
+    syslog_config: Option<crate::SyslogConfig>,
+    #[cfg(feature = "redaction")]
+    redaction: Option<std::sync::Arc<crate::RedactionConfig>>,
+    #[cfg(feature = "rate-limit")]
+    rate_limits: HashMap<LogTarget, crate::RateLimitConfig>,
+    #[cfg(feature = "rate-limit")]
+    summary_flusher: crate::rate_limit::SummaryFlusher,
 }
 
 impl<T> LoggerBuilder<T>
@@ -148,9 +268,11 @@ where
 {
     pub fn new(label: Label, offset_time: OffsetTime<T>) -> Self {
         Self {
+            #[cfg(feature = "file")]
+            log_dir: crate::LogDir::default_for(&label),
             label,
             #[cfg(feature = "file")]
-            file_rotation_period: tracing_appender::rolling::Rotation::HOURLY,
+            file_options: Default::default(),
             offset_time,
             // The default number of buffered lines is quite large and uses a ton of memory
             // We aren't logging a ton of messages so setting this value somewhat low is fine in
@@ -160,9 +282,20 @@ where
             log_to_stderr: true,
             #[cfg(feature = "ipc")]
             enable_ipc_logger: false,
-            target_directives: Default::default(),
-            env_filter_directives: vec![],
-            env_config: None,
+            #[cfg(feature = "ipc")]
+            ipc_replay_buffer_size: 256,
+            #[cfg(feature = "otlp")]
+            otlp_config: None,
+            #[cfg(feature = "syslog")]
+            syslog_config: None,
+            #[cfg(feature = "redaction")]
+            redaction: None,
+            #[cfg(feature = "rate-limit")]
+            rate_limits: Default::default(),
+            #[cfg(feature = "rate-limit")]
+            summary_flusher: Default::default(),
+            filters: Default::default(),
+            target_formats: Default::default(),
         }
     }
 
@@ -171,10 +304,65 @@ where
         mut self,
         rotation: tracing_appender::rolling::Rotation,
     ) -> Self {
-        self.file_rotation_period = rotation;
+        self.file_options.rotation = rotation;
+        self
+    }
+
+    /// Directory to write log files to. Defaults to [`LogDir::System`](crate::LogDir::System) when
+    /// running as a system-level service and [`LogDir::ProjectDir`](crate::LogDir::ProjectDir)
+    /// otherwise.
+    #[cfg(feature = "file")]
+    pub fn with_log_dir(mut self, log_dir: crate::LogDir) -> Self {
+        self.log_dir = log_dir;
+        self
+    }
+
+    /// Rotates the log file once it reaches the given size in bytes, in addition to the rotation
+    /// period.
+    #[cfg(feature = "file")]
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.file_options.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Maximum number of rotated files to keep. The oldest files are removed first.
+    #[cfg(feature = "file")]
+    pub fn with_max_log_files(mut self, max_log_files: usize) -> Self {
+        self.file_options.max_files = Some(max_log_files);
+        self
+    }
+
+    /// Removes rotated files that were last modified longer than `max_log_age` ago.
+    #[cfg(feature = "file")]
+    pub fn with_max_log_age(mut self, max_log_age: std::time::Duration) -> Self {
+        self.file_options.max_age = Some(max_log_age);
+        self
+    }
+
+    /// Compresses rotated files with gzip.
+    #[cfg(feature = "file")]
+    pub fn with_gzip_rotated_files(mut self, gzip: bool) -> Self {
+        self.file_options.gzip = gzip;
+        self
+    }
+
+    /// Called when rotating, compressing, or pruning the log files fails. These errors can't be
+    /// logged since they come from the logger itself, so they're ignored unless a handler is set.
+    #[cfg(feature = "file")]
+    pub fn with_file_error_handler(
+        mut self,
+        handler: impl Fn(crate::LogFileError) + Send + Sync + 'static,
+    ) -> Self {
+        self.file_options.on_error = Some(crate::file::FileErrorHandler::new(handler));
         self
     }
 
+    /// The directory containing the log files.
+    #[cfg(feature = "file")]
+    pub fn log_dir(&self) -> Result<std::path::PathBuf, LoggerCreationError> {
+        self.log_dir.resolve(&self.label)
+    }
+
     pub fn with_log_to_stdout(mut self, log_to_stdout: bool) -> Self {
         self.log_to_stdout = log_to_stdout;
         self
@@ -191,7 +379,7 @@ where
     }
 
     pub fn with_env_filter_directive(mut self, directive: Directive) -> Self {
-        self.env_filter_directives.push(directive);
+        self.filters.env_filter_directives.push(directive);
         self
     }
 
@@ -201,41 +389,162 @@ where
         self
     }
 
+    /// Number of recent events that are sent to IPC clients when they connect so events logged
+    /// before the console was opened, such as startup errors, are still visible. Set to 0 to
+    /// disable.
+    #[cfg(feature = "ipc")]
+    pub fn with_ipc_replay_buffer_size(mut self, ipc_replay_buffer_size: usize) -> Self {
+        self.ipc_replay_buffer_size = ipc_replay_buffer_size;
+        self
+    }
+
+    /// Exports spans and logs to an OpenTelemetry collector. When using gRPC, the logger must be
+    /// built from within a Tokio runtime.
+    #[cfg(feature = "otlp")]
+    pub fn with_otlp_exporter(mut self, config: crate::OtlpConfig) -> Self {
+        self.otlp_config = Some(config);
+        self
+    }
+
+    /// Sends logs to a syslog daemon. The app name defaults to the label's application name.
+    #[cfg(feature = "syslog")]
+    pub fn with_syslog(mut self, config: crate::SyslogConfig) -> Self {
+        self.syslog_config = Some(config);
+        self
+    }
+
+    /// Masks sensitive fields and values before they're written to any of the targets.
+    #[cfg(feature = "redaction")]
+    pub fn with_redaction(mut self, config: crate::RedactionConfig) -> Self {
+        self.redaction = Some(std::sync::Arc::new(config));
+        self
+    }
+
+    /// Suppresses duplicate events and limits the rate of events that are written to a target.
+    #[cfg(feature = "rate-limit")]
+    pub fn with_rate_limit(mut self, target: LogTarget, config: crate::RateLimitConfig) -> Self {
+        self.rate_limits.insert(target, config);
+        self
+    }
+
     pub fn with_env_config(mut self, config: EnvConfig) -> Self {
-        self.env_config = Some(config);
+        self.filters.env_config = Some(config);
         self
     }
 
     pub fn with_target_directive(mut self, target: LogTarget, directive: Directive) -> Self {
-        if let Some(directives) = self.target_directives.get_mut(&target) {
-            directives.push(directive);
-        } else {
-            self.target_directives.insert(target, vec![directive]);
-        }
+        self.filters
+            .target_directives
+            .entry(target)
+            .or_default()
+            .push(directive);
         self
     }
 
-    fn get_base_env_filter(&self) -> EnvFilter {
-        if let Some(config) = &self.env_config {
-            EnvFilter::try_from_env(config.var_name.clone()).unwrap_or_else(|_| {
-                EnvFilter::default().add_directive(config.default_if_missing.into())
+    /// Sets the output format for a target. Stdout and stderr default to [`LogFormat::Pretty`],
+    /// files default to [`LogFormat::Full`], and IPC defaults to [`LogFormat::Compact`].
+    /// Targets that aren't written by `tracing-subscriber`, such as journald, ignore this setting.
+    pub fn with_target_format(mut self, target: LogTarget, format: LogFormat) -> Self {
+        self.target_formats.insert(target, format);
+        self
+    }
+
+    fn get_format_for_target(&self, target: &LogTarget) -> LogFormat {
+        self.target_formats
+            .get(target)
+            .copied()
+            .unwrap_or(match target {
+                LogTarget::File => LogFormat::Full,
+                LogTarget::Ipc => LogFormat::Compact,
+                _ => LogFormat::Pretty,
             })
-        } else {
-            EnvFilter::default()
-        }
     }
 
-    fn get_filter_for_target(&self, target: LogTarget) -> EnvFilter {
-        let mut env_filter = self.get_base_env_filter();
-        for directive in &self.env_filter_directives {
-            env_filter = env_filter.add_directive(directive.clone());
+    fn get_layer_for_target<S, W>(
+        &self,
+        target: LogTarget,
+        writer: W,
+    ) -> Box<dyn SubscriberLayer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+        W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+    {
+        let layer = Layer::new()
+            .with_timer(self.offset_time.clone())
+            .with_thread_ids(true)
+            .with_thread_names(true)
+            .with_ansi(target != LogTarget::File)
+            .with_writer(writer);
+        match self.get_format_for_target(&target) {
+            LogFormat::Pretty => self.wrap_layer(&target, layer.pretty()).boxed(),
+            LogFormat::Compact => self.wrap_layer(&target, layer.compact()).boxed(),
+            LogFormat::Full => self.wrap_layer(&target, layer).boxed(),
+            LogFormat::Json => self
+                .wrap_layer(
+                    &target,
+                    layer
+                        .fmt_fields(JsonFields::new())
+                        .event_format(FlattenedJson::new(self.offset_time.clone())),
+                )
+                .boxed(),
         }
-        if let Some(directives) = self.target_directives.get(&target) {
-            for directive in directives {
-                env_filter = env_filter.add_directive(directive.clone());
-            }
+    }
+
+    // Redaction runs after rate limiting so the events that are emitted by the rate limiter are
+    // masked as well
+    #[cfg_attr(not(feature = "rate-limit"), allow(unused_variables))]
+    fn wrap_layer<S, L>(
+        &self,
+        target: &LogTarget,
+        layer: L,
+    ) -> impl SubscriberLayer<S> + Send + Sync + use<T, S, L>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        L: SubscriberLayer<S> + Send + Sync,
+    {
+        #[cfg(feature = "redaction")]
+        let layer = crate::redact::Redacted::new(layer, self.redaction.clone());
+        #[cfg(feature = "rate-limit")]
+        let layer = crate::rate_limit::RateLimited::new(
+            layer,
+            self.rate_limits.get(target).cloned(),
+            &self.summary_flusher,
+        );
+        layer
+    }
+
+    // The error layer formats span fields into the same extension that the fmt layers print from, so
+    // it needs to be redacted as well
+    fn get_error_layer<S>(&self) -> impl SubscriberLayer<S> + Send + Sync + use<T, S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let layer = tracing_error::ErrorLayer::default();
+        #[cfg(feature = "redaction")]
+        let layer = crate::redact::Redacted::new(layer, self.redaction.clone());
+        layer
+    }
+
+    // Filters start out disabled and are set once all of the layers are created
+    fn get_reloadable_filter<S>(
+        target: LogTarget,
+        enabled: bool,
+        reload_fns: &mut ReloadFns,
+    ) -> reload::Layer<EnvFilter, S>
+    where
+        S: Subscriber + 'static,
+    {
+        let (filter, handle) =
+            reload::Layer::new(EnvFilter::default().add_directive(LevelFilter::OFF.into()));
+        if enabled {
+            reload_fns.push((
+                target,
+                Box::new(move |env_filter| {
+                    handle.reload(env_filter).ok();
+                }),
+            ));
         }
-        env_filter
+        filter
     }
 
     pub fn register(&self) -> Result<(), BoxedError> {
@@ -262,30 +571,38 @@ where
         self,
     ) -> Result<impl SubscriberInitExt + Subscriber + for<'a> LookupSpan<'a>, LoggerCreationError>
     {
-        let mut env_filter = self.get_base_env_filter();
-        for directive in &self.env_filter_directives {
-            env_filter = env_filter.add_directive(directive.clone());
-        }
+        self.build_inner(None).map(|(collector, _)| collector)
+    }
 
-        let collector = tracing_subscriber::registry().with(env_filter);
+    fn build_inner(
+        self,
+        user_config: Option<UserConfig>,
+    ) -> Result<
+        (
+            impl SubscriberInitExt + Subscriber + for<'a> LookupSpan<'a>,
+            ReloadHandle,
+        ),
+        LoggerCreationError,
+    > {
+        let mut reload_fns = ReloadFns::new();
+        let (global_filter, global_handle) = reload::Layer::new(LevelFilter::TRACE);
+        let collector = tracing_subscriber::registry().with(global_filter);
 
         let mut guard = LoggerGuard::default();
+        // Guards are dropped in order, so summaries need to be written before the outputs are
+        // flushed
+        #[cfg(feature = "rate-limit")]
+        guard.add_guard(self.summary_flusher.flush_on_drop());
 
         #[cfg(feature = "file")]
-        let proj_dirs = directories::ProjectDirs::from(
-            &self.label.qualifier,
-            &self.label.organization,
-            &self.label.application,
-        )
-        .ok_or(LoggerCreationError::NoHomeDir)?;
-        #[cfg(feature = "file")]
-        let log_dir = proj_dirs.cache_dir();
+        let log_dir = self.log_dir()?;
         #[cfg(feature = "file")]
-        let file_appender = tracing_appender::rolling::RollingFileAppender::new(
-            self.file_rotation_period.clone(),
-            log_dir,
-            format!("{}.log", self.label.application),
-        );
+        let file_appender = crate::file::RotatingFileWriter::new(
+            log_dir.clone(),
+            self.label.application.clone(),
+            self.file_options.clone(),
+        )
+        .map_err(|e| LoggerCreationError::FileFailure(log_dir, e))?;
         #[cfg(feature = "file")]
         let (non_blocking_file, file_guard) = NonBlockingBuilder::default()
             .buffered_lines_limit(self.output_buffer_limit)
@@ -293,15 +610,14 @@ where
         #[cfg(feature = "file")]
         guard.add_guard(Box::new(file_guard));
         #[cfg(feature = "file")]
-        let collector = collector.with({
-            Layer::new()
-                .with_timer(self.offset_time.clone())
-                .with_thread_ids(true)
-                .with_thread_names(true)
-                .with_ansi(false)
-                .with_writer(non_blocking_file)
-                .with_filter(self.get_filter_for_target(LogTarget::File))
-        });
+        let collector = collector.with(
+            self.get_layer_for_target(LogTarget::File, non_blocking_file)
+                .with_filter(Self::get_reloadable_filter(
+                    LogTarget::File,
+                    true,
+                    &mut reload_fns,
+                )),
+        );
 
         let (non_blocking_stdout, stdout_guard) = NonBlockingBuilder::default()
             .buffered_lines_limit(self.output_buffer_limit)
@@ -314,34 +630,23 @@ where
         guard.add_guard(Box::new(stderr_guard));
 
         let collector = collector
-            .with({
-                Layer::new()
-                    .pretty()
-                    .with_timer(self.offset_time.clone())
-                    .with_thread_ids(true)
-                    .with_thread_names(true)
-                    .with_writer(non_blocking_stdout)
-                    .with_filter(if self.log_to_stdout {
-                        self.get_filter_for_target(LogTarget::Stdout)
-                    } else {
-                        EnvFilter::default().add_directive(LevelFilter::OFF.into())
-                    })
-            })
-            .with({
-                #[allow(clippy::redundant_clone)]
-                Layer::new()
-                    .pretty()
-                    .with_timer(self.offset_time.clone())
-                    .with_thread_ids(true)
-                    .with_thread_names(true)
-                    .with_writer(non_blocking_stderr)
-                    .with_filter(if self.log_to_stderr {
-                        self.get_filter_for_target(LogTarget::Stderr)
-                    } else {
-                        EnvFilter::default().add_directive(LevelFilter::OFF.into())
-                    })
-            })
-            .with(tracing_error::ErrorLayer::default());
+            .with(
+                self.get_layer_for_target(LogTarget::Stdout, non_blocking_stdout)
+                    .with_filter(Self::get_reloadable_filter(
+                        LogTarget::Stdout,
+                        self.log_to_stdout,
+                        &mut reload_fns,
+                    )),
+            )
+            .with(
+                self.get_layer_for_target(LogTarget::Stderr, non_blocking_stderr)
+                    .with_filter(Self::get_reloadable_filter(
+                        LogTarget::Stderr,
+                        self.log_to_stderr,
+                        &mut reload_fns,
+                    )),
+            )
+            .with(self.get_error_layer());
 
         #[cfg(feature = "ipc")]
         let (ipc_writer, ipc_guard) = {
@@ -349,8 +654,11 @@ where
             use tilia::transport_async::codec::{CodecStream, LengthDelimitedCodec};
             use tilia::transport_async::ipc::{self, ServerId};
             let name = self.label.application.to_owned() + "_logger";
+            let replay_buffer = crate::replay::ReplayBuffer::new(self.ipc_replay_buffer_size);
+            let replay = replay_buffer.clone();
             let make_transport = move || {
                 let name = name.to_owned();
+                let replay = replay.clone();
                 Box::pin(async move {
                     let transport = ipc::Endpoint::bind(
                         ipc::EndpointParams::new(
@@ -362,10 +670,10 @@ where
                     )
                     .await
                     .unwrap();
-                    CodecStream::new(transport, LengthDelimitedCodec)
+                    replay.replay_on_connect(CodecStream::new(transport, LengthDelimitedCodec))
                 })
             };
-            if self.enable_ipc_logger {
+            let (writer, guard) = if self.enable_ipc_logger {
                 let (writer, guard) = tilia::Writer::new(1024, make_transport);
                 // Attempt to eagerly initialize the RPC server
                 // since this function is likely called from the main thread
@@ -377,49 +685,139 @@ where
                 (writer, guard)
             } else {
                 tilia::Writer::disabled(make_transport)
-            }
+            };
+            (
+                crate::replay::ReplayWriter::new(writer, replay_buffer),
+                guard,
+            )
         };
         #[cfg(feature = "ipc")]
         guard.add_guard(Box::new(ipc_guard));
         #[cfg(feature = "ipc")]
-        let collector = collector.with({
-            Layer::new()
-                .compact()
-                .with_timer(self.offset_time.clone())
-                .with_thread_ids(true)
-                .with_thread_names(true)
-                .with_writer(ipc_writer)
-                .with_filter(tilia::Filter::new(
-                    self.get_filter_for_target(LogTarget::Ipc),
-                ))
-        });
+        let collector = collector.with(
+            self.get_layer_for_target(LogTarget::Ipc, ipc_writer)
+                .with_filter(tilia::Filter::new(Self::get_reloadable_filter(
+                    LogTarget::Ipc,
+                    true,
+                    &mut reload_fns,
+                ))),
+        );
+
+        #[cfg(feature = "otlp")]
+        let collector = {
+            let (trace_layer, log_layer) = match &self.otlp_config {
+                Some(config) => {
+                    let layers = crate::otlp::OtlpLayers::new(config, &self.label)?;
+                    guard.add_guard(layers.guard);
+                    (
+                        Some(
+                            self.wrap_layer(&LogTarget::Otlp, layers.trace_layer)
+                                .with_filter(Self::get_reloadable_filter(
+                                    LogTarget::Otlp,
+                                    true,
+                                    &mut reload_fns,
+                                )),
+                        ),
+                        Some(
+                            self.wrap_layer(&LogTarget::Otlp, layers.log_layer)
+                                .with_filter(Self::get_reloadable_filter(
+                                    LogTarget::Otlp,
+                                    true,
+                                    &mut reload_fns,
+                                )),
+                        ),
+                    )
+                }
+                None => (None, None),
+            };
+            collector.with(trace_layer).with(log_layer)
+        };
 
         #[cfg(all(target_os = "linux", feature = "linux-journald"))]
         let collector = collector.with(
-            tracing_journald::layer()
-                .map_err(LoggerCreationError::JournaldFailure)?
-                .with_filter(self.get_filter_for_target(LogTarget::JournalD)),
+            self.wrap_layer(
+                &LogTarget::JournalD,
+                tracing_journald::layer().map_err(LoggerCreationError::JournaldFailure)?,
+            )
+            .with_filter(Self::get_reloadable_filter(
+                LogTarget::JournalD,
+                true,
+                &mut reload_fns,
+            )),
         );
 
+        #[cfg(feature = "syslog")]
+        let collector = {
+            let syslog_layer = match &self.syslog_config {
+                Some(config) => {
+                    let (syslog_layer, syslog_guard) = crate::syslog::SyslogLayer::new(
+                        config,
+                        &self.label.application,
+                        self.output_buffer_limit,
+                    )?;
+                    guard.add_guard(Box::new(syslog_guard));
+                    Some(
+                        self.wrap_layer(&LogTarget::Syslog, syslog_layer)
+                            .with_filter(Self::get_reloadable_filter(
+                                LogTarget::Syslog,
+                                true,
+                                &mut reload_fns,
+                            )),
+                    )
+                }
+                None => None,
+            };
+            collector.with(syslog_layer)
+        };
+
         #[cfg(all(target_os = "macos", feature = "mac-oslog"))]
         let collector = collector.with(
-            tracing_oslog::OsLogger::new(self.label.qualified_name(), "default")
-                .with_filter(self.get_filter_for_target(LogTarget::OsLog)),
+            self.wrap_layer(
+                &LogTarget::OsLog,
+                tracing_oslog::OsLogger::new(self.label.qualified_name(), "default"),
+            )
+            .with_filter(Self::get_reloadable_filter(
+                LogTarget::OsLog,
+                true,
+                &mut reload_fns,
+            )),
         );
 
         #[cfg(all(windows, feature = "windows-eventlog"))]
         let collector = collector.with(
-            tracing_eventlog::EventLogLayer::pretty(self.label.application.clone())
-                .map_err(|e| LoggerCreationError::EventLogError(e.to_string()))?
-                .with_filter(self.get_filter_for_target(LogTarget::EventLog)),
+            self.wrap_layer(
+                &LogTarget::EventLog,
+                tracing_eventlog::EventLogLayer::pretty(self.label.application.clone())
+                    .map_err(|e| LoggerCreationError::EventLogError(e.to_string()))?,
+            )
+            .with_filter(Self::get_reloadable_filter(
+                LogTarget::EventLog,
+                true,
+                &mut reload_fns,
+            )),
         );
 
+        let reload_handle = ReloadHandle::new(
+            self.filters,
+            user_config.as_ref(),
+            reload_fns,
+            Box::new(move |level_filter| {
+                global_handle.reload(level_filter).ok();
+            }),
+        );
+        reload_handle.reload();
+        // Lets the server handle log level requests without the service forwarding them
+        daemon_slayer_core::control::register_log_level_handler({
+            let reload_handle = reload_handle.clone();
+            move |level, revert_after| Ok(reload_handle.set_runtime_filter(level, revert_after)?)
+        });
+
         LOGGER_GUARD.set(Mutex::new(Some(guard))).ok();
-        Ok(collector)
+        Ok((collector, reload_handle))
     }
 
     pub fn build_with_reload<S>(
-        self,
+        mut self,
         service: S,
     ) -> Result<
         (
@@ -431,14 +829,13 @@ where
     where
         S: Accessor<UserConfig> + Clone + Unpin + 'static,
     {
-        let user_config = service.access();
-        let collector = self.build()?;
-        let (filter, reload_handle) = reload::Layer::new(user_config.snapshot().log_level.0.into());
-        let reload_fn = Box::new(move |level_filter: LevelFilter| {
-            reload_handle.modify(|l| *l = level_filter).ok();
-        });
-
-        let collector = collector.with(filter);
-        Ok((collector, ReloadHandle::new(reload_fn)))
+        let user_config = service.access().snapshot();
+        // Formats can't be changed after the logger is built so they're only read on startup
+        self.target_formats.extend(user_config.log_formats.clone());
+        self.build_inner(Some(user_config))
     }
 }
+
+#[cfg(test)]
+#[path = "./logger_builder_test.rs"]
+mod logger_builder_test;
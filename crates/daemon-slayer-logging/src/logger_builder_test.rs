@@ -0,0 +1,159 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use time::UtcOffset;
+use time::format_description::well_known::Rfc3339;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::prelude::*;
+
+use super::{LogTarget, LoggerBuilder};
+use crate::LogFormat;
+
+#[derive(Clone, Default)]
+struct TestWriter(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for TestWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for TestWriter {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn builder() -> LoggerBuilder<Rfc3339> {
+    LoggerBuilder::new(
+        "com.test.app".parse().unwrap(),
+        OffsetTime::new(UtcOffset::UTC, Rfc3339),
+    )
+}
+
+// Files are written without ANSI colors which keeps the output easy to compare
+fn log_file(format: LogFormat) -> String {
+    let builder = builder().with_target_format(LogTarget::File, format);
+    let writer = TestWriter::default();
+    let subscriber = tracing_subscriber::registry()
+        .with(builder.get_layer_for_target(LogTarget::File, writer.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("request", request_id = 1).entered();
+        tracing::info!(status = "ok", "handled request");
+    });
+    let output = writer.0.lock().unwrap().clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_default_formats() {
+    let builder = builder();
+    assert_eq!(
+        LogFormat::Full,
+        builder.get_format_for_target(&LogTarget::File)
+    );
+    assert_eq!(
+        LogFormat::Compact,
+        builder.get_format_for_target(&LogTarget::Ipc)
+    );
+    assert_eq!(
+        LogFormat::Pretty,
+        builder.get_format_for_target(&LogTarget::Stdout)
+    );
+    assert_eq!(
+        LogFormat::Pretty,
+        builder.get_format_for_target(&LogTarget::Stderr)
+    );
+}
+
+#[test]
+fn test_target_format_override() {
+    let builder = builder()
+        .with_target_format(LogTarget::File, LogFormat::Json)
+        .with_target_format(LogTarget::Stderr, LogFormat::Compact);
+    assert_eq!(
+        LogFormat::Json,
+        builder.get_format_for_target(&LogTarget::File)
+    );
+    assert_eq!(
+        LogFormat::Compact,
+        builder.get_format_for_target(&LogTarget::Stderr)
+    );
+    assert_eq!(
+        LogFormat::Pretty,
+        builder.get_format_for_target(&LogTarget::Stdout)
+    );
+}
+
+#[test]
+fn test_full_format() {
+    let output = log_file(LogFormat::Full);
+    assert_eq!(1, output.lines().count());
+    assert!(output.contains("request{request_id=1}:"));
+    assert!(output.contains("handled request status=\"ok\""));
+}
+
+#[test]
+fn test_compact_format() {
+    let output = log_file(LogFormat::Compact);
+    assert_eq!(1, output.lines().count());
+    assert!(output.contains("request:"));
+    assert!(!output.contains("request{"));
+    assert!(output.contains("handled request status=\"ok\" request_id=1"));
+}
+
+#[test]
+fn test_pretty_format() {
+    let output = log_file(LogFormat::Pretty);
+    assert!(output.lines().count() > 1);
+    assert!(output.contains("handled request"));
+    assert!(output.contains("in ") && output.contains("request with request_id: 1"));
+}
+
+#[test]
+fn test_json_format() {
+    let output = log_file(LogFormat::Json);
+    assert_eq!(1, output.lines().count());
+    let line: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!("handled request", line["message"]);
+    assert_eq!("ok", line["status"]);
+    assert_eq!(1, line["request_id"]);
+    assert_eq!(serde_json::json!(["request"]), line["spans"]);
+    assert!(line["timestamp"].as_str().unwrap().ends_with('Z'));
+}
+
+#[cfg(feature = "redaction")]
+#[test]
+fn test_redacted_span_fields() {
+    let builder = builder().with_redaction(crate::RedactionConfig::new().with_field("token"));
+    let writer = TestWriter::default();
+    // The error layer goes first so it's the one that creates the span's formatted fields
+    let subscriber = tracing_subscriber::registry()
+        .with(builder.get_error_layer())
+        .with(builder.get_layer_for_target(LogTarget::File, writer.clone()));
+    let span_trace = tracing::subscriber::with_default(subscriber, || {
+        let span =
+            tracing::info_span!("request", token = "abc123", session = tracing::field::Empty);
+        let _entered = span.enter();
+        span.record("session", "ok");
+        span.record("token", "def456");
+        tracing::info!("handled request");
+        tracing_error::SpanTrace::capture().to_string()
+    });
+    let output = String::from_utf8(writer.0.lock().unwrap().clone()).unwrap();
+
+    for output in [output, span_trace] {
+        assert!(output.contains("token=[REDACTED]"), "{output}");
+        assert!(output.contains("session=\"ok\""), "{output}");
+        assert!(!output.contains("abc123"), "{output}");
+        assert!(!output.contains("def456"), "{output}");
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use daemon_slayer_core::Label;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
+use opentelemetry_otlp::{LogExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::logs::{SdkLogger, SdkLoggerProvider};
+use opentelemetry_sdk::trace::{SdkTracerProvider, Tracer};
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::LoggerCreationError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    Grpc,
+    Http,
+}
+
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    endpoint: String,
+    protocol: OtlpProtocol,
+    timeout: Duration,
+}
+
+impl OtlpConfig {
+    /// Exports to a collector's gRPC endpoint, usually `http://localhost:4317`.
+    pub fn grpc(endpoint: impl Into<String>) -> Self {
+        Self::new(endpoint.into(), OtlpProtocol::Grpc)
+    }
+
+    /// Exports to a collector's HTTP endpoint, usually `http://localhost:4318`. The `/v1/traces`
+    /// and `/v1/logs` paths are appended automatically.
+    pub fn http(endpoint: impl Into<String>) -> Self {
+        Self::new(endpoint.into(), OtlpProtocol::Http)
+    }
+
+    fn new(endpoint: String, protocol: OtlpProtocol) -> Self {
+        Self {
+            endpoint,
+            protocol,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Maximum time to wait for each export. Defaults to 10 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn signal_endpoint(&self, signal: &str) -> String {
+        match self.protocol {
+            OtlpProtocol::Grpc => self.endpoint.clone(),
+            OtlpProtocol::Http => format!("{}/v1/{signal}", self.endpoint.trim_end_matches('/')),
+        }
+    }
+
+    fn span_exporter(&self) -> Result<SpanExporter, LoggerCreationError> {
+        let endpoint = self.signal_endpoint("traces");
+        match self.protocol {
+            OtlpProtocol::Grpc => SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .with_timeout(self.timeout)
+                .build(),
+            OtlpProtocol::Http => SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .with_timeout(self.timeout)
+                .build(),
+        }
+        .map_err(LoggerCreationError::OtlpFailure)
+    }
+
+    fn log_exporter(&self) -> Result<LogExporter, LoggerCreationError> {
+        let endpoint = self.signal_endpoint("logs");
+        match self.protocol {
+            OtlpProtocol::Grpc => LogExporter::builder()
+                .with_tonic()
+                .with_endpoint(endpoint)
+                .with_timeout(self.timeout)
+                .build(),
+            OtlpProtocol::Http => LogExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .with_timeout(self.timeout)
+                .build(),
+        }
+        .map_err(LoggerCreationError::OtlpFailure)
+    }
+}
+
+/// Flushes any pending spans and logs when the logger guard is dropped.
+pub(crate) struct OtlpGuard {
+    tracer_provider: SdkTracerProvider,
+    logger_provider: SdkLoggerProvider,
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        self.tracer_provider.shutdown().ok();
+        self.logger_provider.shutdown().ok();
+    }
+}
+
+pub(crate) struct OtlpLayers<S> {
+    pub(crate) trace_layer: tracing_opentelemetry::OpenTelemetryLayer<S, Tracer>,
+    pub(crate) log_layer: OpenTelemetryTracingBridge<SdkLoggerProvider, SdkLogger>,
+    pub(crate) guard: OtlpGuard,
+}
+
+impl<S> OtlpLayers<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    // The gRPC exporter needs to be created from within a Tokio runtime
+    pub(crate) fn new(config: &OtlpConfig, label: &Label) -> Result<Self, LoggerCreationError> {
+        let resource = Resource::builder()
+            .with_service_name(label.application.clone())
+            .build();
+
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(config.span_exporter()?)
+            .with_resource(resource.clone())
+            .build();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_batch_exporter(config.log_exporter()?)
+            .with_resource(resource)
+            .build();
+
+        Ok(Self {
+            trace_layer: tracing_opentelemetry::layer()
+                .with_tracer(tracer_provider.tracer(label.qualified_name())),
+            log_layer: OpenTelemetryTracingBridge::new(&logger_provider),
+            guard: OtlpGuard {
+                tracer_provider,
+                logger_provider,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "./otlp_test.rs"]
+mod otlp_test;
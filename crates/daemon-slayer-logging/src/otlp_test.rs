@@ -0,0 +1,147 @@
+use std::net::SocketAddr;
+
+use daemon_slayer_core::Label;
+use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::{
+    LogsService, LogsServiceServer,
+};
+use opentelemetry_proto::tonic::collector::logs::v1::{
+    ExportLogsServiceRequest, ExportLogsServiceResponse,
+};
+use opentelemetry_proto::tonic::collector::trace::v1::trace_service_server::{
+    TraceService, TraceServiceServer,
+};
+use opentelemetry_proto::tonic::collector::trace::v1::{
+    ExportTraceServiceRequest, ExportTraceServiceResponse,
+};
+use opentelemetry_proto::tonic::common::v1::any_value::Value;
+use opentelemetry_proto::tonic::resource::v1::Resource;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::TcpListenerStream;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::{OtlpConfig, OtlpLayers};
+
+struct Collector {
+    traces: mpsc::UnboundedSender<ExportTraceServiceRequest>,
+    logs: mpsc::UnboundedSender<ExportLogsServiceRequest>,
+}
+
+#[async_trait::async_trait]
+impl TraceService for Collector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportTraceServiceRequest>,
+    ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+        self.traces.send(request.into_inner()).ok();
+        Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+    }
+}
+
+#[async_trait::async_trait]
+impl LogsService for Collector {
+    async fn export(
+        &self,
+        request: tonic::Request<ExportLogsServiceRequest>,
+    ) -> Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+        self.logs.send(request.into_inner()).ok();
+        Ok(tonic::Response::new(ExportLogsServiceResponse::default()))
+    }
+}
+
+struct CollectorHandle {
+    addr: SocketAddr,
+    traces: mpsc::UnboundedReceiver<ExportTraceServiceRequest>,
+    logs: mpsc::UnboundedReceiver<ExportLogsServiceRequest>,
+}
+
+async fn start_collector() -> CollectorHandle {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (trace_tx, traces) = mpsc::unbounded_channel();
+    let (log_tx, logs) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(TraceServiceServer::new(Collector {
+                traces: trace_tx.clone(),
+                logs: log_tx.clone(),
+            }))
+            .add_service(LogsServiceServer::new(Collector {
+                traces: trace_tx,
+                logs: log_tx,
+            }))
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+
+    CollectorHandle { addr, traces, logs }
+}
+
+fn service_name(resource: Option<&Resource>) -> Option<String> {
+    resource?
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "service.name")
+        .and_then(|attr| match attr.value.as_ref()?.value.as_ref()? {
+            Value::StringValue(name) => Some(name.clone()),
+            _ => None,
+        })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_export_flushed_on_drop() {
+    let mut collector = start_collector().await;
+    let label: Label = "com.test.otlpapp".parse().unwrap();
+
+    let layers = OtlpLayers::new(
+        &OtlpConfig::grpc(format!("http://{}", collector.addr)),
+        &label,
+    )
+    .unwrap();
+    let subscriber = tracing_subscriber::registry()
+        .with(layers.trace_layer)
+        .with(layers.log_layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("test_span").entered();
+        tracing::info!("test message");
+    });
+
+    // The batch processors only export every few seconds, so anything received at this point
+    // was sent by the guard's shutdown
+    let guard = layers.guard;
+    tokio::task::spawn_blocking(move || drop(guard))
+        .await
+        .unwrap();
+
+    let traces = collector.traces.try_recv().unwrap();
+    let resource_spans = &traces.resource_spans[0];
+    assert_eq!(
+        Some("otlpapp".to_owned()),
+        service_name(resource_spans.resource.as_ref())
+    );
+    let spans: Vec<_> = resource_spans
+        .scope_spans
+        .iter()
+        .flat_map(|scope| &scope.spans)
+        .map(|span| span.name.as_str())
+        .collect();
+    assert_eq!(vec!["test_span"], spans);
+
+    let logs = collector.logs.try_recv().unwrap();
+    let resource_logs = &logs.resource_logs[0];
+    assert_eq!(
+        Some("otlpapp".to_owned()),
+        service_name(resource_logs.resource.as_ref())
+    );
+    let bodies: Vec<_> = resource_logs
+        .scope_logs
+        .iter()
+        .flat_map(|scope| &scope.log_records)
+        .filter_map(|record| match record.body.as_ref()?.value.as_ref()? {
+            Value::StringValue(body) => Some(body.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec!["test message"], bodies);
+}
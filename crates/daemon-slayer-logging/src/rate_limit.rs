@@ -0,0 +1,540 @@
+use std::any::TypeId;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex, OnceLock, Weak, mpsc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tracing::callsite::Identifier;
+use tracing::dispatcher::WeakDispatch;
+use tracing::field::{Field, Visit, display};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Dispatch, Event, Metadata, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::recorded::{Recorded, as_values, with_values};
+
+/// Limits how often events from the same callsite are logged. Spans are not affected.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    duplicate_window: Option<Duration>,
+    burst: u32,
+    per_second: u32,
+}
+
+impl RateLimitConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events that are identical to the previous one from the same callsite are suppressed
+    /// until `window` elapses. The last suppressed event is logged with "message repeated N times"
+    /// once the window ends or the logger is shut down.
+    pub fn with_duplicate_window(mut self, window: Duration) -> Self {
+        self.duplicate_window = Some(window);
+        self
+    }
+
+    /// Allows up to `burst` events from each callsite at once, refilled at a rate of
+    /// `per_second`. The number of dropped events is added to the next event that's allowed.
+    pub fn with_rate_limit(mut self, burst: u32, per_second: u32) -> Self {
+        self.burst = burst;
+        self.per_second = per_second;
+        self
+    }
+
+    fn has_rate_limit(&self) -> bool {
+        self.burst > 0
+    }
+}
+
+struct RecordingVisitor {
+    values: Vec<Option<Recorded>>,
+    message: Option<String>,
+    // Used to tell if two events from the same callsite are identical
+    key: String,
+}
+
+impl RecordingVisitor {
+    fn new(field_count: usize) -> Self {
+        Self {
+            values: (0..field_count).map(|_| None).collect(),
+            message: None,
+            key: String::new(),
+        }
+    }
+
+    fn record(&mut self, field: &Field, value: Recorded, formatted: impl fmt::Display) {
+        write!(self.key, "{}={formatted};", field.name()).ok();
+        if let Some(slot) = self.values.get_mut(field.index()) {
+            *slot = Some(value);
+        }
+    }
+
+    fn record_string(&mut self, field: &Field, value: String, as_str: bool) {
+        if field.name() == "message" {
+            self.message = Some(value.clone());
+        }
+        let formatted = format!("{value:?}");
+        let value = if as_str {
+            Recorded::Str(value)
+        } else {
+            Recorded::Display(display(value))
+        };
+        self.record(field, value, formatted);
+    }
+
+    /// Appends a note to the message. Events without a message are left unchanged.
+    fn annotate(&mut self, metadata: &Metadata<'_>, note: &str) {
+        if let (Some(message), Some(field)) = (&self.message, metadata.fields().field("message")) {
+            self.values[field.index()] =
+                Some(Recorded::Display(display(format!("{message} ({note})"))));
+        }
+    }
+}
+
+impl Visit for RecordingVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, Recorded::F64(value), value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, Recorded::I64(value), value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, Recorded::U64(value), value);
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.record(field, Recorded::I128(value), value);
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.record(field, Recorded::U128(value), value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, Recorded::Bool(value), value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_string(field, value.to_owned(), true);
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_string(field, value.to_string(), false);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_string(field, format!("{value:?}"), false);
+    }
+}
+
+struct Suppressed {
+    metadata: &'static Metadata<'static>,
+    event: RecordingVisitor,
+    repeated: usize,
+}
+
+struct CallsiteState {
+    last_key: Option<String>,
+    window_start: Instant,
+    suppressed: Option<Suppressed>,
+    tokens: f64,
+    last_refill: Instant,
+    dropped: usize,
+}
+
+impl CallsiteState {
+    fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            last_key: None,
+            window_start: now,
+            suppressed: None,
+            tokens: config.burst as f64,
+            last_refill: now,
+            dropped: 0,
+        }
+    }
+
+    fn try_acquire(&mut self, config: &RateLimitConfig, now: Instant) -> bool {
+        if !config.has_rate_limit() {
+            return true;
+        }
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.per_second as f64).min(config.burst as f64);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_summary(&mut self) -> Option<Suppressed> {
+        let mut suppressed = self.suppressed.take()?;
+        let note = format!("message repeated {} times", suppressed.repeated);
+        suppressed.event.annotate(suppressed.metadata, &note);
+        Some(suppressed)
+    }
+}
+
+thread_local! {
+    // Set while a summary is dispatched from outside of a layer callback so only the layer that
+    // suppressed the events writes it
+    static FLUSHING: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+#[derive(Default)]
+struct Callsites {
+    states: HashMap<Identifier, CallsiteState>,
+    // Callsites with suppressed duplicates that haven't been reported yet
+    pending: HashSet<Identifier>,
+}
+
+struct RateLimitState {
+    config: RateLimitConfig,
+    callsites: Mutex<Callsites>,
+    dispatch: OnceLock<WeakDispatch>,
+}
+
+impl RateLimitState {
+    fn id(&self) -> usize {
+        self as *const _ as usize
+    }
+
+    // Returns true if the state has summaries that need to be flushed in the background
+    fn register_dispatch(&self, dispatch: &Dispatch) -> bool {
+        self.config.duplicate_window.is_some() && self.dispatch.set(dispatch.downgrade()).is_ok()
+    }
+
+    fn take_summaries(&self, now: Instant, force: bool) -> Vec<Suppressed> {
+        let Some(window) = self.config.duplicate_window else {
+            return Vec::new();
+        };
+        let mut summaries = Vec::new();
+        let mut callsites = self.callsites.lock().unwrap();
+        let Callsites { states, pending } = &mut *callsites;
+        pending.retain(|callsite| {
+            let Some(state) = states.get_mut(callsite) else {
+                return false;
+            };
+            if force || now.duration_since(state.window_start) >= window {
+                summaries.extend(state.take_summary());
+                false
+            } else {
+                true
+            }
+        });
+        summaries
+    }
+
+    fn flush(&self, force: bool) {
+        let Some(dispatch) = self.dispatch.get().and_then(WeakDispatch::upgrade) else {
+            return;
+        };
+        for summary in self.take_summaries(Instant::now(), force) {
+            let values = as_values(&summary.event.values);
+            let value_set = summary.metadata.fields().value_set_all(&values);
+            FLUSHING.set(Some(self.id()));
+            if dispatch.enabled(summary.metadata) {
+                // The span that the original event was in may have already closed
+                dispatch.event(&Event::new_child_of(None, summary.metadata, &value_set));
+            }
+            FLUSHING.set(None);
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FlushWorker {
+    // The worker stops once this is dropped
+    stop_tx: mpsc::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+/// Reports summaries once the duplicate window ends, even if the callsite has gone quiet. One
+/// worker thread is shared by every target, and any remaining summaries are reported when the
+/// logger is shut down.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SummaryFlusher {
+    states: Arc<Mutex<Vec<Weak<RateLimitState>>>>,
+    worker: Arc<Mutex<Option<FlushWorker>>>,
+}
+
+impl SummaryFlusher {
+    fn register(&self, state: &Arc<RateLimitState>) {
+        let mut states = self.states.lock().unwrap();
+        states.retain(|state| state.strong_count() > 0);
+        states.push(Arc::downgrade(state));
+    }
+
+    fn live_states(states: &Mutex<Vec<Weak<RateLimitState>>>) -> Vec<Arc<RateLimitState>> {
+        states
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    fn start(&self) {
+        let mut worker = self.worker.lock().unwrap();
+        // The worker exits by itself once every target it was flushing is gone
+        if worker
+            .as_ref()
+            .is_some_and(|worker| !worker.handle.is_finished())
+        {
+            return;
+        }
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let states = self.states.clone();
+        let handle = std::thread::Builder::new()
+            .name("rate-limit-flush".to_owned())
+            .spawn(move || {
+                loop {
+                    // Checking at the shortest window keeps summaries from being held for much
+                    // longer than their own window
+                    let Some(interval) = Self::live_states(&states)
+                        .iter()
+                        .filter_map(|state| state.config.duplicate_window)
+                        .min()
+                    else {
+                        return;
+                    };
+                    if let Err(mpsc::RecvTimeoutError::Disconnected) =
+                        stop_rx.recv_timeout(interval)
+                    {
+                        return;
+                    }
+                    for state in Self::live_states(&states) {
+                        state.flush(false);
+                    }
+                }
+            });
+        if let Ok(handle) = handle {
+            *worker = Some(FlushWorker { stop_tx, handle });
+        }
+    }
+
+    fn stop(&self) {
+        let worker = self.worker.lock().unwrap().take();
+        if let Some(FlushWorker { stop_tx, handle }) = worker {
+            drop(stop_tx);
+            handle.join().ok();
+        }
+    }
+
+    pub(crate) fn flush(&self) {
+        for state in Self::live_states(&self.states) {
+            state.flush(true);
+        }
+    }
+
+    pub(crate) fn flush_on_drop(&self) -> FlushOnDrop {
+        FlushOnDrop(self.clone())
+    }
+}
+
+pub(crate) struct FlushOnDrop(SummaryFlusher);
+
+impl Drop for FlushOnDrop {
+    fn drop(&mut self) {
+        // Stopped first so the worker can't report the same summaries
+        self.0.stop();
+        self.0.flush();
+    }
+}
+
+/// Wraps a target's layer so repeated and excessive events are dropped before they're written.
+pub(crate) struct RateLimited<L> {
+    inner: L,
+    state: Option<Arc<RateLimitState>>,
+    flusher: SummaryFlusher,
+}
+
+impl<L> RateLimited<L> {
+    pub(crate) fn new(inner: L, config: Option<RateLimitConfig>, flusher: &SummaryFlusher) -> Self {
+        let state = config.map(|config| {
+            Arc::new(RateLimitState {
+                config,
+                callsites: Mutex::default(),
+                dispatch: OnceLock::new(),
+            })
+        });
+        if let Some(state) = &state {
+            flusher.register(state);
+        }
+        Self {
+            inner,
+            state,
+            flusher: flusher.clone(),
+        }
+    }
+
+    fn emit_summary<S>(&self, summary: Suppressed, ctx: Context<'_, S>)
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+        L: Layer<S>,
+    {
+        let values = as_values(&summary.event.values);
+        let value_set = summary.metadata.fields().value_set_all(&values);
+        // The span that the original event was in may have already closed
+        self.inner.on_event(
+            &Event::new_child_of(None, summary.metadata, &value_set),
+            ctx,
+        );
+    }
+}
+
+impl<S, L> Layer<S> for RateLimited<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &Dispatch) {
+        self.inner.on_register_dispatch(subscriber);
+        if let Some(state) = &self.state
+            && state.register_dispatch(subscriber)
+        {
+            self.flusher.start();
+        }
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_new_span(attrs, id, ctx);
+    }
+
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        self.inner.on_record(span, values, ctx);
+    }
+
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if let Some(flushing) = FLUSHING.get() {
+            if self
+                .state
+                .as_ref()
+                .is_some_and(|state| state.id() == flushing)
+            {
+                self.inner.on_event(event, ctx);
+            }
+            return;
+        }
+        let Some(rate_limit) = &self.state else {
+            return self.inner.on_event(event, ctx);
+        };
+        let config = &rate_limit.config;
+        let metadata = event.metadata();
+        let now = Instant::now();
+        let mut visitor = RecordingVisitor::new(metadata.fields().len());
+        event.record(&mut visitor);
+
+        let mut summary = None;
+        let forward = {
+            let mut callsites = rate_limit.callsites.lock().unwrap();
+            let Callsites { states, pending } = &mut *callsites;
+            let callsite = metadata.callsite();
+            let state = states
+                .entry(callsite.clone())
+                .or_insert_with(|| CallsiteState::new(config, now));
+
+            let is_duplicate = config.duplicate_window.is_some_and(|window| {
+                state.last_key.as_ref() == Some(&visitor.key)
+                    && now.duration_since(state.window_start) < window
+            });
+            if is_duplicate {
+                let repeated = state
+                    .suppressed
+                    .as_ref()
+                    .map(|suppressed| suppressed.repeated)
+                    .unwrap_or_default();
+                state.suppressed = Some(Suppressed {
+                    metadata,
+                    event: visitor,
+                    repeated: repeated + 1,
+                });
+                pending.insert(callsite);
+                None
+            } else {
+                summary = state.take_summary();
+                pending.remove(&callsite);
+                if state.try_acquire(config, now) {
+                    state.last_key = Some(visitor.key.clone());
+                    state.window_start = now;
+                    Some((visitor, std::mem::take(&mut state.dropped)))
+                } else {
+                    state.dropped += 1;
+                    None
+                }
+            }
+        };
+
+        if let Some(summary) = summary {
+            self.emit_summary(summary, ctx.clone());
+        }
+        match forward {
+            Some((_, 0)) => self.inner.on_event(event, ctx),
+            Some((mut visitor, dropped)) => {
+                visitor.annotate(metadata, &format!("{dropped} events dropped by rate limit"));
+                with_values(event, &visitor.values, |event| {
+                    self.inner.on_event(event, ctx)
+                });
+            }
+            None => {}
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const _ as *const ())
+        } else {
+            unsafe { self.inner.downcast_raw(id) }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./rate_limit_test.rs"]
+mod rate_limit_test;
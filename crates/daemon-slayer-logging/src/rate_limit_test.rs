@@ -0,0 +1,251 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::{Dispatch, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+
+use super::{CallsiteState, RateLimitConfig, RateLimited, SummaryFlusher};
+
+#[derive(Clone, Default)]
+struct TestWriter(Arc<Mutex<Vec<u8>>>);
+
+impl TestWriter {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+}
+
+impl io::Write for TestWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for TestWriter {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn fmt_layer<S>(writer: TestWriter) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_target(false)
+        .with_level(false)
+        .without_time()
+}
+
+struct TestLogger {
+    dispatch: Dispatch,
+    flusher: SummaryFlusher,
+    limited: TestWriter,
+    unlimited: TestWriter,
+}
+
+// Events go to one layer with the rate limit and one without it
+fn logger(config: RateLimitConfig) -> TestLogger {
+    let flusher = SummaryFlusher::default();
+    let limited = TestWriter::default();
+    let unlimited = TestWriter::default();
+    let dispatch = Dispatch::new(
+        tracing_subscriber::registry()
+            .with(RateLimited::new(
+                fmt_layer(limited.clone()),
+                Some(config),
+                &flusher,
+            ))
+            .with(RateLimited::new(
+                fmt_layer(unlimited.clone()),
+                None,
+                &flusher,
+            )),
+    );
+    TestLogger {
+        dispatch,
+        flusher,
+        limited,
+        unlimited,
+    }
+}
+
+impl TestLogger {
+    fn log(&self, statuses: &[&str]) {
+        tracing::dispatcher::with_default(&self.dispatch, || {
+            for status in statuses {
+                tracing::warn!(status, "connection");
+            }
+        });
+    }
+}
+
+#[test]
+fn test_token_bucket() {
+    let config = RateLimitConfig::new().with_rate_limit(2, 10);
+    let now = Instant::now();
+    let mut state = CallsiteState::new(&config, now);
+    assert!(state.try_acquire(&config, now));
+    assert!(state.try_acquire(&config, now));
+    assert!(!state.try_acquire(&config, now));
+
+    let now = now + Duration::from_millis(100);
+    assert!(state.try_acquire(&config, now));
+    assert!(!state.try_acquire(&config, now));
+
+    // Tokens are capped at the burst size
+    let now = now + Duration::from_secs(10);
+    assert!(state.try_acquire(&config, now));
+    assert!(state.try_acquire(&config, now));
+    assert!(!state.try_acquire(&config, now));
+}
+
+#[test]
+fn test_no_rate_limit() {
+    let config = RateLimitConfig::new();
+    let now = Instant::now();
+    let mut state = CallsiteState::new(&config, now);
+    assert!((0..100).all(|_| state.try_acquire(&config, now)));
+}
+
+#[test]
+fn test_dropped_events_reported() {
+    let logger = logger(RateLimitConfig::new().with_rate_limit(2, 20));
+    logger.log(&["1", "2", "3", "4", "5"]);
+    assert_eq!(2, logger.limited.lines().len());
+
+    std::thread::sleep(Duration::from_millis(100));
+    logger.log(&["6"]);
+    assert_eq!(
+        "connection (3 events dropped by rate limit) status=\"6\"",
+        logger.limited.lines()[2].trim()
+    );
+    assert_eq!(6, logger.unlimited.lines().len());
+}
+
+#[test]
+fn test_duplicates_suppressed() {
+    let logger = logger(RateLimitConfig::new().with_duplicate_window(Duration::from_secs(3600)));
+    logger.log(&["down", "down", "down", "up", "up"]);
+    assert_eq!(
+        vec![
+            "connection status=\"down\"",
+            "connection (message repeated 2 times) status=\"down\"",
+            "connection status=\"up\"",
+        ],
+        logger
+            .limited
+            .lines()
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(5, logger.unlimited.lines().len());
+}
+
+#[test]
+fn test_summary_flushed_after_window() {
+    let logger = logger(RateLimitConfig::new().with_duplicate_window(Duration::from_millis(50)));
+    logger.log(&["down", "down", "down"]);
+    assert_eq!(1, logger.limited.lines().len());
+
+    // No more events are logged, so the summary has to come from the background flush
+    let start = Instant::now();
+    while logger.limited.lines().len() < 2 && start.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(
+        "connection (message repeated 2 times) status=\"down\"",
+        logger.limited.lines()[1].trim()
+    );
+    assert_eq!(3, logger.unlimited.lines().len());
+}
+
+#[test]
+fn test_summary_flushed_on_shutdown() {
+    let logger = logger(RateLimitConfig::new().with_duplicate_window(Duration::from_secs(3600)));
+    logger.log(&["down", "down"]);
+    assert_eq!(1, logger.limited.lines().len());
+
+    assert!(logger.flusher.worker.lock().unwrap().is_some());
+    drop(logger.flusher.flush_on_drop());
+    assert!(logger.flusher.worker.lock().unwrap().is_none());
+    assert_eq!(
+        "connection (message repeated 1 times) status=\"down\"",
+        logger.limited.lines()[1].trim()
+    );
+    assert_eq!(2, logger.unlimited.lines().len());
+
+    // Nothing is left to report
+    logger.flusher.flush();
+    assert_eq!(2, logger.limited.lines().len());
+}
+
+#[test]
+fn test_flush_worker_shared() {
+    let flusher = SummaryFlusher::default();
+    let first = TestWriter::default();
+    let second = TestWriter::default();
+    let window = RateLimitConfig::new().with_duplicate_window(Duration::from_millis(50));
+    let dispatch = Dispatch::new(
+        tracing_subscriber::registry()
+            .with(RateLimited::new(
+                fmt_layer(first.clone()),
+                Some(window.clone()),
+                &flusher,
+            ))
+            .with(RateLimited::new(
+                fmt_layer(second.clone()),
+                Some(window),
+                &flusher,
+            )),
+    );
+    let thread_id = flusher
+        .worker
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|worker| worker.handle.thread().id());
+    assert!(thread_id.is_some());
+
+    tracing::dispatcher::with_default(&dispatch, || {
+        for _ in 0..2 {
+            tracing::warn!(status = "down", "connection");
+        }
+    });
+    let start = Instant::now();
+    while (first.lines().len() < 2 || second.lines().len() < 2)
+        && start.elapsed() < Duration::from_secs(5)
+    {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(2, first.lines().len());
+    assert_eq!(2, second.lines().len());
+    // Registering the second target didn't replace the worker
+    assert_eq!(
+        thread_id,
+        flusher
+            .worker
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|worker| worker.handle.thread().id())
+    );
+}
@@ -0,0 +1,53 @@
+use tracing::Event;
+use tracing::field::{DisplayValue, Value};
+
+/// An owned copy of a field value so events can be rebuilt with modified fields.
+pub(crate) enum Recorded {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    // Debug values are already formatted so they're displayed as-is to avoid adding quotes
+    Display(DisplayValue<String>),
+}
+
+impl Recorded {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Recorded::I64(value) => value,
+            Recorded::U64(value) => value,
+            Recorded::I128(value) => value,
+            Recorded::U128(value) => value,
+            Recorded::F64(value) => value,
+            Recorded::Bool(value) => value,
+            Recorded::Str(value) => value,
+            Recorded::Display(value) => value,
+        }
+    }
+}
+
+pub(crate) fn as_values(values: &[Option<Recorded>]) -> Vec<Option<&dyn Value>> {
+    values
+        .iter()
+        .map(|value| value.as_ref().map(Recorded::as_value))
+        .collect()
+}
+
+/// Calls `f` with a copy of `event` that uses the given values instead.
+pub(crate) fn with_values(
+    event: &Event<'_>,
+    values: &[Option<Recorded>],
+    f: impl FnOnce(&Event<'_>),
+) {
+    let values = as_values(values);
+    let value_set = event.metadata().fields().value_set_all(&values);
+    let event = match event.parent() {
+        Some(parent) => Event::new_child_of(parent.clone(), event.metadata(), &value_set),
+        None if event.is_root() => Event::new_child_of(None, event.metadata(), &value_set),
+        None => Event::new(event.metadata(), &value_set),
+    };
+    f(&event);
+}
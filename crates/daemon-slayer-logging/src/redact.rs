@@ -0,0 +1,297 @@
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use regex::Regex;
+use tracing::field::{Field, Visit, display};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Dispatch, Event, Metadata, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::recorded::{Recorded, as_values, with_values};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Masks sensitive values before they reach any of the log targets. Field names are matched
+/// case-insensitively and their values are always replaced. Patterns are matched against string
+/// and debug-formatted values, including the message. If a pattern contains a capture group named
+/// `secret`, only that group is replaced.
+#[derive(Debug, Clone, Default)]
+pub struct RedactionConfig {
+    fields: HashSet<String>,
+    patterns: Vec<Regex>,
+}
+
+impl RedactionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_field(mut self, name: impl Into<String>) -> Self {
+        self.fields.insert(name.into().to_lowercase());
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: Regex) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Redacts common secrets such as bearer tokens and `password=...` pairs.
+    pub fn with_default_patterns(self) -> Self {
+        self.with_field("password")
+            .with_field("token")
+            .with_field("secret")
+            .with_field("authorization")
+            .with_pattern(Regex::new(r"(?i)\bbearer\s+(?<secret>[A-Za-z0-9\-._~+/]+=*)").unwrap())
+            .with_pattern(
+                Regex::new(
+                    r#"(?i)\b(?:password|passwd|pwd|secret|token|api[_-]?key)["']?\s*[=:]\s*["']?(?<secret>[^\s"',;&]+)"#,
+                )
+                .unwrap(),
+            )
+    }
+
+    fn redact_field(&self, field: &Field) -> bool {
+        self.fields.contains(&field.name().to_lowercase())
+    }
+
+    fn redact_value(&self, value: &str) -> Option<String> {
+        let mut ranges: Vec<_> = self
+            .patterns
+            .iter()
+            .flat_map(|pattern| {
+                pattern.captures_iter(value).filter_map(|captures| {
+                    captures
+                        .name("secret")
+                        .or_else(|| captures.get(0))
+                        .map(|secret| (secret.start(), secret.end()))
+                })
+            })
+            .collect();
+        if ranges.is_empty() {
+            return None;
+        }
+        ranges.sort();
+
+        let mut redacted = String::with_capacity(value.len());
+        let mut position = 0;
+        for (start, end) in ranges {
+            // Matches from different patterns may overlap
+            if end <= position {
+                continue;
+            }
+            redacted.push_str(&value[position..start.max(position)]);
+            redacted.push_str(REDACTED);
+            position = end;
+        }
+        redacted.push_str(&value[position..]);
+        Some(redacted)
+    }
+}
+
+struct RedactingVisitor<'a> {
+    config: &'a RedactionConfig,
+    values: Vec<Option<Recorded>>,
+    redacted: bool,
+}
+
+impl<'a> RedactingVisitor<'a> {
+    fn new(config: &'a RedactionConfig, field_count: usize) -> Self {
+        Self {
+            config,
+            values: (0..field_count).map(|_| None).collect(),
+            redacted: false,
+        }
+    }
+
+    fn record(&mut self, field: &Field, value: Recorded) {
+        let value = if self.config.redact_field(field) {
+            self.redacted = true;
+            Recorded::Display(display(REDACTED.to_owned()))
+        } else {
+            value
+        };
+        if let Some(slot) = self.values.get_mut(field.index()) {
+            *slot = Some(value);
+        }
+    }
+
+    fn record_string(&mut self, field: &Field, value: String, as_str: bool) {
+        let value = match self.config.redact_value(&value) {
+            Some(redacted) => {
+                self.redacted = true;
+                redacted
+            }
+            None => value,
+        };
+        self.record(
+            field,
+            if as_str {
+                Recorded::Str(value)
+            } else {
+                Recorded::Display(display(value))
+            },
+        );
+    }
+}
+
+impl Visit for RedactingVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, Recorded::F64(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, Recorded::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, Recorded::U64(value));
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.record(field, Recorded::I128(value));
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.record(field, Recorded::U128(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, Recorded::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_string(field, value.to_owned(), true);
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.record_string(field, value.to_string(), false);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_string(field, format!("{value:?}"), false);
+    }
+}
+
+/// Wraps a target's layer so it only sees redacted field values.
+pub(crate) struct Redacted<L> {
+    inner: L,
+    config: Option<Arc<RedactionConfig>>,
+}
+
+impl<L> Redacted<L> {
+    pub(crate) fn new(inner: L, config: Option<Arc<RedactionConfig>>) -> Self {
+        Self { inner, config }
+    }
+
+    /// Returns `None` if nothing needed to be redacted so the original values can be used.
+    fn visit(
+        &self,
+        metadata: &Metadata<'_>,
+        record: impl FnOnce(&mut dyn Visit),
+    ) -> Option<Vec<Option<Recorded>>> {
+        let config = self.config.as_ref()?;
+        let mut visitor = RedactingVisitor::new(config, metadata.fields().len());
+        record(&mut visitor);
+        visitor.redacted.then_some(visitor.values)
+    }
+}
+
+impl<S, L> Layer<S> for Redacted<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &Dispatch) {
+        self.inner.on_register_dispatch(subscriber);
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber);
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        match self.visit(attrs.metadata(), |visitor| attrs.record(visitor)) {
+            Some(values) => {
+                let values = as_values(&values);
+                let value_set = attrs.metadata().fields().value_set_all(&values);
+                let attrs = match attrs.parent() {
+                    Some(parent) => {
+                        Attributes::child_of(parent.clone(), attrs.metadata(), &value_set)
+                    }
+                    None if attrs.is_root() => Attributes::new_root(attrs.metadata(), &value_set),
+                    None => Attributes::new(attrs.metadata(), &value_set),
+                };
+                self.inner.on_new_span(&attrs, id, ctx);
+            }
+            None => self.inner.on_new_span(attrs, id, ctx),
+        }
+    }
+
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(metadata) = ctx.metadata(span) else {
+            return self.inner.on_record(span, values, ctx);
+        };
+        match self.visit(metadata, |visitor| values.record(visitor)) {
+            Some(redacted) => {
+                let redacted = as_values(&redacted);
+                let value_set = metadata.fields().value_set_all(&redacted);
+                self.inner.on_record(span, &Record::new(&value_set), ctx);
+            }
+            None => self.inner.on_record(span, values, ctx),
+        }
+    }
+
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx);
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        match self.visit(event.metadata(), |visitor| event.record(visitor)) {
+            Some(values) => with_values(event, &values, |event| self.inner.on_event(event, ctx)),
+            None => self.inner.on_event(event, ctx),
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx);
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx);
+    }
+
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            Some(self as *const _ as *const ())
+        } else {
+            // Some layers, such as the OpenTelemetry layer, look themselves up by type
+            unsafe { self.inner.downcast_raw(id) }
+        }
+    }
+}
@@ -1,20 +1,159 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use tokio_util::future::FutureExt;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
 use tracing::metadata::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::ParseError;
+
+use crate::target_filters::TargetFilters;
+use crate::{LogFilter, LogTarget, UserConfig};
+
+type ReloadFn<T> = Box<dyn Fn(T) + Send + Sync>;
+
+struct ReloadState {
+    log_level: Option<LevelFilter>,
+    log_filters: HashMap<LogTarget, LogFilter>,
+    runtime_filter: Option<LogFilter>,
+    // Cancelled when the runtime filter is replaced so the pending revert doesn't remove the new one
+    revert: Option<CancellationToken>,
+}
 
 #[derive(Clone)]
 pub struct ReloadHandle {
-    reload_fn: Arc<dyn Fn(LevelFilter) + Send + Sync>,
+    filters: Arc<TargetFilters>,
+    state: Arc<Mutex<ReloadState>>,
+    target_reload_fns: Arc<Vec<(LogTarget, ReloadFn<EnvFilter>)>>,
+    global_reload_fn: Arc<ReloadFn<LevelFilter>>,
 }
 
 impl ReloadHandle {
-    pub(crate) fn new(reload_fn: impl Fn(LevelFilter) + Send + Sync + 'static) -> Self {
+    pub(crate) fn new(
+        filters: TargetFilters,
+        user_config: Option<&UserConfig>,
+        target_reload_fns: Vec<(LogTarget, ReloadFn<EnvFilter>)>,
+        global_reload_fn: ReloadFn<LevelFilter>,
+    ) -> Self {
         Self {
-            reload_fn: Arc::new(reload_fn),
+            filters: Arc::new(filters),
+            state: Arc::new(Mutex::new(ReloadState {
+                log_level: user_config.map(|config| config.log_level.to_level_filter()),
+                log_filters: user_config
+                    .map(|config| config.log_filters.clone())
+                    .unwrap_or_default(),
+                runtime_filter: None,
+                revert: None,
+            })),
+            target_reload_fns: Arc::new(target_reload_fns),
+            global_reload_fn: Arc::new(global_reload_fn),
         }
     }
 
     pub fn update_log_level(&self, new_level: LevelFilter) {
-        (self.reload_fn)(new_level);
+        let mut state = self.state.lock().unwrap();
+        state.log_level = Some(new_level);
+        self.apply(&state);
+    }
+
+    /// Replaces the filter directives for each target. Targets that aren't included are reset to
+    /// the log level.
+    pub fn update_log_filters(&self, log_filters: HashMap<LogTarget, LogFilter>) {
+        let mut state = self.state.lock().unwrap();
+        state.log_filters = log_filters;
+        self.apply(&state);
+    }
+
+    pub fn update(&self, user_config: &UserConfig) {
+        let mut state = self.state.lock().unwrap();
+        state.log_level = Some(user_config.log_level.to_level_filter());
+        state.log_filters = user_config.log_filters.clone();
+        self.apply(&state);
+    }
+
+    /// Applies directives to every target on top of the configured ones, such as a level change
+    /// requested from the CLI. If `revert_after` is set, the directives are removed once it
+    /// elapses, which must be scheduled from within a Tokio runtime.
+    pub fn set_runtime_filter(
+        &self,
+        directives: &str,
+        revert_after: Option<Duration>,
+    ) -> Result<(), ParseError> {
+        let runtime_filter: LogFilter = directives.parse()?;
+        let mut state = self.state.lock().unwrap();
+        state.runtime_filter = Some(runtime_filter);
+        if let Some(revert) = state.revert.take() {
+            revert.cancel();
+        }
+        self.apply(&state);
+
+        if let Some(revert_after) = revert_after {
+            let revert = CancellationToken::new();
+            state.revert = Some(revert.clone());
+            let handle = self.clone();
+            tokio::spawn(async move {
+                if tokio::time::sleep(revert_after)
+                    .with_cancellation_token(&revert)
+                    .await
+                    .is_none()
+                {
+                    return;
+                }
+                let mut state = handle.state.lock().unwrap();
+                // The token is cancelled while holding the lock, so this can't race with a new
+                // filter being set
+                if !revert.is_cancelled() {
+                    state.runtime_filter = None;
+                    state.revert = None;
+                    handle.apply(&state);
+                    info!("Reverted log filter directives");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    pub fn clear_runtime_filter(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.runtime_filter = None;
+        if let Some(revert) = state.revert.take() {
+            revert.cancel();
+        }
+        self.apply(&state);
+    }
+
+    pub(crate) fn reload(&self) {
+        self.apply(&self.state.lock().unwrap());
+    }
+
+    fn apply(&self, state: &ReloadState) {
+        let env_filters: Vec<_> = self
+            .target_reload_fns
+            .iter()
+            .map(|(target, _)| {
+                self.filters.for_target(
+                    target,
+                    state.log_level,
+                    &state.log_filters,
+                    state.runtime_filter.as_ref(),
+                )
+            })
+            .collect();
+        let global_level = self.filters.global_level(
+            &env_filters,
+            state.log_level,
+            &state.log_filters,
+            state.runtime_filter.as_ref(),
+        );
+        for ((_, reload_fn), env_filter) in self.target_reload_fns.iter().zip(env_filters) {
+            reload_fn(env_filter);
+        }
+        (self.global_reload_fn)(global_level);
     }
 }
+
+#[cfg(test)]
+#[path = "./reload_handle_test.rs"]
+mod reload_handle_test;
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tracing::Level;
+use tracing::metadata::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::prelude::*;
+
+use super::ReloadHandle;
+use crate::target_filters::TargetFilters;
+use crate::{LogFilter, LogLevel, LogTarget, UserConfig};
+
+#[derive(Clone, Default)]
+struct Reloaded {
+    filters: Arc<Mutex<HashMap<LogTarget, EnvFilter>>>,
+    global: Arc<Mutex<Option<LevelFilter>>>,
+}
+
+impl Reloaded {
+    fn handle(&self, user_config: Option<&UserConfig>) -> ReloadHandle {
+        let reload_fns = [LogTarget::File, LogTarget::Stdout]
+            .into_iter()
+            .map(|target| {
+                let filters = self.filters.clone();
+                let reload_target = target.clone();
+                let reload_fn: Box<dyn Fn(EnvFilter) + Send + Sync> = Box::new(move |filter| {
+                    filters
+                        .lock()
+                        .unwrap()
+                        .insert(reload_target.clone(), filter);
+                });
+                (target, reload_fn)
+            })
+            .collect();
+        let global = self.global.clone();
+        let handle = ReloadHandle::new(
+            TargetFilters::default(),
+            user_config,
+            reload_fns,
+            Box::new(move |level| {
+                *global.lock().unwrap() = Some(level);
+            }),
+        );
+        handle.reload();
+        handle
+    }
+
+    // Returns the test callsites that make it through the target's current filter
+    fn enabled(&self, target: LogTarget) -> Vec<&'static str> {
+        let filter = self.filters.lock().unwrap().remove(&target).unwrap();
+        let mut enabled = Vec::new();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(filter), || {
+            if tracing::enabled!(target: "app", Level::INFO) {
+                enabled.push("app=info");
+            }
+            if tracing::enabled!(target: "app", Level::DEBUG) {
+                enabled.push("app=debug");
+            }
+            if tracing::enabled!(target: "app::db", Level::TRACE) {
+                enabled.push("app::db=trace");
+            }
+        });
+        enabled
+    }
+
+    fn global(&self) -> LevelFilter {
+        self.global.lock().unwrap().unwrap()
+    }
+}
+
+fn user_config(log_level: Level, log_filters: &[(LogTarget, &str)]) -> UserConfig {
+    UserConfig {
+        log_level: LogLevel(log_level),
+        log_filters: log_filters
+            .iter()
+            .map(|(target, filter)| (target.clone(), filter.parse::<LogFilter>().unwrap()))
+            .collect(),
+        log_formats: HashMap::new(),
+    }
+}
+
+#[test]
+fn test_initial_config() {
+    let reloaded = Reloaded::default();
+    let config = user_config(Level::INFO, &[(LogTarget::File, "app::db=trace")]);
+    reloaded.handle(Some(&config));
+
+    assert_eq!(
+        vec!["app=info", "app::db=trace"],
+        reloaded.enabled(LogTarget::File)
+    );
+    assert_eq!(vec!["app=info"], reloaded.enabled(LogTarget::Stdout));
+    assert_eq!(LevelFilter::TRACE, reloaded.global());
+}
+
+#[test]
+fn test_update_replaces_filters() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(
+        Level::INFO,
+        &[(LogTarget::File, "app::db=trace")],
+    )));
+
+    handle.update(&user_config(
+        Level::INFO,
+        &[(LogTarget::Stdout, "app=debug")],
+    ));
+
+    assert_eq!(vec!["app=info"], reloaded.enabled(LogTarget::File));
+    assert_eq!(
+        vec!["app=info", "app=debug"],
+        reloaded.enabled(LogTarget::Stdout)
+    );
+    assert_eq!(LevelFilter::DEBUG, reloaded.global());
+}
+
+#[test]
+fn test_update_log_level_keeps_filters() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(
+        Level::INFO,
+        &[(LogTarget::File, "app::db=trace")],
+    )));
+
+    handle.update_log_level(LevelFilter::WARN);
+
+    assert_eq!(vec!["app::db=trace"], reloaded.enabled(LogTarget::File));
+    assert!(reloaded.enabled(LogTarget::Stdout).is_empty());
+}
+
+#[test]
+fn test_invalid_runtime_filter_rejected() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(Level::INFO, &[])));
+    handle.set_runtime_filter("app=debug", None).unwrap();
+
+    assert!(handle.set_runtime_filter("app=loud", None).is_err());
+
+    handle.reload();
+    assert_eq!(
+        vec!["app=info", "app=debug"],
+        reloaded.enabled(LogTarget::File)
+    );
+}
+
+#[test]
+fn test_clear_runtime_filter() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(Level::INFO, &[])));
+
+    handle.set_runtime_filter("app=debug", None).unwrap();
+    assert_eq!(
+        vec!["app=info", "app=debug"],
+        reloaded.enabled(LogTarget::File)
+    );
+
+    handle.clear_runtime_filter();
+    assert_eq!(vec!["app=info"], reloaded.enabled(LogTarget::File));
+    assert_eq!(LevelFilter::INFO, reloaded.global());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_runtime_filter_reverted() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(Level::INFO, &[])));
+
+    handle
+        .set_runtime_filter("app=debug", Some(Duration::from_secs(60)))
+        .unwrap();
+    assert_eq!(LevelFilter::DEBUG, reloaded.global());
+
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    assert_eq!(vec!["app=info"], reloaded.enabled(LogTarget::File));
+    assert_eq!(LevelFilter::INFO, reloaded.global());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_replaced_runtime_filter_not_reverted() {
+    let reloaded = Reloaded::default();
+    let handle = reloaded.handle(Some(&user_config(Level::INFO, &[])));
+
+    handle
+        .set_runtime_filter("app=debug", Some(Duration::from_secs(60)))
+        .unwrap();
+    handle
+        .set_runtime_filter("app::db=trace", Some(Duration::from_secs(120)))
+        .unwrap();
+
+    // Only the revert for the current filter is still pending
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    assert_eq!(
+        vec!["app=info", "app::db=trace"],
+        reloaded.enabled(LogTarget::File)
+    );
+
+    tokio::time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(vec!["app=info"], reloaded.enabled(LogTarget::File));
+}
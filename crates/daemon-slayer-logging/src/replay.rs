@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::stream::{SplitSink, SplitStream};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tokio_util::bytes::Bytes;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::fmt::MakeWriter;
+
+// Replaying is abandoned after this long so a stalled client can't hold up its own live events
+// forever
+const REPLAY_TIMEOUT: Duration = Duration::from_secs(5);
+// Live events that can queue up for a client before it starts missing them
+const LIVE_EVENT_LIMIT: usize = 1024;
+
+type SequencedEvent = (u64, Bytes);
+
+/// Keeps the most recent events written to the IPC target so they can be sent to clients that
+/// connect later.
+#[derive(Clone)]
+pub(crate) struct ReplayBuffer {
+    inner: Arc<Mutex<ReplayBufferInner>>,
+    capacity: usize,
+}
+
+#[derive(Default)]
+struct ReplayBufferInner {
+    events: VecDeque<SequencedEvent>,
+    next_seq: u64,
+    subscribers: Vec<mpsc::Sender<SequencedEvent>>,
+}
+
+impl ReplayBuffer {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ReplayBufferInner {
+                events: VecDeque::with_capacity(capacity),
+                ..Default::default()
+            })),
+            capacity,
+        }
+    }
+
+    fn push(&self, event: Bytes) {
+        let mut inner = self.inner.lock().unwrap();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        // Clients that fall too far behind miss events rather than blocking the logger
+        inner.subscribers.retain(|subscriber| {
+            !matches!(
+                subscriber.try_send((seq, event.clone())),
+                Err(mpsc::error::TrySendError::Closed(_))
+            )
+        });
+        if self.capacity == 0 {
+            return;
+        }
+        if inner.events.len() == self.capacity {
+            inner.events.pop_front();
+        }
+        inner.events.push_back((seq, event));
+    }
+
+    fn subscribe(&self) -> mpsc::Receiver<SequencedEvent> {
+        let (tx, rx) = mpsc::channel(LIVE_EVENT_LIMIT);
+        self.inner.lock().unwrap().subscribers.push(tx);
+        rx
+    }
+
+    fn snapshot(&self) -> Vec<SequencedEvent> {
+        self.inner.lock().unwrap().events.iter().cloned().collect()
+    }
+
+    /// Sends the buffered events to each new connection before it starts receiving live events.
+    ///
+    /// Events are delivered to the connection from a background task, so the connection that's
+    /// handed off only passes through incoming messages and reports when the client is gone.
+    pub(crate) fn replay_on_connect<S, C, E>(
+        &self,
+        incoming: S,
+    ) -> impl Stream<Item = Result<ReplayConnection<C>, E>> + Send + Unpin + use<S, C, E>
+    where
+        S: Stream<Item = Result<C, E>> + Send + 'static,
+        C: Sink<Bytes, Error = io::Error> + Stream + Send + 'static,
+        E: Send + 'static,
+    {
+        let buffer = self.clone();
+        Box::pin(incoming.map(move |connection| Ok(buffer.connect(connection?))))
+    }
+
+    fn connect<C>(&self, connection: C) -> ReplayConnection<C>
+    where
+        C: Sink<Bytes, Error = io::Error> + Stream + Send + 'static,
+    {
+        // Subscribing first means nothing logged while the snapshot is taken can be missed.
+        // Anything that ends up in both is skipped by its sequence number.
+        let live = self.subscribe();
+        let replay = self.snapshot();
+        let (sink, stream) = connection.split();
+        let token = CancellationToken::new();
+        let task_token = token.clone();
+        tokio::spawn(async move {
+            task_token
+                .run_until_cancelled(forward_events(sink, replay, live))
+                .await;
+            // Lets the server know the client is gone
+            task_token.cancel();
+        });
+        ReplayConnection { stream, token }
+    }
+}
+
+// Runs until the client disconnects or the buffer is dropped
+async fn forward_events<C>(
+    mut sink: SplitSink<C, Bytes>,
+    replay: Vec<SequencedEvent>,
+    mut live: mpsc::Receiver<SequencedEvent>,
+) where
+    C: Sink<Bytes, Error = io::Error>,
+{
+    let last_replayed = replay.last().map(|(seq, _)| *seq);
+    let mut replay = futures::stream::iter(replay.into_iter().map(|(_, event)| Ok(event)));
+    if let Ok(Err(_)) = tokio::time::timeout(REPLAY_TIMEOUT, sink.send_all(&mut replay)).await {
+        return;
+    }
+    while let Some((seq, event)) = live.recv().await {
+        if last_replayed.is_some_and(|last_replayed| seq <= last_replayed) {
+            continue;
+        }
+        if sink.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Connection that's handed off to the IPC server. Events written by the server are discarded
+/// since the replay task is the one sending them to the client.
+pub(crate) struct ReplayConnection<C> {
+    stream: SplitStream<C>,
+    token: CancellationToken,
+}
+
+impl<C> ReplayConnection<C> {
+    fn check_open(&self) -> io::Result<()> {
+        if self.token.is_cancelled() {
+            Err(io::ErrorKind::BrokenPipe.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C> Drop for ReplayConnection<C> {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+impl<C: Stream> Stream for ReplayConnection<C> {
+    type Item = C::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.poll_next_unpin(cx)
+    }
+}
+
+impl<C> Sink<Bytes> for ReplayConnection<C> {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.check_open())
+    }
+
+    fn start_send(self: Pin<&mut Self>, _item: Bytes) -> io::Result<()> {
+        self.check_open()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.check_open())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.token.cancel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+pub(crate) struct ReplayWriter<W> {
+    inner: W,
+    buffer: ReplayBuffer,
+}
+
+impl<W> ReplayWriter<W> {
+    pub(crate) fn new(inner: W, buffer: ReplayBuffer) -> Self {
+        Self { inner, buffer }
+    }
+}
+
+impl<'a, W> MakeWriter<'a> for ReplayWriter<W>
+where
+    W: MakeWriter<'a>,
+{
+    type Writer = ReplayEventWriter<W::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ReplayEventWriter {
+            inner: self.inner.make_writer(),
+            buffer: self.buffer.clone(),
+            event: Vec::new(),
+        }
+    }
+}
+
+/// A writer is created for each event, so the event is buffered once the writer is dropped.
+pub(crate) struct ReplayEventWriter<W> {
+    inner: W,
+    buffer: ReplayBuffer,
+    event: Vec<u8>,
+}
+
+impl<W: io::Write> io::Write for ReplayEventWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The event is kept even if there are no clients to write it to yet
+        match self.inner.write(buf) {
+            Ok(written) => {
+                self.event.extend_from_slice(&buf[..written]);
+                Ok(written)
+            }
+            Err(e) => {
+                self.event.extend_from_slice(buf);
+                Err(e)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W> Drop for ReplayEventWriter<W> {
+    fn drop(&mut self) {
+        if !self.event.is_empty() {
+            self.buffer.push(std::mem::take(&mut self.event).into());
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./replay_test.rs"]
+mod replay_test;
@@ -0,0 +1,133 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use tokio_util::bytes::Bytes;
+
+use super::{ReplayBuffer, forward_events};
+
+struct TestConnection {
+    tx: mpsc::UnboundedSender<Bytes>,
+    stalled: bool,
+}
+
+fn connection(stalled: bool) -> (TestConnection, mpsc::UnboundedReceiver<Bytes>) {
+    let (tx, rx) = mpsc::unbounded();
+    (TestConnection { tx, stalled }, rx)
+}
+
+impl Sink<Bytes> for TestConnection {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.stalled {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> io::Result<()> {
+        self.tx
+            .unbounded_send(item)
+            .map_err(|_| io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Stream for TestConnection {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Pending
+    }
+}
+
+async fn received(rx: &mut mpsc::UnboundedReceiver<Bytes>, count: usize) -> Vec<Bytes> {
+    tokio::time::timeout(Duration::from_secs(5), rx.take(count).collect())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_replay_then_live_events() {
+    let buffer = ReplayBuffer::new(2);
+    buffer.push("one".into());
+    buffer.push("two".into());
+    buffer.push("three".into());
+
+    let (test_connection, mut rx) = connection(false);
+    let mut incoming =
+        buffer.replay_on_connect(futures::stream::iter([Ok::<_, ()>(test_connection)]));
+    let _connection = incoming.next().await.unwrap().unwrap();
+    buffer.push("four".into());
+
+    assert_eq!(vec!["two", "three", "four"], received(&mut rx, 3).await);
+}
+
+#[tokio::test]
+async fn test_replayed_events_not_repeated() {
+    let (test_connection, mut rx) = connection(false);
+    let (sink, _stream) = test_connection.split();
+    let (live_tx, live_rx) = tokio::sync::mpsc::channel(4);
+    // Events logged between subscribing and taking the snapshot show up in both
+    live_tx.send((1, "two".into())).await.unwrap();
+    live_tx.send((2, "three".into())).await.unwrap();
+    drop(live_tx);
+
+    forward_events(sink, vec![(0, "one".into()), (1, "two".into())], live_rx).await;
+
+    assert_eq!(vec!["one", "two", "three"], received(&mut rx, 3).await);
+}
+
+#[tokio::test]
+async fn test_stalled_client() {
+    let buffer = ReplayBuffer::new(2);
+    buffer.push("one".into());
+
+    let (stalled, _stalled_rx) = connection(true);
+    let (test_connection, mut rx) = connection(false);
+    let mut incoming = buffer.replay_on_connect(futures::stream::iter([
+        Ok::<_, ()>(stalled),
+        Ok(test_connection),
+    ]));
+    let _stalled = incoming.next().await.unwrap().unwrap();
+    let _connection = incoming.next().await.unwrap().unwrap();
+    buffer.push("two".into());
+
+    assert_eq!(vec!["one", "two"], received(&mut rx, 2).await);
+}
+
+#[tokio::test]
+async fn test_disconnected_client() {
+    let buffer = ReplayBuffer::new(2);
+    let (test_connection, rx) = connection(false);
+    let mut incoming =
+        buffer.replay_on_connect(futures::stream::iter([Ok::<_, ()>(test_connection)]));
+    let mut connection = incoming.next().await.unwrap().unwrap();
+    assert!(connection.send("discarded".into()).await.is_ok());
+
+    drop(rx);
+    buffer.push("one".into());
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while connection.flush().await.is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+
+    // Closed subscribers are removed on the next event
+    buffer.push("two".into());
+    assert!(buffer.inner.lock().unwrap().subscribers.is_empty());
+}
@@ -0,0 +1,72 @@
+use std::sync::{Arc, mpsc};
+use std::time::Duration;
+
+use daemon_slayer_core::CancellationToken;
+use daemon_slayer_core::server::BroadcastEventStore;
+use daemon_slayer_core::server::tracked::{Manager, Settings};
+use tokio::sync::broadcast;
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+
+use super::LoggingUpdateService;
+use crate::target_filters::TargetFilters;
+use crate::{LogLevel, LogTarget, ReloadHandle, UserConfig};
+
+#[derive(Debug)]
+struct TestConfig(UserConfig);
+
+impl AsRef<UserConfig> for TestConfig {
+    fn as_ref(&self) -> &UserConfig {
+        &self.0
+    }
+}
+
+fn config(log_filter: Option<&str>) -> Arc<TestConfig> {
+    Arc::new(TestConfig(UserConfig {
+        log_level: LogLevel(Level::INFO),
+        log_filters: log_filter
+            .map(|filter| (LogTarget::File, filter.parse().unwrap()))
+            .into_iter()
+            .collect(),
+        ..Default::default()
+    }))
+}
+
+#[tokio::test]
+async fn test_filters_reloaded_on_config_change() {
+    let (reload_tx, reload_rx) = mpsc::channel::<EnvFilter>();
+    let reload_handle = ReloadHandle::new(
+        TargetFilters::default(),
+        None,
+        vec![(
+            LogTarget::File,
+            Box::new(move |filter| {
+                reload_tx.send(filter).ok();
+            }),
+        )],
+        Box::new(|_| {}),
+    );
+    let (config_tx, _) = broadcast::channel(32);
+    let manager = Manager::new(CancellationToken::new(), Settings::default());
+    manager.get_context().spawn(LoggingUpdateService::new(
+        reload_handle,
+        BroadcastEventStore::new(config_tx.clone()),
+    ));
+
+    // Wait for the service to subscribe before sending the event
+    while config_tx.receiver_count() == 0 {
+        tokio::task::yield_now().await;
+    }
+    config_tx
+        .send((config(None), config(Some("app::db=trace"))))
+        .unwrap();
+
+    let filter = tokio::task::spawn_blocking(move || {
+        reload_rx.recv_timeout(Duration::from_secs(5)).unwrap()
+    })
+    .await
+    .unwrap();
+    assert!(filter.to_string().contains("app::db=trace"));
+
+    manager.cancel().await.unwrap();
+}
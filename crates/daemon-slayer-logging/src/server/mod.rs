@@ -2,8 +2,8 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use daemon_slayer_core::BoxedError;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
 use daemon_slayer_core::server::tokio_stream::StreamExt;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use daemon_slayer_core::server::{BroadcastEventStore, EventStore};
 use tokio_util::future::FutureExt;
 
@@ -42,10 +42,12 @@ impl<T: LoggingConfig> BackgroundService for LoggingUpdateService<T> {
             .await
             .flatten()
         {
-            let log_level = new.deref().as_ref().log_level.to_level_filter();
-
-            self.reload_handle.update_log_level(log_level);
+            self.reload_handle.update(new.deref().as_ref());
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[path = "./logging_update_service_test.rs"]
+mod logging_update_service_test;
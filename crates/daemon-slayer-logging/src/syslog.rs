@@ -0,0 +1,346 @@
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpStream, UdpSocket};
+#[cfg(unix)]
+use std::os::unix::net::{UnixDatagram, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use time::format_description::well_known::Rfc3339;
+use time::{OffsetDateTime, UtcOffset};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
+use tracing_subscriber::Layer;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::Context;
+
+use crate::LoggerCreationError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFacility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogFormat {
+    /// The legacy BSD format, which is what most local syslog daemons expect.
+    Rfc3164,
+    Rfc5424,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyslogTransport {
+    /// The local syslog socket, `/dev/log` on Linux and `/var/run/syslog` on macOS.
+    #[cfg(unix)]
+    Local,
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Udp(SocketAddr),
+    /// Messages are framed using octet counting as described in RFC 6587.
+    Tcp(SocketAddr),
+}
+
+#[derive(Debug, Clone)]
+pub struct SyslogConfig {
+    transport: SyslogTransport,
+    facility: SyslogFacility,
+    format: SyslogFormat,
+    app_name: Option<String>,
+}
+
+impl SyslogConfig {
+    pub fn new(transport: SyslogTransport) -> Self {
+        Self {
+            transport,
+            facility: SyslogFacility::Daemon,
+            format: SyslogFormat::Rfc3164,
+            app_name: None,
+        }
+    }
+
+    /// Defaults to [`SyslogFacility::Daemon`].
+    pub fn with_facility(mut self, facility: SyslogFacility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Defaults to [`SyslogFormat::Rfc3164`].
+    pub fn with_format(mut self, format: SyslogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Defaults to the application name from the label.
+    pub fn with_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = Some(app_name.into());
+        self
+    }
+}
+
+enum Connection {
+    #[cfg(unix)]
+    UnixDatagram(UnixDatagram),
+    #[cfg(unix)]
+    UnixStream(UnixStream),
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+}
+
+impl Connection {
+    fn connect(transport: &SyslogTransport) -> io::Result<Self> {
+        match transport {
+            #[cfg(unix)]
+            SyslogTransport::Local => {
+                let path = if cfg!(target_os = "macos") {
+                    "/var/run/syslog"
+                } else {
+                    "/dev/log"
+                };
+                Self::connect_unix(path.as_ref())
+            }
+            #[cfg(unix)]
+            SyslogTransport::Unix(path) => Self::connect_unix(path),
+            SyslogTransport::Udp(addr) => {
+                let bind_addr: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0u16; 8], 0).into()
+                };
+                let socket = UdpSocket::bind(bind_addr)?;
+                socket.connect(addr)?;
+                Ok(Self::Udp(socket))
+            }
+            SyslogTransport::Tcp(addr) => Ok(Self::Tcp(TcpStream::connect(addr)?)),
+        }
+    }
+
+    #[cfg(unix)]
+    fn connect_unix(path: &std::path::Path) -> io::Result<Self> {
+        // Most syslog daemons listen with a datagram socket, but some use a stream socket instead
+        let socket = UnixDatagram::unbound()?;
+        match socket.connect(path) {
+            Ok(()) => Ok(Self::UnixDatagram(socket)),
+            Err(_) => Ok(Self::UnixStream(UnixStream::connect(path)?)),
+        }
+    }
+
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::UnixDatagram(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            #[cfg(unix)]
+            Self::UnixStream(stream) => {
+                stream.write_all(message.as_bytes())?;
+                stream.write_all(b"\0")
+            }
+            Self::Udp(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Self::Tcp(stream) => {
+                write!(stream, "{} {message}", message.len())
+            }
+        }
+    }
+}
+
+/// Writes each message to the syslog daemon from the background thread that's created by
+/// [`NonBlocking`].
+struct SyslogWriter {
+    transport: SyslogTransport,
+    connection: Option<Connection>,
+}
+
+impl Write for SyslogWriter {
+    // Each call receives exactly one message from the layer
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let message = String::from_utf8_lossy(buf);
+        if let Some(current) = self.connection.as_mut()
+            && current.send(&message).is_ok()
+        {
+            return Ok(buf.len());
+        }
+        // The syslog daemon may have restarted so try reconnecting once before giving up on the
+        // message
+        self.connection = Connection::connect(&self.transport).ok();
+        if let Some(current) = self.connection.as_mut()
+            && current.send(&message).is_err()
+        {
+            self.connection = None;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.connection {
+            Some(Connection::Tcp(stream)) => stream.flush(),
+            #[cfg(unix)]
+            Some(Connection::UnixStream(stream)) => stream.flush(),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub(crate) struct SyslogLayer {
+    config: SyslogConfig,
+    app_name: String,
+    hostname: Option<String>,
+    offset: UtcOffset,
+    writer: NonBlocking,
+}
+
+impl SyslogLayer {
+    /// Messages are sent from a background thread so a slow or unresponsive syslog daemon doesn't
+    /// block the thread that's logging. Once `buffer_limit` messages are queued, new messages are
+    /// dropped until the writer catches up.
+    pub(crate) fn new(
+        config: &SyslogConfig,
+        default_app_name: &str,
+        buffer_limit: usize,
+    ) -> Result<(Self, WorkerGuard), LoggerCreationError> {
+        let connection =
+            Connection::connect(&config.transport).map_err(LoggerCreationError::SyslogFailure)?;
+        let (writer, guard) = NonBlockingBuilder::default()
+            .buffered_lines_limit(buffer_limit)
+            .thread_name("syslog-writer")
+            .finish(SyslogWriter {
+                transport: config.transport.clone(),
+                connection: Some(connection),
+            });
+        Ok((
+            Self {
+                app_name: config
+                    .app_name
+                    .clone()
+                    .unwrap_or_else(|| default_app_name.to_owned()),
+                config: config.clone(),
+                hostname: hostname(),
+                // Looking up the local offset can fail once other threads are running so it's
+                // only done once when the logger is created
+                offset: UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+                writer,
+            },
+            guard,
+        ))
+    }
+
+    fn format_message(&self, level: &Level, body: &str, now: OffsetDateTime) -> String {
+        let priority = (self.config.facility as u8) * 8 + severity(level);
+        let hostname = self.hostname.as_deref().unwrap_or("-");
+        let pid = std::process::id();
+        match self.config.format {
+            SyslogFormat::Rfc3164 => {
+                const MONTHS: [&str; 12] = [
+                    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov",
+                    "Dec",
+                ];
+                // RFC 3164 timestamps don't include an offset so they're expected to be in local
+                // time
+                format!(
+                    "<{priority}>{} {:>2} {:02}:{:02}:{:02} {hostname} {}[{pid}]: {body}",
+                    MONTHS[u8::from(now.month()) as usize - 1],
+                    now.day(),
+                    now.hour(),
+                    now.minute(),
+                    now.second(),
+                    self.app_name,
+                )
+            }
+            SyslogFormat::Rfc5424 => {
+                let timestamp = now.format(&Rfc3339).unwrap_or_else(|_| "-".to_owned());
+                format!(
+                    "<{priority}>1 {timestamp} {hostname} {} {pid} - - {body}",
+                    self.app_name
+                )
+            }
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SyslogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = SyslogVisitor::default();
+        event.record(&mut visitor);
+        let mut body = format!("{}: {}", metadata.target(), visitor.message);
+        body.push_str(&visitor.fields);
+        let now = OffsetDateTime::now_utc().to_offset(self.offset);
+        let message = self.format_message(metadata.level(), &body, now);
+        self.writer.make_writer().write_all(message.as_bytes()).ok();
+    }
+}
+
+fn severity(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // Leave room for the NUL terminator since some platforms don't write one when the name is
+    // truncated
+    let max_len = buf.len() - 1;
+    // SAFETY: the buffer is valid for writes of max_len bytes
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), max_len) };
+    if result != 0 {
+        return None;
+    }
+    // A name without a terminator within max_len bytes was truncated
+    let len = buf[..max_len].iter().position(|b| *b == 0)?;
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(windows)]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[derive(Default)]
+struct SyslogVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for SyslogVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            write!(self.fields, " {}={value:?}", field.name()).ok();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{value:?}").ok();
+        } else {
+            write!(self.fields, " {}={value:?}", field.name()).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./syslog_test.rs"]
+mod syslog_test;
@@ -0,0 +1,165 @@
+use std::io::Read;
+use std::net::{TcpListener, UdpSocket};
+use std::time::Duration;
+
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::prelude::*;
+
+use super::{SyslogConfig, SyslogFacility, SyslogFormat, SyslogLayer, SyslogTransport, hostname};
+
+fn udp_socket() -> UdpSocket {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    socket
+}
+
+fn udp_layer(socket: &UdpSocket, config: impl FnOnce(SyslogConfig) -> SyslogConfig) -> SyslogLayer {
+    let config = config(SyslogConfig::new(SyslogTransport::Udp(
+        socket.local_addr().unwrap(),
+    )));
+    SyslogLayer::new(&config, "app", 16).unwrap().0
+}
+
+fn recv(socket: &UdpSocket) -> String {
+    let mut buf = [0; 1024];
+    let len = socket.recv(&mut buf).unwrap();
+    String::from_utf8(buf[..len].to_vec()).unwrap()
+}
+
+fn log_event(layer: SyslogLayer, guard: WorkerGuard) {
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(target: "app::db", attempts = 3, "connection failed");
+    });
+    // Waits for the background writer to send the message
+    drop(guard);
+}
+
+fn timestamp() -> OffsetDateTime {
+    Date::from_calendar_date(2024, Month::March, 5)
+        .unwrap()
+        .with_hms(7, 8, 9)
+        .unwrap()
+        .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap())
+}
+
+#[test]
+fn test_priority() {
+    let socket = udp_socket();
+    let layer = udp_layer(&socket, |config| config);
+    assert!(
+        layer
+            .format_message(&Level::INFO, "", timestamp())
+            .starts_with("<30>")
+    );
+    assert!(
+        layer
+            .format_message(&Level::TRACE, "", timestamp())
+            .starts_with("<31>")
+    );
+
+    let layer = udp_layer(&socket, |config| {
+        config.with_facility(SyslogFacility::Local0)
+    });
+    assert!(
+        layer
+            .format_message(&Level::ERROR, "", timestamp())
+            .starts_with("<131>")
+    );
+    assert!(
+        layer
+            .format_message(&Level::WARN, "", timestamp())
+            .starts_with("<132>")
+    );
+    assert!(
+        layer
+            .format_message(&Level::DEBUG, "", timestamp())
+            .starts_with("<135>")
+    );
+}
+
+#[test]
+fn test_rfc3164_format() {
+    let socket = udp_socket();
+    let layer = udp_layer(&socket, |config| config.with_app_name("custom"));
+    let hostname = hostname().unwrap_or_else(|| "-".to_owned());
+    assert_eq!(
+        format!(
+            "<30>Mar  5 07:08:09 {hostname} custom[{}]: message",
+            std::process::id()
+        ),
+        layer.format_message(&Level::INFO, "message", timestamp())
+    );
+}
+
+#[test]
+fn test_rfc5424_format() {
+    let socket = udp_socket();
+    let layer = udp_layer(&socket, |config| config.with_format(SyslogFormat::Rfc5424));
+    let hostname = hostname().unwrap_or_else(|| "-".to_owned());
+    assert_eq!(
+        format!(
+            "<30>1 2024-03-05T07:08:09+02:00 {hostname} app {} - - message",
+            std::process::id()
+        ),
+        layer.format_message(&Level::INFO, "message", timestamp())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hostname() {
+    let hostname = hostname().unwrap();
+    assert!(!hostname.is_empty());
+    assert!(!hostname.contains('\0'));
+}
+
+#[test]
+fn test_udp() {
+    let socket = udp_socket();
+    let config = SyslogConfig::new(SyslogTransport::Udp(socket.local_addr().unwrap()));
+    let (layer, guard) = SyslogLayer::new(&config, "app", 16).unwrap();
+    log_event(layer, guard);
+
+    let message = recv(&socket);
+    assert!(message.starts_with("<28>"));
+    assert!(message.ends_with("app::db: connection failed attempts=3"));
+}
+
+#[test]
+fn test_tcp_octet_counting() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let config = SyslogConfig::new(SyslogTransport::Tcp(listener.local_addr().unwrap()));
+    let (layer, guard) = SyslogLayer::new(&config, "app", 16).unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+    log_event(layer, guard);
+
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+    let (len, message) = received.split_once(' ').unwrap();
+    assert_eq!(len.parse::<usize>().unwrap(), message.len());
+    assert!(message.ends_with("app::db: connection failed attempts=3"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unix_datagram() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("log.sock");
+    let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let config = SyslogConfig::new(SyslogTransport::Unix(path));
+    let (layer, guard) = SyslogLayer::new(&config, "app", 16).unwrap();
+    log_event(layer, guard);
+
+    let mut buf = [0; 1024];
+    let len = socket.recv(&mut buf).unwrap();
+    let message = String::from_utf8(buf[..len].to_vec()).unwrap();
+    assert!(message.ends_with("app::db: connection failed attempts=3"));
+}
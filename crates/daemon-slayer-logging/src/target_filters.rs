@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use tracing::metadata::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::Directive;
+
+use crate::{EnvConfig, LogFilter, LogTarget};
+
+/// Builds the filter for each target so it can be recreated when the config changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TargetFilters {
+    pub(crate) env_config: Option<EnvConfig>,
+    pub(crate) env_filter_directives: Vec<Directive>,
+    pub(crate) target_directives: HashMap<LogTarget, Vec<Directive>>,
+}
+
+impl TargetFilters {
+    /// The environment variable replaces the configured log level as the starting point for each
+    /// target, but the log level still caps the result. See [`TargetFilters::global_level`].
+    pub(crate) fn base(&self, log_level: Option<LevelFilter>) -> EnvFilter {
+        let from_env = self
+            .env_config
+            .as_ref()
+            .and_then(|config| EnvFilter::try_from_env(&config.var_name).ok());
+        match (from_env, log_level, &self.env_config) {
+            (Some(env_filter), _, _) => env_filter,
+            (None, Some(log_level), _) => EnvFilter::default().add_directive(log_level.into()),
+            (None, None, Some(config)) => {
+                EnvFilter::default().add_directive(config.default_if_missing.into())
+            }
+            (None, None, None) => EnvFilter::default(),
+        }
+    }
+
+    pub(crate) fn for_target(
+        &self,
+        target: &LogTarget,
+        log_level: Option<LevelFilter>,
+        log_filters: &HashMap<LogTarget, LogFilter>,
+        runtime_filter: Option<&LogFilter>,
+    ) -> EnvFilter {
+        let mut env_filter = self.base(log_level);
+        if *target == LogTarget::Otlp {
+            // Events emitted while exporting would otherwise get exported again
+            for dependency in ["opentelemetry", "hyper", "h2", "tonic", "tower", "reqwest"] {
+                env_filter = env_filter.add_directive(format!("{dependency}=off").parse().unwrap());
+            }
+        }
+        for directive in &self.env_filter_directives {
+            env_filter = env_filter.add_directive(directive.clone());
+        }
+        for directive in self.target_directives.get(target).into_iter().flatten() {
+            env_filter = env_filter.add_directive(directive.clone());
+        }
+        for directive in log_filters
+            .get(target)
+            .into_iter()
+            .chain(runtime_filter)
+            .flat_map(|filter| filter.directives())
+        {
+            env_filter = env_filter.add_directive(directive);
+        }
+        env_filter
+    }
+
+    /// The global filter only needs to be as verbose as the most verbose target. It's also capped
+    /// by the configured log level so the environment variable can't raise the verbosity past it.
+    /// Config filters and runtime filters name their levels explicitly, so they're allowed to go
+    /// past the log level.
+    pub(crate) fn global_level(
+        &self,
+        env_filters: &[EnvFilter],
+        log_level: Option<LevelFilter>,
+        log_filters: &HashMap<LogTarget, LogFilter>,
+        runtime_filter: Option<&LogFilter>,
+    ) -> LevelFilter {
+        let max = max_level(env_filters);
+        let Some(log_level) = log_level else {
+            return max;
+        };
+        let explicit: Vec<_> = log_filters
+            .values()
+            .chain(runtime_filter)
+            .flat_map(|filter| filter.directives())
+            .collect();
+        let cap = if explicit.is_empty() {
+            log_level
+        } else {
+            let explicit = explicit
+                .into_iter()
+                .fold(EnvFilter::default(), |filter, directive| {
+                    filter.add_directive(directive)
+                });
+            log_level.max(max_level(&[explicit]))
+        };
+        max.min(cap)
+    }
+}
+
+/// The most verbose level enabled by any of the filters. This prevents layers without their own
+/// filter from seeing every span.
+pub(crate) fn max_level(filters: &[EnvFilter]) -> LevelFilter {
+    filters
+        .iter()
+        .map(|filter| filter.max_level_hint().unwrap_or(LevelFilter::TRACE))
+        .max()
+        .unwrap_or(LevelFilter::OFF)
+}
+
+#[cfg(test)]
+#[path = "./target_filters_test.rs"]
+mod target_filters_test;
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use tracing::Level;
+use tracing::metadata::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::prelude::*;
+
+use super::{TargetFilters, max_level};
+use crate::{LogFilter, LogTarget};
+
+// Returns the test callsites that make it through the filter
+fn enabled(filter: EnvFilter) -> Vec<&'static str> {
+    let mut enabled = Vec::new();
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(filter), || {
+        if tracing::enabled!(target: "app", Level::INFO) {
+            enabled.push("app=info");
+        }
+        if tracing::enabled!(target: "app", Level::DEBUG) {
+            enabled.push("app=debug");
+        }
+        if tracing::enabled!(target: "app::db", Level::TRACE) {
+            enabled.push("app::db=trace");
+        }
+        if tracing::enabled!(target: "tonic", Level::INFO) {
+            enabled.push("tonic=info");
+        }
+    });
+    enabled
+}
+
+fn log_filters(filters: &[(LogTarget, &str)]) -> HashMap<LogTarget, LogFilter> {
+    filters
+        .iter()
+        .map(|(target, filter)| (target.clone(), filter.parse().unwrap()))
+        .collect()
+}
+
+#[test]
+fn test_log_level() {
+    let filters = TargetFilters::default();
+    let filter = filters.for_target(
+        &LogTarget::File,
+        Some(LevelFilter::DEBUG),
+        &HashMap::new(),
+        None,
+    );
+    assert_eq!(vec!["app=info", "app=debug", "tonic=info"], enabled(filter));
+}
+
+#[test]
+fn test_log_filters_per_target() {
+    let filters = TargetFilters::default();
+    let log_filters = log_filters(&[(LogTarget::File, "app::db=trace,tonic=warn")]);
+
+    let file_filter = filters.for_target(
+        &LogTarget::File,
+        Some(LevelFilter::INFO),
+        &log_filters,
+        None,
+    );
+    assert_eq!(vec!["app=info", "app::db=trace"], enabled(file_filter));
+
+    let stdout_filter = filters.for_target(
+        &LogTarget::Stdout,
+        Some(LevelFilter::INFO),
+        &log_filters,
+        None,
+    );
+    assert_eq!(vec!["app=info", "tonic=info"], enabled(stdout_filter));
+}
+
+#[test]
+fn test_runtime_filter_applied_last() {
+    let filters = TargetFilters {
+        target_directives: HashMap::from([(LogTarget::File, vec!["app=warn".parse().unwrap()])]),
+        ..Default::default()
+    };
+    let runtime_filter: LogFilter = "app=debug".parse().unwrap();
+    let filter = filters.for_target(
+        &LogTarget::File,
+        Some(LevelFilter::INFO),
+        &log_filters(&[(LogTarget::File, "app=error")]),
+        Some(&runtime_filter),
+    );
+    assert_eq!(vec!["app=info", "app=debug", "tonic=info"], enabled(filter));
+}
+
+#[test]
+fn test_otlp_excludes_exporter_dependencies() {
+    let filters = TargetFilters::default();
+    let filter = filters.for_target(
+        &LogTarget::Otlp,
+        Some(LevelFilter::INFO),
+        &HashMap::new(),
+        None,
+    );
+    assert_eq!(vec!["app=info"], enabled(filter));
+}
+
+#[test]
+fn test_invalid_log_filter() {
+    assert!("app=loud".parse::<LogFilter>().is_err());
+    assert!("app=debug,[".parse::<LogFilter>().is_err());
+}
+
+#[test]
+fn test_log_filter_whitespace() {
+    let filter: LogFilter = " app=debug , ,tonic=warn,".parse().unwrap();
+    assert_eq!(2, filter.directives().len());
+}
+
+#[test]
+fn test_max_level() {
+    let filters = [
+        EnvFilter::default().add_directive(LevelFilter::WARN.into()),
+        EnvFilter::default().add_directive("app::db=trace".parse().unwrap()),
+    ];
+    assert_eq!(LevelFilter::TRACE, max_level(&filters));
+    assert_eq!(LevelFilter::OFF, max_level(&[]));
+}
+
+#[test]
+fn test_global_level_capped_by_log_level() {
+    let filters = TargetFilters::default();
+    // Stands in for a filter from the environment variable
+    let env_filters = [EnvFilter::default().add_directive(LevelFilter::TRACE.into())];
+    let global_level =
+        |log_level, config_filters: &[(LogTarget, &str)], runtime_filter: Option<&str>| {
+            filters.global_level(
+                &env_filters,
+                log_level,
+                &log_filters(config_filters),
+                runtime_filter
+                    .map(|filter| filter.parse().unwrap())
+                    .as_ref(),
+            )
+        };
+
+    assert_eq!(LevelFilter::TRACE, global_level(None, &[], None));
+    assert_eq!(
+        LevelFilter::INFO,
+        global_level(Some(LevelFilter::INFO), &[], None)
+    );
+    assert_eq!(
+        LevelFilter::DEBUG,
+        global_level(
+            Some(LevelFilter::INFO),
+            &[(LogTarget::File, "app::db=debug")],
+            None
+        )
+    );
+    assert_eq!(
+        LevelFilter::TRACE,
+        global_level(Some(LevelFilter::WARN), &[], Some("app=trace"))
+    );
+}
@@ -9,9 +9,10 @@ pub(crate) fn define_service(ident: Ident, crate_name: proc_macro2::TokenStream)
             #crate_name::ServiceError<Self::Error>> {
                 #crate_name::platform::run_as_service::<#ident>(input_data).await
             }
-            async fn run_directly(input_data: Option<Self::InputData>) -> Result<(),
+            async fn run_directly(input_data: Option<Self::InputData>,
+            settings: #crate_name::ForegroundSettings) -> Result<(),
             #crate_name::ServiceError<Self::Error>> {
-                Self::run_as_service(input_data).await
+                #crate_name::run_in_foreground::<#ident>(input_data, settings).await
             }
         }
     }
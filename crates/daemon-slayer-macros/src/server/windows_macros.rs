@@ -50,9 +50,10 @@ pub(crate) fn define_service(ident: Ident, crate_name: proc_macro2::TokenStream)
                 rx.recv().await.expect("Failed to receive service result")
             }
 
-            async fn run_directly(input_data: Option<Self::InputData>) -> Result<(),
+            async fn run_directly(input_data: Option<Self::InputData>,
+            settings: #crate_name::ForegroundSettings) -> Result<(),
             #crate_name::ServiceError<Self::Error>> {
-                #crate_name::platform::get_direct_handler::<#ident>(input_data).await
+                #crate_name::run_in_foreground::<#ident>(input_data, settings).await
             }
         }
     }
@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::cli::clap::{self, FromArgMatches, Subcommand};
+use daemon_slayer_core::cli::{ActionType, CommandMatch, CommandOutput, CommandProvider};
+
+use crate::fetch_metrics;
+
+#[derive(Subcommand)]
+enum CliCommands {
+    /// Print the service's metrics in the Prometheus text format
+    Metrics,
+}
+
+#[derive(Clone, Debug)]
+pub struct MetricsCliProvider {
+    app_name: String,
+    matched: bool,
+}
+
+impl MetricsCliProvider {
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+            matched: false,
+        }
+    }
+}
+
+#[async_trait]
+impl CommandProvider for MetricsCliProvider {
+    fn get_commands(&self, command: clap::Command) -> clap::Command {
+        CliCommands::augment_subcommands(command)
+    }
+
+    fn matches(&mut self, matches: &clap::ArgMatches) -> Option<CommandMatch> {
+        CliCommands::from_arg_matches(matches).ok()?;
+        self.matched = true;
+        Some(CommandMatch {
+            action_type: ActionType::Client,
+            action: None,
+        })
+    }
+
+    async fn handle_input(self: Box<Self>) -> Result<CommandOutput, BoxedError> {
+        if !self.matched {
+            return Ok(CommandOutput::unhandled());
+        }
+        Ok(match fetch_metrics(self.app_name).await {
+            Ok(metrics) => CommandOutput::handled(metrics.trim_end().to_owned()),
+            Err(e) => CommandOutput::handled(format!("Error fetching metrics: {e}")),
+        })
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use daemon_slayer_core::server::tracked::ServiceRegistry;
+#[cfg(feature = "health-check")]
+use daemon_slayer_health_check::server::HealthRegistry;
+use daemon_slayer_process::ProcessManager;
+
+/// Gathers the metrics exposed by a [`MetricsServer`](crate::MetricsServer). It can also be used
+/// directly to serve the metrics from an existing HTTP server.
+#[derive(Clone)]
+pub struct MetricsCollector {
+    process_manager: Arc<Mutex<ProcessManager>>,
+    services: Option<ServiceRegistry>,
+    #[cfg(feature = "health-check")]
+    health_registry: Option<HealthRegistry>,
+}
+
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsCollector {
+    pub fn new() -> Self {
+        Self {
+            // The process manager is kept between collections because CPU usage is calculated
+            // from the time since the last refresh
+            process_manager: Arc::new(Mutex::new(ProcessManager::new(std::process::id()))),
+            services: None,
+            #[cfg(feature = "health-check")]
+            health_registry: None,
+        }
+    }
+
+    /// Reports the background services spawned from the manager that owns `services`. A
+    /// [`MetricsServer`](crate::MetricsServer) uses the manager it was spawned from by default.
+    pub fn with_service_registry(mut self, services: ServiceRegistry) -> Self {
+        self.services = Some(services);
+        self
+    }
+
+    pub(crate) fn set_default_service_registry(&mut self, services: ServiceRegistry) {
+        self.services.get_or_insert(services);
+    }
+
+    #[cfg(feature = "health-check")]
+    pub fn with_health_registry(mut self, health_registry: HealthRegistry) -> Self {
+        self.health_registry = Some(health_registry);
+        self
+    }
+
+    /// Renders all metrics in the Prometheus text format.
+    pub async fn collect(&self) -> String {
+        let mut writer = MetricsWriter::default();
+        self.write_background_services(&mut writer);
+        #[cfg(feature = "health-check")]
+        self.write_health_checks(&mut writer).await;
+        self.write_process(&mut writer);
+        writer.output
+    }
+
+    fn write_background_services(&self, writer: &mut MetricsWriter) {
+        let Some(services) = &self.services else {
+            return;
+        };
+        // Restarts are tracked per service instance, but separate services may share a name. Each
+        // name can only be reported once, so they're combined here.
+        let mut by_name = BTreeMap::<String, (bool, usize)>::new();
+        for service in services.info() {
+            let (up, restarts) = by_name.entry(service.name).or_default();
+            *up |= service.running > 0;
+            *restarts += service.restarts;
+        }
+
+        writer.family(
+            "daemon_slayer_background_service_up",
+            "Whether the background service is running",
+            "gauge",
+        );
+        for (name, (up, _)) in &by_name {
+            writer.sample(
+                "daemon_slayer_background_service_up",
+                &[("service", name)],
+                *up as u8,
+            );
+        }
+
+        writer.family(
+            "daemon_slayer_background_service_restarts_total",
+            "Number of times the background service was restarted",
+            "counter",
+        );
+        for (name, (_, restarts)) in &by_name {
+            writer.sample(
+                "daemon_slayer_background_service_restarts_total",
+                &[("service", name)],
+                restarts,
+            );
+        }
+    }
+
+    #[cfg(feature = "health-check")]
+    async fn write_health_checks(&self, writer: &mut MetricsWriter) {
+        use daemon_slayer_health_check::{CheckKind, HealthStatus};
+
+        let Some(health_registry) = &self.health_registry else {
+            return;
+        };
+        let report = health_registry.report(None).await;
+
+        writer.family(
+            "daemon_slayer_health_check_status",
+            "Result of the last health check. The current status is set to 1",
+            "gauge",
+        );
+        for component in &report.components {
+            let kind = match component.kind {
+                CheckKind::Readiness => "readiness",
+                CheckKind::Liveness => "liveness",
+            };
+            for (status, active) in [
+                ("healthy", component.status.is_healthy()),
+                (
+                    "degraded",
+                    matches!(component.status, HealthStatus::Degraded(_)),
+                ),
+                ("unhealthy", component.status.is_unhealthy()),
+            ] {
+                writer.sample(
+                    "daemon_slayer_health_check_status",
+                    &[
+                        ("check", &component.name),
+                        ("kind", kind),
+                        ("status", status),
+                    ],
+                    active as u8,
+                );
+            }
+        }
+    }
+
+    fn write_process(&self, writer: &mut MetricsWriter) {
+        let Some(info) = self.process_manager.lock().unwrap().process_info() else {
+            return;
+        };
+
+        writer.family(
+            "process_cpu_usage_percent",
+            "CPU usage of the process across all cores",
+            "gauge",
+        );
+        writer.sample(
+            "process_cpu_usage_percent",
+            &[],
+            info.total_cpu_usage_percent(),
+        );
+
+        writer.family(
+            "process_resident_memory_bytes",
+            "Resident memory size in bytes",
+            "gauge",
+        );
+        writer.sample("process_resident_memory_bytes", &[], info.memory.as_u64());
+
+        writer.family(
+            "process_virtual_memory_bytes",
+            "Virtual memory size in bytes",
+            "gauge",
+        );
+        writer.sample(
+            "process_virtual_memory_bytes",
+            &[],
+            info.virtual_memory.as_u64(),
+        );
+
+        if let Some(open_files) = info.open_files {
+            writer.family(
+                "process_open_fds",
+                "Number of open file descriptors",
+                "gauge",
+            );
+            writer.sample("process_open_fds", &[], open_files);
+        }
+
+        writer.family(
+            "process_uptime_seconds",
+            "Time since the process started in seconds",
+            "gauge",
+        );
+        writer.sample("process_uptime_seconds", &[], info.run_time().num_seconds());
+    }
+}
+
+#[derive(Default)]
+struct MetricsWriter {
+    output: String,
+}
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, help: &str, metric_type: &str) {
+        writeln!(self.output, "# HELP {name} {help}").unwrap();
+        writeln!(self.output, "# TYPE {name} {metric_type}").unwrap();
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        self.output.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<_> = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect();
+            write!(self.output, "{{{}}}", labels.join(",")).unwrap();
+        }
+        writeln!(self.output, " {value}").unwrap();
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+#[path = "./collector_test.rs"]
+mod collector_test;
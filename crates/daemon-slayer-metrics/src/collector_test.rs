@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use daemon_slayer_core::server::tracked::{BackgroundService, Manager, ServiceContext, Settings};
+use daemon_slayer_core::{BoxedError, CancellationToken};
+use tokio::sync::oneshot;
+
+use super::{MetricsCollector, escape_label};
+
+struct TestService {
+    name: &'static str,
+    finished: Option<oneshot::Receiver<()>>,
+}
+
+impl BackgroundService for TestService {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        match self.finished {
+            Some(finished) => {
+                tokio::select! {
+                    _ = finished => {}
+                    _ = context.cancellation_token().cancelled() => {}
+                }
+            }
+            None => context.cancellation_token().cancelled().await,
+        }
+        Ok(())
+    }
+}
+
+fn lines(output: &str, metric: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.starts_with(&format!("{metric}{{")))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+async fn wait() {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_background_services() {
+    let manager = Manager::new(CancellationToken::new(), Settings::default());
+    let context = manager.get_context();
+    let collector = MetricsCollector::new().with_service_registry(manager.services());
+
+    for _ in 0..3 {
+        let (finished_tx, finished_rx) = oneshot::channel();
+        context.spawn(TestService {
+            name: "worker",
+            finished: Some(finished_rx),
+        });
+        wait().await;
+        finished_tx.send(()).unwrap();
+        wait().await;
+    }
+    context.spawn(TestService {
+        name: "server",
+        finished: None,
+    });
+    // A different service with the same name isn't a restart
+    context.spawn(TestService {
+        name: "server",
+        finished: None,
+    });
+    wait().await;
+
+    let output = collector.collect().await;
+    assert_eq!(
+        vec![
+            "daemon_slayer_background_service_up{service=\"server\"} 1",
+            "daemon_slayer_background_service_up{service=\"worker\"} 0",
+        ],
+        lines(&output, "daemon_slayer_background_service_up")
+    );
+    assert_eq!(
+        vec![
+            "daemon_slayer_background_service_restarts_total{service=\"server\"} 0",
+            "daemon_slayer_background_service_restarts_total{service=\"worker\"} 2",
+        ],
+        lines(&output, "daemon_slayer_background_service_restarts_total")
+    );
+
+    manager.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_no_registry() {
+    let output = MetricsCollector::new().collect().await;
+    assert!(!output.contains("daemon_slayer_background_service"));
+}
+
+#[tokio::test]
+async fn test_process() {
+    let output = MetricsCollector::new().collect().await;
+    for metric in [
+        "process_cpu_usage_percent",
+        "process_resident_memory_bytes",
+        "process_virtual_memory_bytes",
+        "process_uptime_seconds",
+    ] {
+        assert!(output.contains(&format!("# TYPE {metric} gauge\n{metric} ")));
+    }
+}
+
+#[test]
+fn test_escape_label() {
+    assert_eq!("a\\\\b\\\"c\\nd", escape_label("a\\b\"c\nd"));
+}
@@ -0,0 +1,46 @@
+use std::net::SocketAddr;
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+
+use crate::MetricsCollector;
+
+pub(crate) struct HttpMetricsServer {
+    addr: SocketAddr,
+    collector: MetricsCollector,
+}
+
+impl HttpMetricsServer {
+    pub(crate) fn new(addr: SocketAddr, collector: MetricsCollector) -> Self {
+        Self { addr, collector }
+    }
+}
+
+impl BackgroundService for HttpMetricsServer {
+    fn name(&self) -> &str {
+        "http_metrics_server"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        let app = Router::new()
+            .route("/metrics", get(metrics))
+            .with_state(self.collector);
+        let listener = tokio::net::TcpListener::bind(self.addr).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(context.cancellation_token().clone().cancelled_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+async fn metrics(State(collector): State<MetricsCollector>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        collector.collect().await,
+    )
+}
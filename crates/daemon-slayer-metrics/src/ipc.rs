@@ -0,0 +1,20 @@
+use std::io;
+use std::path::PathBuf;
+
+use tipsy::{Endpoint, IntoIpcPath, ServerId};
+use tokio::io::AsyncReadExt;
+
+pub fn metrics_socket_path(app_name: impl Into<String>) -> io::Result<PathBuf> {
+    ServerId::new(format!("{}_metrics", app_name.into()))
+        .parent_folder("/tmp")
+        .into_ipc_path()
+}
+
+/// Fetches the current metrics from a running [`MetricsServer`](crate::MetricsServer) in the
+/// Prometheus text format.
+pub async fn fetch_metrics(app_name: impl Into<String>) -> io::Result<String> {
+    let mut client = Endpoint::connect(metrics_socket_path(app_name)?).await?;
+    let mut metrics = String::new();
+    client.read_to_string(&mut metrics).await?;
+    Ok(metrics)
+}
@@ -0,0 +1,11 @@
+#[cfg(feature = "cli")]
+pub mod cli;
+mod collector;
+#[cfg(feature = "http")]
+mod http;
+mod ipc;
+mod server;
+
+pub use collector::*;
+pub use ipc::*;
+pub use server::*;
@@ -0,0 +1,96 @@
+#[cfg(feature = "http")]
+use std::net::SocketAddr;
+
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+use futures::StreamExt;
+use tap::TapFallible;
+use tipsy::{Endpoint, OnConflict};
+use tokio::io::AsyncWriteExt;
+use tracing::error;
+
+use crate::{MetricsCollector, metrics_socket_path};
+
+/// Serves Prometheus metrics over the socket used by [`fetch_metrics`](crate::fetch_metrics) and,
+/// optionally, over HTTP.
+pub struct MetricsServer {
+    app_name: String,
+    collector: MetricsCollector,
+    #[cfg(feature = "http")]
+    http_addr: Option<SocketAddr>,
+}
+
+impl MetricsServer {
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Self {
+            app_name: app_name.into(),
+            collector: MetricsCollector::new(),
+            #[cfg(feature = "http")]
+            http_addr: None,
+        }
+    }
+
+    pub fn with_collector(mut self, collector: MetricsCollector) -> Self {
+        self.collector = collector;
+        self
+    }
+
+    /// Also serves the metrics over HTTP at `/metrics`.
+    #[cfg(feature = "http")]
+    pub fn with_http_addr(mut self, http_addr: impl Into<SocketAddr>) -> Self {
+        self.http_addr = Some(http_addr.into());
+        self
+    }
+}
+
+impl BackgroundService for MetricsServer {
+    fn name(&self) -> &str {
+        "metrics_server"
+    }
+
+    async fn run(mut self, context: ServiceContext) -> Result<(), BoxedError> {
+        self.collector
+            .set_default_service_registry(context.services());
+
+        #[cfg(feature = "http")]
+        if let Some(http_addr) = self.http_addr {
+            context.spawn(crate::http::HttpMetricsServer::new(
+                http_addr,
+                self.collector.clone(),
+            ));
+        }
+
+        let incoming = Endpoint::new(metrics_socket_path(self.app_name)?, OnConflict::Overwrite)?
+            .incoming()?;
+        futures::pin_mut!(incoming);
+        let cancellation_token = context.cancellation_token().clone();
+
+        loop {
+            let conn = tokio::select! {
+                _ = cancellation_token.cancelled() => return Ok(()),
+                conn = incoming.next() => conn,
+            };
+            let Some(conn) = conn else {
+                return Ok(());
+            };
+            let Ok(mut conn) =
+                conn.tap_err(|e| error!("Error accepting metrics connection: {e:?}"))
+            else {
+                continue;
+            };
+            let collector = self.collector.clone();
+            tokio::spawn(async move {
+                let metrics = collector.collect().await;
+                conn.write_all(metrics.as_bytes())
+                    .await
+                    .tap_err(|e| error!("Error writing metrics response: {e:?}"))
+                    .ok();
+                conn.shutdown().await.ok();
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./server_test.rs"]
+mod server_test;
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use daemon_slayer_core::CancellationToken;
+use daemon_slayer_core::server::tracked::{Manager, Settings};
+
+use crate::{MetricsServer, fetch_metrics};
+
+#[tokio::test]
+async fn test_fetch_metrics() {
+    let app_name = "daemon_slayer_metrics_server_test";
+    let manager = Manager::new(CancellationToken::new(), Settings::default());
+    manager.get_context().spawn(MetricsServer::new(app_name));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The server reports the services from the manager it was spawned from by default
+    let metrics = fetch_metrics(app_name).await.unwrap();
+    assert!(
+        metrics.contains("daemon_slayer_background_service_up{service=\"metrics_server\"} 1\n")
+    );
+
+    manager.cancel().await.unwrap();
+}
@@ -3,8 +3,8 @@ use std::time::Duration;
 use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::notify::AsyncNotification;
 use daemon_slayer_core::server::EventStore;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
 use daemon_slayer_core::server::tokio_stream::StreamExt;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use tap::TapFallible;
 use tokio_util::future::FutureExt;
 use tracing::error;
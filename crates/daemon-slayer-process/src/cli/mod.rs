@@ -1,7 +1,7 @@
 use async_trait::async_trait;
-use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::cli::clap::{self, Args, FromArgMatches, Subcommand};
 use daemon_slayer_core::cli::{ActionType, CommandMatch, CommandOutput, CommandProvider};
+use daemon_slayer_core::{BoxedError, Label, instance};
 
 use crate::ProcessManager;
 
@@ -28,6 +28,7 @@ enum CliCommands {
 #[derive(Clone, Debug)]
 pub struct ProcessCliProvider {
     pid: Option<u32>,
+    pid_file_label: Option<Label>,
     matched_args: Option<ProcessArgs>,
 }
 
@@ -35,9 +36,17 @@ impl ProcessCliProvider {
     pub fn new(pid: Option<u32>) -> Self {
         Self {
             pid,
+            pid_file_label: None,
             matched_args: None,
         }
     }
+
+    /// Reads the PID from the server's PID file if one wasn't provided by the service manager.
+    /// The server needs to enable single instance mode for the file to be written.
+    pub fn with_pid_file_fallback(mut self, label: Label) -> Self {
+        self.pid_file_label = Some(label);
+        self
+    }
 }
 
 #[async_trait]
@@ -57,7 +66,9 @@ impl CommandProvider for ProcessCliProvider {
     }
 
     async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
-        let pid = self.pid.as_ref();
+        let pid = self
+            .pid
+            .or_else(|| self.pid_file_label.as_ref().and_then(instance::read_pid));
         let Some(args) = self.matched_args else {
             return Ok(CommandOutput::unhandled());
         };
@@ -68,14 +79,14 @@ impl CommandProvider for ProcessCliProvider {
         };
         return Ok(match args.commands {
             ProcessSubcommands::Info => {
-                let message = match ProcessManager::new(*pid).process_info() {
+                let message = match ProcessManager::new(pid).process_info() {
                     Some(info) => info.pretty_print(),
                     None => "Process not found".to_owned(),
                 };
                 CommandOutput::handled(message)
             }
             ProcessSubcommands::Kill => {
-                let message = match ProcessManager::kill(*pid) {
+                let message = match ProcessManager::kill(pid) {
                     Some(true) => "Kill signal sent",
                     Some(false) => "Failed to send kill signal",
                     None => "Process not found",
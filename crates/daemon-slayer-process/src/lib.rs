@@ -115,6 +115,7 @@ pub struct ProcessInfo {
     pub virtual_memory: ByteSize,
     pub parent_pid: Option<u32>,
     pub disk_usage: Option<DiskUsage>,
+    pub open_files: Option<u64>,
     pub status: ProcessStatus,
     pub child_processes: Vec<ProcessInfo>,
     start_time: DateTime<Utc>,
@@ -149,6 +150,7 @@ impl ProcessInfo {
             memory: ByteSize(process.memory()),
             virtual_memory: ByteSize(process.virtual_memory()),
             parent_pid: process.parent().map(|p| p.as_u32()),
+            open_files: process.open_files().map(|f| f as u64),
             status: ProcessStatus::from_sysinfo_status(process.status()),
             start_time: Utc.timestamp_opt(process.start_time() as i64, 0).unwrap(),
             cpu_usage: process.cpu_usage(),
@@ -243,6 +245,7 @@ impl ProcessInfo {
             .with_line("Memory Percent", self.memory_percent(2))
             .with_line("Virtual Memory", self.virtual_memory.to_string())
             .with_optional_line("Parent PID", self.parent_pid.map(|p| p.to_string()))
+            .with_optional_line("Open Files", self.open_files.map(|f| f.to_string()))
             //.with_line("Disk Usage", self.disk_usage.map(|d| d.total_read_bytes))
             .with_line("Status", self.status.to_string())
             .with_line("Start Time", self.format_start_time())
@@ -7,15 +7,22 @@ use daemon_slayer_core::cli::{
     Action, ActionType, CommandMatch, CommandOutput, CommandProvider, ServerAction,
 };
 use daemon_slayer_core::{BoxedError, CommandArg};
+use tracing::error;
 
-use crate::Service;
+use crate::{ForegroundSettings, Service, ServiceError};
 
 const RUN_ID: &str = "run";
 const LABEL_ID: &str = "label";
+#[cfg(feature = "restart-on-change")]
+const RESTART_ON_CHANGE_ID: &str = "restart-on-change";
+#[cfg(all(unix, feature = "daemonize"))]
+const DAEMONIZE_ID: &str = "daemonize";
 
 #[derive(Clone, Debug)]
 enum ServerCommand {
     Run,
+    #[cfg(all(unix, feature = "daemonize"))]
+    Daemonize,
     Direct,
     Label,
 }
@@ -25,6 +32,7 @@ pub struct ServerCliProvider<S: Service> {
     input_data: Option<S::InputData>,
     run_command: CommandArg,
     matched_command: Option<ServerCommand>,
+    foreground_settings: ForegroundSettings,
     _phantom: PhantomData<S>,
 }
 
@@ -35,12 +43,54 @@ impl<S: Service> ServerCliProvider<S> {
             input_data: Default::default(),
             _phantom: Default::default(),
             matched_command: None,
+            foreground_settings: Default::default(),
         }
     }
 
+    /// Settings used when the service is run directly instead of through the service manager.
+    pub fn with_foreground_settings(mut self, foreground_settings: ForegroundSettings) -> Self {
+        self.foreground_settings = foreground_settings;
+        self
+    }
+
     pub fn set_input_data(&mut self, input_data: S::InputData) {
         self.input_data = Some(input_data);
     }
+
+    #[cfg_attr(not(all(unix, feature = "daemonize")), allow(unused_variables))]
+    fn match_run(&mut self, run_matches: &clap::ArgMatches) -> Option<CommandMatch> {
+        #[cfg(all(unix, feature = "daemonize"))]
+        if run_matches.get_flag(DAEMONIZE_ID) {
+            self.matched_command = Some(ServerCommand::Daemonize);
+            return Some(CommandMatch {
+                action_type: ActionType::Server,
+                action: Some(Action::Server(ServerAction::Daemonize)),
+            });
+        }
+        self.matched_command = Some(ServerCommand::Run);
+        Some(CommandMatch {
+            action_type: ActionType::Server,
+            action: Some(Action::Server(ServerAction::Run)),
+        })
+    }
+
+    fn match_other(&mut self, matches: &clap::ArgMatches, has_flags: bool) -> Option<CommandMatch> {
+        if matches.get_flag(LABEL_ID) {
+            self.matched_command = Some(ServerCommand::Label);
+            Some(CommandMatch {
+                action_type: ActionType::Other,
+                action: None,
+            })
+        } else if matches.subcommand().is_none() && !has_flags {
+            self.matched_command = Some(ServerCommand::Direct);
+            Some(CommandMatch {
+                action_type: ActionType::Server,
+                action: Some(Action::Server(ServerAction::Direct)),
+            })
+        } else {
+            None
+        }
+    }
 }
 
 #[async_trait]
@@ -48,7 +98,12 @@ impl<S: Service> CommandProvider for ServerCliProvider<S> {
     fn get_commands(&self, cmd: clap::Command) -> clap::Command {
         let cmd = cmd.arg_required_else_help(false);
         let cmd = match &self.run_command {
-            CommandArg::Subcommand(sub) => cmd.subcommand(clap::Command::new(sub)),
+            CommandArg::Subcommand(sub) => {
+                let run_cmd = clap::Command::new(sub);
+                #[cfg(all(unix, feature = "daemonize"))]
+                let run_cmd = run_cmd.arg(daemonize_arg());
+                cmd.subcommand(run_cmd)
+            }
             CommandArg::ShortArg(arg) => cmd.arg(
                 clap::Arg::new(RUN_ID)
                     .short(*arg)
@@ -58,66 +113,105 @@ impl<S: Service> CommandProvider for ServerCliProvider<S> {
                 cmd.arg(clap::Arg::new(RUN_ID).long(arg).action(ArgAction::SetTrue))
             }
         };
-        cmd.arg(
+        #[cfg(all(unix, feature = "daemonize"))]
+        let cmd = match &self.run_command {
+            CommandArg::Subcommand(_) => cmd,
+            _ => cmd.arg(daemonize_arg().requires(RUN_ID)),
+        };
+        let cmd = cmd.arg(
             clap::Arg::new(LABEL_ID)
                 .long(LABEL_ID)
                 .action(ArgAction::SetTrue),
-        )
+        );
+        #[cfg(feature = "restart-on-change")]
+        let cmd = cmd.arg(
+            clap::Arg::new(RESTART_ON_CHANGE_ID)
+                .long(RESTART_ON_CHANGE_ID)
+                .help("Restart the service when the executable or watched files change")
+                .action(ArgAction::SetTrue),
+        );
+        cmd
     }
 
     fn matches(&mut self, matches: &clap::ArgMatches) -> Option<CommandMatch> {
-        let has_flags = matches
-            .ids()
-            .any(|i| matches.value_source(i.as_str()) != Some(ValueSource::DefaultValue));
+        #[cfg(feature = "restart-on-change")]
+        let restart_on_change = matches.get_flag(RESTART_ON_CHANGE_ID);
+        #[cfg(feature = "restart-on-change")]
+        if restart_on_change {
+            self.foreground_settings = self
+                .foreground_settings
+                .clone()
+                .with_restart_on_change(true);
+        }
 
-        match &self.run_command {
-            CommandArg::Subcommand(sub)
-                if matches!(
-                    matches.subcommand(), Some((sub_name, _)) if sub_name == sub) =>
-            {
-                self.matched_command = Some(ServerCommand::Run);
-                Some(CommandMatch {
-                    action_type: ActionType::Server,
-                    action: Some(Action::Server(ServerAction::Run)),
-                })
+        // Options that only modify how the service is run directly shouldn't prevent it from being
+        // matched
+        let has_flags = matches.ids().any(|i| {
+            #[cfg(feature = "restart-on-change")]
+            if i == RESTART_ON_CHANGE_ID {
+                return false;
             }
+            matches.value_source(i.as_str()) != Some(ValueSource::DefaultValue)
+        });
+
+        match &self.run_command {
+            CommandArg::Subcommand(sub) => match matches.subcommand() {
+                Some((sub_name, run_matches)) if sub_name == sub => self.match_run(run_matches),
+                _ => self.match_other(matches, has_flags),
+            },
             CommandArg::LongArg(_) | CommandArg::ShortArg(_) if matches.get_flag(RUN_ID) => {
-                self.matched_command = Some(ServerCommand::Run);
-                Some(CommandMatch {
-                    action_type: ActionType::Server,
-                    action: Some(Action::Server(ServerAction::Run)),
-                })
-            }
-            _ if matches.get_flag(LABEL_ID) => {
-                self.matched_command = Some(ServerCommand::Label);
-                Some(CommandMatch {
-                    action_type: ActionType::Other,
-                    action: None,
-                })
-            }
-            _ if matches.subcommand().is_none() && !has_flags => {
-                self.matched_command = Some(ServerCommand::Direct);
-                Some(CommandMatch {
-                    action_type: ActionType::Server,
-                    action: Some(Action::Server(ServerAction::Direct)),
-                })
+                self.match_run(matches)
             }
-            _ => None,
+            _ => self.match_other(matches, has_flags),
         }
     }
 
     async fn handle_input(mut self: Box<Self>) -> Result<CommandOutput, BoxedError> {
         match self.matched_command {
             Some(ServerCommand::Direct) => {
-                S::run_directly(self.input_data).await?;
+                S::run_directly(self.input_data, self.foreground_settings)
+                    .await
+                    .inspect_err(exit_on_shutdown_timeout)?;
                 Ok(CommandOutput::handled(None))
             }
             Some(ServerCommand::Run) => {
-                S::run_as_service(self.input_data).await?;
+                S::run_as_service(self.input_data)
+                    .await
+                    .inspect_err(exit_on_shutdown_timeout)?;
                 Ok(CommandOutput::handled(None))
             }
+            #[cfg(all(unix, feature = "daemonize"))]
+            Some(ServerCommand::Daemonize) => {
+                // The daemon is started with the same arguments, so this runs twice
+                if crate::is_daemonized() {
+                    S::run_as_service(self.input_data)
+                        .await
+                        .inspect_err(exit_on_shutdown_timeout)?;
+                    Ok(CommandOutput::handled(None))
+                } else {
+                    crate::daemonize()?;
+                    Ok(CommandOutput::handled("Daemon started".to_owned()))
+                }
+            }
             Some(ServerCommand::Label) => Ok(CommandOutput::handled(S::label().qualified_name())),
             None => Ok(CommandOutput::unhandled()),
         }
     }
 }
+
+#[cfg(all(unix, feature = "daemonize"))]
+fn daemonize_arg() -> clap::Arg {
+    clap::Arg::new(DAEMONIZE_ID)
+        .long(DAEMONIZE_ID)
+        .help("Detach from the terminal and run in the background without a service manager")
+        .action(ArgAction::SetTrue)
+}
+
+// Background services that missed the shutdown deadline may still be holding on to the runtime, so
+// returning normally isn't guaranteed to terminate the process
+fn exit_on_shutdown_timeout<E: std::fmt::Debug + Send + Sync + 'static>(error: &ServiceError<E>) {
+    if let ServiceError::ShutdownTimeout(..) = error {
+        error!("{error}");
+        std::process::exit(error.exit_code());
+    }
+}
@@ -0,0 +1,388 @@
+use std::io;
+use std::marker::PhantomData;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(windows)]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::control::{ControlRequest, ControlResponse, ServerStatus};
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext, ServiceRegistry};
+#[cfg(windows)]
+use tokio::sync::mpsc;
+use tracing::{debug, error};
+#[cfg(windows)]
+use windows_service::service::ServiceControlAccept;
+#[cfg(windows)]
+use windows_service::service_control_handler::ServiceStatusHandle;
+
+use crate::Handler;
+
+static STARTED: LazyLock<Instant> = LazyLock::new(Instant::now);
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Debug, Default)]
+pub struct ControlSettings {
+    pub(crate) signals: bool,
+    #[cfg(feature = "control")]
+    pub(crate) ipc: bool,
+}
+
+impl ControlSettings {
+    /// Maps `SIGTSTP`, `SIGCONT`, and `SIGHUP` to pause, resume, and reload. `SIGCONT` is ignored
+    /// unless the service is paused since systemd also sends it when stopping the service. On
+    /// Windows, the service manager's pause, continue, and parameter change controls are used
+    /// instead.
+    pub fn with_signals(mut self, signals: bool) -> Self {
+        self.signals = signals;
+        self
+    }
+
+    /// Accepts control requests from other processes over a local socket, such as the `pause`,
+    /// `resume`, `send`, and `ctl` client commands.
+    #[cfg(feature = "control")]
+    pub fn with_ipc(mut self, ipc: bool) -> Self {
+        self.ipc = ipc;
+        self
+    }
+}
+
+/// The control socket is bound before the handler is created so that startup fails early if
+/// another instance is already listening on it.
+#[cfg(any(unix, feature = "control"))]
+#[derive(Default)]
+pub(crate) struct ControlListener {
+    #[cfg(feature = "control")]
+    incoming: Option<tipsy::IpcStream>,
+}
+
+#[cfg(any(unix, feature = "control"))]
+impl ControlListener {
+    #[cfg_attr(not(feature = "control"), allow(clippy::extra_unused_type_parameters))]
+    pub(crate) async fn bind<T: Handler>() -> io::Result<Self> {
+        LazyLock::force(&STARTED);
+        #[cfg(feature = "control")]
+        if T::control_settings().ipc {
+            return Ok(Self {
+                incoming: Some(bind_control_socket(&T::label()).await?),
+            });
+        }
+        Ok(Self::default())
+    }
+}
+
+#[cfg(feature = "control")]
+async fn bind_control_socket(label: &daemon_slayer_core::Label) -> io::Result<tipsy::IpcStream> {
+    use daemon_slayer_core::control::control_socket_path;
+    use daemon_slayer_core::runtime_dir::create_runtime_dir;
+    use tipsy::{Endpoint, OnConflict};
+
+    create_runtime_dir(label)?;
+    let path = control_socket_path(label)?;
+    // A socket that still accepts connections belongs to a running instance. Anything else was
+    // left behind by a process that exited without cleaning up, so it's safe to replace.
+    if Endpoint::connect(path.clone()).await.is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!(
+                "Another instance is already listening on {}",
+                path.display()
+            ),
+        ));
+    }
+    let incoming = Endpoint::new(path.clone(), OnConflict::Overwrite)?.incoming()?;
+    #[cfg(unix)]
+    {
+        use std::fs::{self, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+    }
+    Ok(incoming)
+}
+
+#[cfg(any(unix, feature = "control"))]
+pub(crate) fn spawn_control_services<T: Handler>(
+    context: &ServiceContext,
+    listener: ControlListener,
+    handle: &T::ControlHandle,
+) {
+    let settings = T::control_settings();
+    #[cfg(unix)]
+    if settings.signals {
+        context.spawn(SignalControlService::<T>::new(handle.clone()));
+    }
+    #[cfg(feature = "control")]
+    if let Some(incoming) = listener.incoming {
+        context.spawn(IpcControlService::<T>::new(handle.clone(), incoming));
+    }
+    #[cfg(not(feature = "control"))]
+    let _ = listener;
+}
+
+pub(crate) async fn dispatch<T: Handler>(
+    handle: T::ControlHandle,
+    services: &ServiceRegistry,
+    request: ControlRequest,
+) -> ControlResponse {
+    debug!("Handling control request: {}", request.kind());
+    let result = match request {
+        ControlRequest::Pause => T::on_pause(handle).await.map(|_| {
+            PAUSED.store(true, Ordering::SeqCst);
+            None
+        }),
+        ControlRequest::Resume => T::on_resume(handle).await.map(|_| {
+            PAUSED.store(false, Ordering::SeqCst);
+            None
+        }),
+        ControlRequest::Reload => T::on_reload(handle).await.map(|_| None),
+        ControlRequest::Status => {
+            return ControlResponse::ServerStatus(ServerStatus {
+                pid: std::process::id(),
+                uptime_secs: STARTED.elapsed().as_secs(),
+                paused: PAUSED.load(Ordering::SeqCst),
+                background_services: services.running().len(),
+            });
+        }
+        ControlRequest::ListServices => {
+            return ControlResponse::Services {
+                names: services.running(),
+            };
+        }
+        ControlRequest::SetLogLevel {
+            level,
+            revert_after_secs,
+        } => {
+            // Invalid directives are the most likely error here so they're reported as-is
+            // instead of using the debug representation
+            return match T::on_log_level(handle, level, revert_after_secs.map(Duration::from_secs))
+                .await
+            {
+                Ok(()) => ControlResponse::Ok { output: None },
+                Err(e) => {
+                    error!("Error changing the log level: {e}");
+                    ControlResponse::Error {
+                        message: e.to_string(),
+                    }
+                }
+            };
+        }
+        ControlRequest::Command { name, args } => {
+            if !T::commands().contains(&name) {
+                return ControlResponse::Error {
+                    message: format!("Unknown command: {name}"),
+                };
+            }
+            T::on_command(handle, name, args).await
+        }
+    };
+    match result {
+        Ok(output) => ControlResponse::Ok { output },
+        Err(e) => {
+            error!("Error handling control request: {e:?}");
+            ControlResponse::Error {
+                message: format!("{e:?}"),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+struct SignalControlService<T: Handler> {
+    handle: T::ControlHandle,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(unix)]
+impl<T: Handler> SignalControlService<T> {
+    fn new(handle: T::ControlHandle) -> Self {
+        Self {
+            handle,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<T: Handler> BackgroundService for SignalControlService<T> {
+    fn name(&self) -> &str {
+        "signal_control_service"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut pause = signal(SignalKind::from_raw(libc::SIGTSTP))?;
+        let mut resume = signal(SignalKind::from_raw(libc::SIGCONT))?;
+        let mut reload = signal(SignalKind::hangup())?;
+        let cancellation_token = context.cancellation_token().clone();
+
+        loop {
+            let request = tokio::select! {
+                biased;
+                _ = cancellation_token.cancelled() => return Ok(()),
+                _ = pause.recv() => ControlRequest::Pause,
+                _ = resume.recv() => {
+                    // systemd sends SIGCONT along with SIGTERM when stopping the service, so it's
+                    // only treated as a resume if the service was paused and isn't shutting down
+                    if !PAUSED.load(Ordering::SeqCst) || cancellation_token.is_cancelled() {
+                        continue;
+                    }
+                    ControlRequest::Resume
+                }
+                _ = reload.recv() => ControlRequest::Reload,
+            };
+            dispatch::<T>(self.handle.clone(), &context.services(), request).await;
+        }
+    }
+}
+
+#[cfg(feature = "control")]
+struct IpcControlService<T: Handler> {
+    handle: T::ControlHandle,
+    incoming: tipsy::IpcStream,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(feature = "control")]
+impl<T: Handler> IpcControlService<T> {
+    fn new(handle: T::ControlHandle, incoming: tipsy::IpcStream) -> Self {
+        Self {
+            handle,
+            incoming,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "control")]
+impl<T: Handler> BackgroundService for IpcControlService<T> {
+    fn name(&self) -> &str {
+        "ipc_control_service"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        use daemon_slayer_core::control::{read_message, write_message};
+        use futures::StreamExt;
+        use tap::TapFallible;
+        use tokio::io::BufReader;
+
+        let incoming = self.incoming;
+        futures::pin_mut!(incoming);
+        let cancellation_token = context.cancellation_token().clone();
+
+        loop {
+            let conn = tokio::select! {
+                _ = cancellation_token.cancelled() => return Ok(()),
+                conn = incoming.next() => conn,
+            };
+            let Some(conn) = conn else {
+                return Ok(());
+            };
+            let Ok(conn) = conn.tap_err(|e| error!("Error accepting control connection: {e:?}"))
+            else {
+                continue;
+            };
+            let handle = self.handle.clone();
+            let services = context.services();
+            tokio::spawn(async move {
+                let (reader, mut writer) = tokio::io::split(conn);
+                let mut reader = BufReader::new(reader);
+                while let Ok(Some(request)) = read_message::<ControlRequest>(&mut reader)
+                    .await
+                    .tap_err(|e| error!("Error reading control request: {e:?}"))
+                {
+                    let response = dispatch::<T>(handle.clone(), &services, request).await;
+                    if write_message(&mut writer, &response)
+                        .await
+                        .tap_err(|e| error!("Error writing control response: {e:?}"))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(windows)]
+pub(crate) struct ScmControlService<T: Handler> {
+    handle: T::ControlHandle,
+    control_rx: mpsc::Receiver<ControlRequest>,
+    status_handle: Arc<Mutex<ServiceStatusHandle>>,
+    controls_accepted: ServiceControlAccept,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(windows)]
+impl<T: Handler> ScmControlService<T> {
+    pub(crate) fn new(
+        handle: T::ControlHandle,
+        control_rx: mpsc::Receiver<ControlRequest>,
+        status_handle: Arc<Mutex<ServiceStatusHandle>>,
+        controls_accepted: ServiceControlAccept,
+    ) -> Self {
+        Self {
+            handle,
+            control_rx,
+            status_handle,
+            controls_accepted,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl<T: Handler> BackgroundService for ScmControlService<T> {
+    fn name(&self) -> &str {
+        "scm_control_service"
+    }
+
+    async fn run(mut self, context: ServiceContext) -> Result<(), BoxedError> {
+        use std::time::Duration;
+
+        use tap::TapFallible;
+        use windows_service::service::{ServiceExitCode, ServiceState, ServiceStatus, ServiceType};
+
+        let cancellation_token = context.cancellation_token().clone();
+        loop {
+            let request = tokio::select! {
+                _ = cancellation_token.cancelled() => return Ok(()),
+                request = self.control_rx.recv() => request,
+            };
+            let Some(request) = request else {
+                return Ok(());
+            };
+            let new_state = match request {
+                ControlRequest::Pause => Some(ServiceState::Paused),
+                ControlRequest::Resume => Some(ServiceState::Running),
+                _ => None,
+            };
+            let response = dispatch::<T>(self.handle.clone(), &context.services(), request).await;
+            // The service manager waits for the state to change after a pause or continue request
+            let (ControlResponse::Ok { .. }, Some(current_state)) = (response, new_state) else {
+                continue;
+            };
+            self.status_handle
+                .lock()
+                .unwrap()
+                .set_service_status(ServiceStatus {
+                    service_type: ServiceType::OWN_PROCESS,
+                    current_state,
+                    controls_accepted: self.controls_accepted,
+                    exit_code: ServiceExitCode::Win32(0),
+                    checkpoint: 0,
+                    wait_hint: Duration::default(),
+                    process_id: None,
+                })
+                .tap_err(|e| error!("Error updating service status: {e:?}"))
+                .ok();
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./control_test.rs"]
+mod control_test;
@@ -0,0 +1,375 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use daemon_slayer_core::control::{ControlRequest, ControlResponse};
+use daemon_slayer_core::server::tracked::{
+    self, BackgroundService, Manager, ServiceContext, ServiceRegistry,
+};
+use daemon_slayer_core::{BoxedError, CancellationToken, Label};
+
+use super::{ControlListener, dispatch, spawn_control_services};
+use crate::{ControlSettings, Handler};
+
+#[derive(Clone, Default)]
+struct Calls(Arc<Mutex<Vec<String>>>);
+
+impl Calls {
+    fn record(&self, call: &str) {
+        self.0.lock().unwrap().push(call.to_owned());
+    }
+
+    fn get(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+struct TestHandler(Calls);
+
+impl Handler for TestHandler {
+    type InputData = Calls;
+    type Error = String;
+    type ControlHandle = Calls;
+
+    async fn new(_context: ServiceContext, input_data: Option<Calls>) -> Result<Self, String> {
+        Ok(Self(input_data.unwrap_or_default()))
+    }
+
+    fn label() -> Label {
+        "com.test.daemon_slayer_control_test".parse().unwrap()
+    }
+
+    fn control_settings() -> ControlSettings {
+        let settings = ControlSettings::default().with_signals(true);
+        #[cfg(feature = "control")]
+        let settings = settings.with_ipc(true);
+        settings
+    }
+
+    fn control_handle(&self) -> Calls {
+        self.0.clone()
+    }
+
+    async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), String> {
+        notify_ready();
+        Ok(())
+    }
+
+    async fn on_pause(calls: Calls) -> Result<(), String> {
+        calls.record("pause");
+        Ok(())
+    }
+
+    async fn on_resume(calls: Calls) -> Result<(), String> {
+        calls.record("resume");
+        Ok(())
+    }
+
+    fn commands() -> Vec<String> {
+        vec!["greet".to_owned()]
+    }
+
+    async fn on_command(
+        _calls: Calls,
+        _name: String,
+        args: Vec<String>,
+    ) -> Result<Option<String>, String> {
+        Ok(Some(format!("hello {}", args.join(" "))))
+    }
+}
+
+struct LogLevelHandler(Calls);
+
+impl Handler for LogLevelHandler {
+    type InputData = Calls;
+    type Error = String;
+    type ControlHandle = Calls;
+
+    async fn new(_context: ServiceContext, input_data: Option<Calls>) -> Result<Self, String> {
+        Ok(Self(input_data.unwrap_or_default()))
+    }
+
+    fn label() -> Label {
+        "com.test.daemon_slayer_control_test".parse().unwrap()
+    }
+
+    fn control_handle(&self) -> Calls {
+        self.0.clone()
+    }
+
+    async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), String> {
+        notify_ready();
+        Ok(())
+    }
+
+    async fn on_log_level(
+        calls: Calls,
+        level: String,
+        revert_after: Option<Duration>,
+    ) -> Result<(), BoxedError> {
+        if level == "app=loud" {
+            return Err("invalid filter directive".into());
+        }
+        calls.record(&format!("log-level {level} {revert_after:?}"));
+        Ok(())
+    }
+}
+
+fn manager() -> Manager {
+    Manager::new(CancellationToken::new(), tracked::Settings::default())
+}
+
+struct IdleService {
+    name: &'static str,
+    child: Option<&'static str>,
+}
+
+impl BackgroundService for IdleService {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        if let Some(child) = self.child {
+            context.spawn(IdleService {
+                name: child,
+                child: None,
+            });
+        }
+        context.cancellation_token().cancelled().await;
+        Ok(())
+    }
+}
+
+fn is_paused(response: ControlResponse) -> bool {
+    let ControlResponse::ServerStatus(status) = response else {
+        panic!("unexpected response {response:?}");
+    };
+    status.paused
+}
+
+#[tokio::test]
+async fn test_command() {
+    assert_eq!(
+        ControlResponse::Ok {
+            output: Some("hello world".to_owned())
+        },
+        dispatch::<TestHandler>(
+            Calls::default(),
+            &ServiceRegistry::default(),
+            ControlRequest::Command {
+                name: "greet".to_owned(),
+                args: vec!["world".to_owned()]
+            }
+        )
+        .await
+    );
+    assert_eq!(
+        ControlResponse::Error {
+            message: "Unknown command: other".to_owned()
+        },
+        dispatch::<TestHandler>(
+            Calls::default(),
+            &ServiceRegistry::default(),
+            ControlRequest::Command {
+                name: "other".to_owned(),
+                args: vec![]
+            }
+        )
+        .await
+    );
+}
+
+#[tokio::test]
+async fn test_log_level_without_logger() {
+    // Nothing registers a log level handler in these tests
+    let response = dispatch::<TestHandler>(
+        Calls::default(),
+        &ServiceRegistry::default(),
+        ControlRequest::SetLogLevel {
+            level: "debug".to_owned(),
+            revert_after_secs: None,
+        },
+    )
+    .await;
+    assert_eq!(
+        ControlResponse::Error {
+            message: "The service doesn't support changing the log level".to_owned()
+        },
+        response
+    );
+}
+
+#[tokio::test]
+async fn test_log_level() {
+    let calls = Calls::default();
+    let response = dispatch::<LogLevelHandler>(
+        calls.clone(),
+        &ServiceRegistry::default(),
+        ControlRequest::SetLogLevel {
+            level: "app=debug".to_owned(),
+            revert_after_secs: Some(600),
+        },
+    )
+    .await;
+    assert_eq!(ControlResponse::Ok { output: None }, response);
+    assert_eq!(vec!["log-level app=debug Some(600s)"], calls.get());
+
+    let response = dispatch::<LogLevelHandler>(
+        calls.clone(),
+        &ServiceRegistry::default(),
+        ControlRequest::SetLogLevel {
+            level: "app=loud".to_owned(),
+            revert_after_secs: None,
+        },
+    )
+    .await;
+    assert_eq!(
+        ControlResponse::Error {
+            message: "invalid filter directive".to_owned()
+        },
+        response
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_signals() {
+    let manager = manager();
+    let calls = Calls::default();
+    spawn_control_services::<TestHandler>(
+        &manager.get_context(),
+        ControlListener::default(),
+        &calls,
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let raise = |signal| {
+        // SAFETY: the signals are handled by the control service
+        unsafe { libc::raise(signal) };
+    };
+
+    // Not paused, so this isn't a resume
+    raise(libc::SIGCONT);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(calls.get().is_empty());
+
+    raise(libc::SIGTSTP);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(vec!["pause"], calls.get());
+    assert!(is_paused(
+        dispatch::<TestHandler>(
+            Calls::default(),
+            &manager.services(),
+            ControlRequest::Status
+        )
+        .await
+    ));
+
+    raise(libc::SIGCONT);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(vec!["pause", "resume"], calls.get());
+    assert!(!is_paused(
+        dispatch::<TestHandler>(
+            Calls::default(),
+            &manager.services(),
+            ControlRequest::Status
+        )
+        .await
+    ));
+
+    manager.cancel().await.unwrap();
+}
+
+#[cfg(feature = "control")]
+#[tokio::test]
+async fn test_ipc() {
+    use daemon_slayer_core::control::ControlClient;
+
+    let manager = manager();
+    let listener = ControlListener::bind::<TestHandler>().await.unwrap();
+    spawn_control_services::<TestHandler>(&manager.get_context(), listener, &Calls::default());
+
+    let client = ControlClient::new(&TestHandler::label()).unwrap();
+    assert_eq!(
+        ControlResponse::Ok {
+            output: Some("hello ipc".to_owned())
+        },
+        client
+            .send(&ControlRequest::Command {
+                name: "greet".to_owned(),
+                args: vec!["ipc".to_owned()]
+            })
+            .await
+            .unwrap()
+    );
+
+    manager.cancel().await.unwrap();
+}
+
+#[cfg(all(unix, feature = "control"))]
+#[tokio::test]
+async fn test_control_socket_in_use() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    use daemon_slayer_core::control::control_socket_path;
+
+    use super::bind_control_socket;
+
+    let label: Label = "com.test.daemon_slayer_control_socket_test"
+        .parse()
+        .unwrap();
+    let path = control_socket_path(&label).unwrap();
+    let incoming = bind_control_socket(&label).await.unwrap();
+    assert_eq!(
+        0o600,
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777
+    );
+    assert_eq!(
+        std::io::ErrorKind::AddrInUse,
+        bind_control_socket(&label)
+            .await
+            .map(|_| ())
+            .unwrap_err()
+            .kind()
+    );
+
+    // The socket file is left behind once nothing is listening on it
+    drop(incoming);
+    bind_control_socket(&label).await.unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_services() {
+    let manager = manager();
+    let context = manager.get_context();
+    context.spawn(IdleService {
+        name: "parent",
+        child: Some("child"),
+    });
+    context.spawn(IdleService {
+        name: "other",
+        child: None,
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let services = manager.services();
+    assert_eq!(
+        ControlResponse::Services {
+            names: vec!["child".to_owned(), "other".to_owned(), "parent".to_owned()]
+        },
+        dispatch::<TestHandler>(Calls::default(), &services, ControlRequest::ListServices).await
+    );
+    let ControlResponse::ServerStatus(status) =
+        dispatch::<TestHandler>(Calls::default(), &services, ControlRequest::Status).await
+    else {
+        panic!("expected a status response");
+    };
+    assert_eq!(3, status.background_services);
+    assert_eq!(std::process::id(), status.pid);
+
+    manager.cancel().await.unwrap();
+    assert_eq!(
+        ControlResponse::Services { names: vec![] },
+        dispatch::<TestHandler>(Calls::default(), &services, ControlRequest::ListServices).await
+    );
+}
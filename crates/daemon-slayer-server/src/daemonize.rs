@@ -0,0 +1,251 @@
+use std::ffi::{CString, OsString};
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::sync::{Mutex, OnceLock};
+use std::{env, io, mem, ptr};
+
+use tap::TapFallible;
+use tracing::warn;
+
+use crate::InstanceLockError;
+
+// Holds the descriptor of the pipe used to report the startup status back to the original process
+const DAEMONIZED_ENV_VAR: &str = "DAEMON_SLAYER_DAEMONIZED";
+const STARTED: libc::c_int = 0;
+// Followed by the PID of the running instance, or 0 if it's unknown. Any other status is an errno.
+const ALREADY_RUNNING: libc::c_int = -1;
+
+// None if the process wasn't daemonized. Otherwise, the pipe is taken once the status is reported.
+static DAEMON_PIPE: OnceLock<Option<Mutex<Option<File>>>> = OnceLock::new();
+
+fn daemon_pipe() -> &'static Option<Mutex<Option<File>>> {
+    // The environment is only read here, never modified, so it's safe to call from any thread. The
+    // variable is still set in processes started by the daemon, but the descriptor is closed on
+    // exec so it's only valid in the daemon itself.
+    DAEMON_PIPE.get_or_init(|| {
+        let fd = env::var(DAEMONIZED_ENV_VAR).ok()?.parse::<RawFd>().ok()?;
+        if !is_pipe(fd) {
+            return None;
+        }
+        set_cloexec(fd).ok()?;
+        // SAFETY: the descriptor was inherited from daemonize and isn't used anywhere else
+        Some(Mutex::new(Some(unsafe { File::from_raw_fd(fd) })))
+    })
+}
+
+/// Whether the current process was started by [`daemonize`].
+pub fn is_daemonized() -> bool {
+    daemon_pipe().is_some()
+}
+
+// Lets the original process return from daemonize now that the daemon has tried to take the
+// instance lock
+pub(crate) fn notify_daemon_status(result: Result<(), &InstanceLockError>) {
+    let status = match result {
+        Ok(()) => [STARTED, 0],
+        Err(InstanceLockError::AlreadyRunning { pid, .. }) => [
+            ALREADY_RUNNING,
+            pid.and_then(|pid| libc::c_int::try_from(pid).ok())
+                .unwrap_or(0),
+        ],
+        Err(InstanceLockError::Io { source, .. }) => {
+            [source.raw_os_error().unwrap_or(libc::EIO), 0]
+        }
+    };
+    if let Some(pipe) = daemon_pipe()
+        && let Some(mut pipe) = pipe.lock().unwrap().take()
+    {
+        pipe.write_all(&[status[0].to_ne_bytes(), status[1].to_ne_bytes()].concat())
+            .tap_err(|e| warn!("Unable to report the daemon's startup status: {e:?}"))
+            .ok();
+    }
+}
+
+/// Detaches from the terminal using the double-fork sequence and re-executes the current binary
+/// with the same arguments. The new process runs from `/` in its own session with stdio redirected
+/// to `/dev/null`, and [`is_daemonized`] returns `true` there.
+///
+/// Returns in the original process once the daemon has acquired its instance lock, or with an
+/// error if it exits before then. The runtime may already be running other threads, so the child
+/// only makes async-signal-safe calls before `execve`.
+pub fn daemonize() -> io::Result<()> {
+    // The daemon writes its startup status here. The write end stays open across exec so the
+    // daemon can report once it has taken the instance lock. It's closed if the daemon exits, so
+    // reading zero bytes means it failed to start.
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for both ends of the pipe
+    check(unsafe { libc::pipe(fds.as_mut_ptr()) })?;
+    // SAFETY: both descriptors were just created and aren't owned by anything else
+    let (read_fd, write_fd) =
+        unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    // Other threads may spawn processes while this is running, so the pipe must not leak into them
+    set_cloexec(read_fd.as_raw_fd())?;
+    set_cloexec(write_fd.as_raw_fd())?;
+
+    // Allocate everything up front since allocating after forking from a multi-threaded process
+    // could deadlock
+    let exe = to_c_string(env::current_exe()?.into_os_string())?;
+    let args = env::args_os()
+        .map(to_c_string)
+        .collect::<io::Result<Vec<_>>>()?;
+    let vars = env::vars_os()
+        .filter(|(key, _)| key != DAEMONIZED_ENV_VAR)
+        .map(|(key, value)| {
+            let mut var = key;
+            var.push("=");
+            var.push(value);
+            to_c_string(var)
+        })
+        .chain([to_c_string(
+            format!("{DAEMONIZED_ENV_VAR}={}", write_fd.as_raw_fd()).into(),
+        )])
+        .collect::<io::Result<Vec<_>>>()?;
+    let argv = null_terminated(&args);
+    let envp = null_terminated(&vars);
+    let dev_null = c"/dev/null";
+    let root = c"/";
+    let raw_write_fd = write_fd.as_raw_fd();
+
+    // SAFETY: the child only calls async-signal-safe functions until it execs or exits
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => unsafe {
+            libc::close(read_fd.as_raw_fd());
+            // Start a new session so the terminal can't send signals to the daemon
+            if libc::setsid() == -1 {
+                fail(raw_write_fd);
+            }
+            // Fork again so the daemon isn't a session leader and can never acquire a terminal
+            match libc::fork() {
+                -1 => fail(raw_write_fd),
+                0 => {}
+                _ => libc::_exit(0),
+            }
+            if libc::chdir(root.as_ptr()) == -1 {
+                fail(raw_write_fd);
+            }
+            libc::umask(0o027);
+            let null_fd = libc::open(dev_null.as_ptr(), libc::O_RDWR);
+            if null_fd == -1 {
+                fail(raw_write_fd);
+            }
+            for fd in [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+                if libc::dup2(null_fd, fd) == -1 {
+                    fail(raw_write_fd);
+                }
+            }
+            if null_fd > libc::STDERR_FILENO {
+                libc::close(null_fd);
+            }
+            // Keep the pipe open in the daemon
+            if libc::fcntl(raw_write_fd, libc::F_SETFD, 0) == -1 {
+                fail(raw_write_fd);
+            }
+            libc::execve(exe.as_ptr(), argv.as_ptr(), envp.as_ptr());
+            fail(raw_write_fd);
+        },
+        pid => {
+            // Only the daemon should hold the write end, otherwise reading would never finish
+            drop(write_fd);
+            let mut status = 0;
+            // Reap the intermediate process, which exits right after the second fork
+            // SAFETY: pid refers to the child that was just forked
+            check(unsafe { libc::waitpid(pid, &mut status, 0) })?;
+            read_status(read_fd.as_raw_fd())
+        }
+    }
+}
+
+fn to_c_string(value: OsString) -> io::Result<CString> {
+    CString::new(value.as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn null_terminated(values: &[CString]) -> Vec<*const libc::c_char> {
+    values
+        .iter()
+        .map(|value| value.as_ptr())
+        .chain([ptr::null()])
+        .collect()
+}
+
+fn check(result: libc::c_int) -> io::Result<()> {
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+// Only async-signal-safe calls are allowed here since this runs in the forked child
+unsafe fn fail(write_fd: libc::c_int) -> ! {
+    unsafe {
+        // Zero is reserved for reporting success
+        let errno = match io::Error::last_os_error().raw_os_error() {
+            Some(0) | None => libc::EIO,
+            Some(errno) => errno,
+        };
+        libc::write(
+            write_fd,
+            (&errno as *const libc::c_int).cast(),
+            size_of::<libc::c_int>(),
+        );
+        libc::_exit(1)
+    }
+}
+
+fn is_pipe(fd: RawFd) -> bool {
+    // SAFETY: stat only contains integers, so zeroed memory is valid
+    let mut stat: libc::stat = unsafe { mem::zeroed() };
+    // SAFETY: stat is valid for writes and invalid descriptors are reported as an error
+    unsafe { libc::fstat(fd, &mut stat) == 0 && stat.st_mode & libc::S_IFMT == libc::S_IFIFO }
+}
+
+fn set_cloexec(fd: RawFd) -> io::Result<()> {
+    // SAFETY: no memory is accessed
+    check(unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) })
+}
+
+fn read_status(read_fd: RawFd) -> io::Result<()> {
+    let exited = || io::Error::other("The daemon exited before it finished starting");
+    match read_int(read_fd)?.ok_or_else(exited)? {
+        STARTED => Ok(()),
+        ALREADY_RUNNING => {
+            let pid = read_int(read_fd)?.filter(|pid| *pid > 0);
+            Err(io::Error::new(
+                io::ErrorKind::ResourceBusy,
+                match pid {
+                    Some(pid) => format!("Another instance is already running with PID {pid}"),
+                    None => "Another instance is already running".to_owned(),
+                },
+            ))
+        }
+        errno => Err(io::Error::from_raw_os_error(errno)),
+    }
+}
+
+// Returns None if the pipe was closed before a full value was written
+fn read_int(read_fd: RawFd) -> io::Result<Option<libc::c_int>> {
+    let mut value: libc::c_int = 0;
+    loop {
+        // SAFETY: the buffer is large enough to hold the requested number of bytes
+        let read = unsafe {
+            libc::read(
+                read_fd,
+                (&mut value as *mut libc::c_int).cast(),
+                size_of::<libc::c_int>(),
+            )
+        };
+        match read {
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            n if n == size_of::<libc::c_int>() as isize => return Ok(Some(value)),
+            _ => return Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./daemonize_test.rs"]
+mod daemonize_test;
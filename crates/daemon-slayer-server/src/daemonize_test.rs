@@ -0,0 +1,38 @@
+use std::io::Write;
+use std::os::fd::AsRawFd;
+
+use super::{ALREADY_RUNNING, STARTED, read_status};
+
+#[test]
+fn test_started() {
+    let (reader, mut writer) = std::io::pipe().unwrap();
+    writer.write_all(&STARTED.to_ne_bytes()).unwrap();
+    read_status(reader.as_raw_fd()).unwrap();
+}
+
+#[test]
+fn test_failed() {
+    let (reader, mut writer) = std::io::pipe().unwrap();
+    writer.write_all(&libc::ENOENT.to_ne_bytes()).unwrap();
+    let error = read_status(reader.as_raw_fd()).unwrap_err();
+    assert_eq!(Some(libc::ENOENT), error.raw_os_error());
+}
+
+#[test]
+fn test_exited() {
+    let (reader, writer) = std::io::pipe().unwrap();
+    drop(writer);
+    let error = read_status(reader.as_raw_fd()).unwrap_err();
+    assert_eq!(None, error.raw_os_error());
+}
+
+#[test]
+fn test_already_running() {
+    let (reader, mut writer) = std::io::pipe().unwrap();
+    writer
+        .write_all(&[ALREADY_RUNNING.to_ne_bytes(), 42_i32.to_ne_bytes()].concat())
+        .unwrap();
+    let error = read_status(reader.as_raw_fd()).unwrap_err();
+    assert_eq!(std::io::ErrorKind::ResourceBusy, error.kind());
+    assert!(error.to_string().contains("42"));
+}
@@ -0,0 +1,213 @@
+use std::future::Future;
+#[cfg(feature = "restart-on-change")]
+use std::path::PathBuf;
+use std::time::Duration;
+
+use daemon_slayer_core::CancellationToken;
+use daemon_slayer_core::server::tracked::{Manager, ServiceRegistry};
+use tracing::{info, warn};
+
+#[cfg(any(unix, feature = "control"))]
+use crate::control::{ControlListener, spawn_control_services};
+use crate::shutdown::cancel_with_deadline;
+#[cfg(feature = "single-instance")]
+use crate::single_instance::acquire_instance_lock;
+use crate::{Handler, ServiceError};
+
+const FORCED_EXIT_CODE: i32 = 130;
+
+/// Settings for running the handler in the foreground during development.
+#[derive(Clone, Debug, Default)]
+pub struct ForegroundSettings {
+    #[cfg(feature = "restart-on-change")]
+    restart_on_change: bool,
+    #[cfg(feature = "restart-on-change")]
+    watch_paths: Vec<PathBuf>,
+}
+
+impl ForegroundSettings {
+    /// Restarts the process when the current executable or any of the watch paths change.
+    #[cfg(feature = "restart-on-change")]
+    pub fn with_restart_on_change(mut self, restart_on_change: bool) -> Self {
+        self.restart_on_change = restart_on_change;
+        self
+    }
+
+    #[cfg(feature = "restart-on-change")]
+    pub fn with_watch_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.watch_paths.push(path.into());
+        self
+    }
+
+    #[cfg(feature = "restart-on-change")]
+    pub fn with_watch_paths(mut self, paths: Vec<impl Into<PathBuf>>) -> Self {
+        let mut paths = paths.into_iter().map(Into::into).collect();
+        self.watch_paths.append(&mut paths);
+        self
+    }
+}
+
+/// Runs the handler outside of the service manager. No readiness notifications are sent and
+/// Ctrl-C stops the handler while reporting which background services are still shutting down.
+/// Pressing Ctrl-C a second time exits immediately.
+pub async fn run_in_foreground<T: Handler>(
+    input_data: Option<T::InputData>,
+    settings: ForegroundSettings,
+) -> Result<(), ServiceError<T::Error>> {
+    #[cfg(feature = "single-instance")]
+    let _instance_lock = acquire_instance_lock::<T>()?;
+    #[cfg(any(unix, feature = "control"))]
+    let control_listener = ControlListener::bind::<T>().await.map_err(|e| {
+        ServiceError::InitializationFailure("Error binding control socket".to_owned(), e.into())
+    })?;
+    let manager = Manager::new(CancellationToken::new(), T::background_service_settings());
+    let context = manager.get_context();
+    let services = manager.services();
+
+    // Linux appends " (deleted)" to the path once the binary is replaced, so it needs to be
+    // resolved before anything changes
+    #[cfg(feature = "restart-on-change")]
+    let exe = std::env::current_exe();
+    #[cfg(feature = "restart-on-change")]
+    let changed = watch_for_changes(&context, &settings, exe.as_ref().ok().cloned());
+    #[cfg(not(feature = "restart-on-change"))]
+    let changed = {
+        let _ = settings;
+        std::future::pending::<()>()
+    };
+
+    let handler = T::new(manager.get_context(), input_data)
+        .await
+        .map_err(|e| ServiceError::ExecutionFailure(e, None))?;
+    #[cfg(any(unix, feature = "control"))]
+    spawn_control_services::<T>(&context, control_listener, &handler.control_handle());
+
+    let run = handler.run_service(|| info!("Service is running, press Ctrl-C to stop"));
+    tokio::pin!(run);
+    #[cfg_attr(not(feature = "restart-on-change"), allow(unused_variables))]
+    let (result, restart) = tokio::select! {
+        result = &mut run => (result, false),
+        _ = tokio::signal::ctrl_c() => {
+            info!("Stopping service, press Ctrl-C again to exit immediately");
+            context.cancel_all();
+            (with_progress(&mut run, &services).await, false)
+        }
+        _ = changed => {
+            info!("Change detected, restarting");
+            context.cancel_all();
+            (with_progress(&mut run, &services).await, true)
+        }
+    };
+
+    let shutdown_result = with_progress(
+        cancel_with_deadline(manager, T::shutdown_deadline()),
+        &services,
+    )
+    .await;
+    if shutdown_result.is_ok() {
+        info!("shutdown successful");
+    }
+    let result = ServiceError::from_shutdown_result(result, shutdown_result);
+
+    #[cfg(feature = "restart-on-change")]
+    if restart {
+        if let Err(e) = &result {
+            warn!("Service stopped with an error before restarting: {e:?}");
+        }
+        return Err(restart_process(exe));
+    }
+    result
+}
+
+async fn with_progress<F: Future>(future: F, services: &ServiceRegistry) -> F::Output {
+    tokio::pin!(future);
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    // The first tick completes immediately
+    interval.tick().await;
+    loop {
+        tokio::select! {
+            output = &mut future => return output,
+            _ = interval.tick() => {
+                let running = services.running();
+                if running.is_empty() {
+                    info!("Waiting for the service to stop");
+                } else {
+                    info!("Waiting for background services to stop: {}", running.join(", "));
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                warn!("Exiting without waiting for the service to stop");
+                std::process::exit(FORCED_EXIT_CODE);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "restart-on-change")]
+async fn watch_for_changes(
+    context: &daemon_slayer_core::server::tracked::ServiceContext,
+    settings: &ForegroundSettings,
+    exe: Option<PathBuf>,
+) {
+    use daemon_slayer_core::server::EventStore;
+    use daemon_slayer_file_watcher::FileWatcher;
+    use futures::StreamExt;
+
+    if !settings.restart_on_change {
+        return std::future::pending().await;
+    }
+    let mut builder = FileWatcher::builder().with_watch_paths(settings.watch_paths.clone());
+    match exe {
+        Some(exe) => builder = builder.with_watch_path(exe),
+        None => warn!("Unable to locate the current executable, it will not be watched"),
+    }
+    let file_watcher = builder.build();
+    let mut events = file_watcher.get_event_store().subscribe_events();
+    context.spawn(file_watcher);
+
+    while let Some(event) = events.next().await {
+        if let Ok(paths) = event {
+            info!("Detected changes in {paths:?}");
+            return;
+        }
+    }
+    std::future::pending().await
+}
+
+// The binary may have been rebuilt, so the handler needs to be started from a fresh process
+#[cfg(feature = "restart-on-change")]
+fn restart_process<E: std::fmt::Debug + Send + Sync + 'static>(
+    exe: std::io::Result<PathBuf>,
+) -> ServiceError<E> {
+    let exe = match exe {
+        Ok(exe) => exe,
+        Err(e) => {
+            return ServiceError::InitializationFailure(
+                "Unable to locate the current executable".to_owned(),
+                Box::new(e),
+            );
+        }
+    };
+    let mut command = std::process::Command::new(exe);
+    command.args(std::env::args_os().skip(1));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        ServiceError::InitializationFailure(
+            "Failed to restart the process".to_owned(),
+            Box::new(command.exec()),
+        )
+    }
+    #[cfg(not(unix))]
+    {
+        match command.status() {
+            Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+            Err(e) => ServiceError::InitializationFailure(
+                "Failed to restart the process".to_owned(),
+                Box::new(e),
+            ),
+        }
+    }
+}
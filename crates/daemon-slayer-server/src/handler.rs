@@ -1,26 +1,100 @@
 use std::fmt;
+use std::time::Duration;
 
-use daemon_slayer_core::Label;
-use daemon_slayer_core::server::background_service::{self, ServiceContext};
+use daemon_slayer_core::server::tracked::{self, ServiceContext};
+use daemon_slayer_core::{BoxedError, Label};
 use futures::Future;
 
+use crate::ControlSettings;
+
 pub trait Handler: Sized + Send + Sync + 'static {
     type InputData: Clone + Send + Sync + 'static;
     type Error: fmt::Debug + Send + Sync + 'static;
+    /// Passed to the control hooks such as [`Handler::on_pause`]. The handler is owned by
+    /// [`Handler::run_service`] while they run, so any state they share with it should be reachable
+    /// from here, such as through an `Arc` or a channel. Use `()` if the hooks aren't needed.
+    type ControlHandle: Clone + Send + Sync + 'static;
 
     fn new(
         context: ServiceContext,
         input_data: Option<Self::InputData>,
     ) -> impl Future<Output = Result<Self, Self::Error>> + Send;
 
-    fn background_service_settings() -> background_service::Settings {
-        background_service::Settings::default()
+    fn background_service_settings() -> tracked::Settings {
+        tracked::Settings::default()
+    }
+
+    /// Maximum time to wait for background services to stop after the handler returns. If it
+    /// expires, any services that are still running are reported in
+    /// [`crate::ServiceError::ShutdownTimeout`].
+    fn shutdown_deadline() -> Option<Duration> {
+        None
+    }
+
+    /// Holds a lock in the runtime directory while the service is running so a second instance
+    /// with the same label fails to start. The PID is also written alongside the lock.
+    #[cfg(feature = "single-instance")]
+    fn single_instance() -> bool {
+        false
+    }
+
+    /// Controls how pause, resume, reload, and custom commands are delivered to the hooks below.
+    /// Nothing is dispatched by default.
+    fn control_settings() -> ControlSettings {
+        ControlSettings::default()
     }
 
     fn label() -> Label;
 
+    /// Called once after [`Handler::new`] to create the handle given to the control hooks.
+    fn control_handle(&self) -> Self::ControlHandle;
+
     fn run_service<F: FnOnce() + Send>(
         self,
         notify_ready: F,
     ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    fn on_pause(
+        _handle: Self::ControlHandle,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async { Ok(()) }
+    }
+
+    fn on_resume(
+        _handle: Self::ControlHandle,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async { Ok(()) }
+    }
+
+    fn on_reload(
+        _handle: Self::ControlHandle,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async { Ok(()) }
+    }
+
+    /// The level is passed through as given, such as `debug` or `my_crate::db=trace`. If
+    /// `revert_after` is set, the previous level should be restored once it elapses. By default,
+    /// this uses the logger's reload handle, which is registered automatically when the logger is
+    /// built. The request fails if logging isn't configured.
+    fn on_log_level(
+        _handle: Self::ControlHandle,
+        level: String,
+        revert_after: Option<Duration>,
+    ) -> impl Future<Output = Result<(), BoxedError>> + Send {
+        async move { daemon_slayer_core::control::set_log_level(&level, revert_after) }
+    }
+
+    /// Names of the commands accepted by [`Handler::on_command`]. Anything else is rejected before
+    /// reaching the handler.
+    fn commands() -> Vec<String> {
+        Vec::new()
+    }
+
+    fn on_command(
+        _handle: Self::ControlHandle,
+        _name: String,
+        _args: Vec<String>,
+    ) -> impl Future<Output = Result<Option<String>, Self::Error>> + Send {
+        async { Ok(None) }
+    }
 }
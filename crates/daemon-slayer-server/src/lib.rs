@@ -1,24 +1,42 @@
 #[cfg(feature = "cli")]
 pub mod cli;
+mod control;
+#[cfg(all(unix, feature = "daemonize"))]
+mod daemonize;
+mod foreground;
 mod handler;
 pub mod platform;
+#[cfg(all(unix, feature = "privileges"))]
+mod privileges;
 mod service;
 mod service_error;
+mod shutdown;
+#[cfg(feature = "single-instance")]
+mod single_instance;
 #[cfg(feature = "socket-activation")]
 pub mod socket_activation;
 
+pub use control::ControlSettings;
 pub use daemon_slayer_core::AsAny;
-pub use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
+pub use daemon_slayer_core::control::{ControlRequest, ControlResponse, ServerStatus};
+pub use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 pub use daemon_slayer_core::server::{BroadcastEventStore, EventStore};
 pub use daemon_slayer_core::signal::{
     Client as SignalHandlerClient, Handler as SignalHandler, Signal,
 };
 pub use daemon_slayer_macros::*;
+#[cfg(all(unix, feature = "daemonize"))]
+pub use daemonize::*;
+pub use foreground::*;
 pub use handler::*;
+#[cfg(all(unix, feature = "privileges"))]
+pub use privileges::*;
 #[cfg(target_os = "linux")]
 pub use sd_notify;
 pub use service::*;
 pub use service_error::*;
+#[cfg(feature = "single-instance")]
+pub use single_instance::{InstanceLock, InstanceLockError};
 #[cfg(windows)]
 pub use windows_service;
 pub use {futures, tokio};
@@ -1,21 +1,35 @@
 use daemon_slayer_core::CancellationToken;
-use daemon_slayer_core::server::background_service::Manager;
+use daemon_slayer_core::server::tracked::Manager;
 #[cfg_attr(target_os = "macos", allow(unused_imports))]
 use tap::TapFallible;
 use tracing::info;
 #[cfg_attr(target_os = "macos", allow(unused_imports))]
 use tracing::{error, warn};
 
+use crate::control::{ControlListener, spawn_control_services};
+use crate::shutdown::cancel_with_deadline;
+#[cfg(feature = "single-instance")]
+use crate::single_instance::acquire_instance_lock;
 use crate::{Handler, ServiceError};
 
 pub async fn run_as_service<T: Handler>(
     input_data: Option<T::InputData>,
 ) -> Result<(), ServiceError<T::Error>> {
+    #[cfg(feature = "single-instance")]
+    let _instance_lock = acquire_instance_lock::<T>()?;
+    let control_listener = ControlListener::bind::<T>().await.map_err(|e| {
+        ServiceError::InitializationFailure("Error binding control socket".to_owned(), e.into())
+    })?;
     let manager = Manager::new(CancellationToken::new(), T::background_service_settings());
 
     let handler = T::new(manager.get_context(), input_data)
         .await
         .map_err(|e| ServiceError::ExecutionFailure(e, None))?;
+    spawn_control_services::<T>(
+        &manager.get_context(),
+        control_listener,
+        &handler.control_handle(),
+    );
 
     let result = handler
         .run_service(|| {
@@ -31,7 +45,9 @@ pub async fn run_as_service<T: Handler>(
         .tap_err(|e| warn!("Error sending stopping notification: {e:?}"))
         .ok();
 
-    let background_service_errors = manager.cancel().await;
-    info!("shutdown successful");
-    ServiceError::from_service_result(result, background_service_errors)
+    let shutdown_result = cancel_with_deadline(manager, T::shutdown_deadline()).await;
+    if shutdown_result.is_ok() {
+        info!("shutdown successful");
+    }
+    ServiceError::from_shutdown_result(result, shutdown_result)
 }
@@ -2,17 +2,24 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use daemon_slayer_core::CancellationToken;
-use daemon_slayer_core::server::background_service::Manager;
+use daemon_slayer_core::control::ControlRequest;
+use daemon_slayer_core::server::tracked::Manager;
 use daemon_slayer_core::signal::{self, Signal};
 use tap::TapFallible;
 use tokio::runtime::Runtime;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{error, info};
 use windows_service::service::{
     ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus, ServiceType,
 };
 use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
 
+use crate::control::ScmControlService;
+#[cfg(feature = "control")]
+use crate::control::{ControlListener, spawn_control_services};
+use crate::shutdown::cancel_with_deadline;
+#[cfg(feature = "single-instance")]
+use crate::single_instance::acquire_instance_lock;
 use crate::{Handler, ServiceError};
 
 // From https://helgeklein.com/blog/per-user-services-in-windows-info-and-configuration
@@ -35,10 +42,26 @@ async fn get_service_main_impl<T: Handler>(
     let (signal_tx, _) = broadcast::channel(32);
     signal::set_sender(signal_tx.clone());
 
+    #[cfg(feature = "single-instance")]
+    let _instance_lock = acquire_instance_lock::<T>()?;
+    #[cfg(feature = "control")]
+    let control_listener = ControlListener::bind::<T>().await.map_err(|e| {
+        ServiceError::InitializationFailure("Error binding control socket".to_owned(), e.into())
+    })?;
     let manager = Manager::new(CancellationToken::new(), T::background_service_settings());
     let handler = T::new(manager.get_context(), input_data)
         .await
         .map_err(|e| ServiceError::ExecutionFailure(e, None))?;
+    let control_handle = handler.control_handle();
+    #[cfg(feature = "control")]
+    spawn_control_services::<T>(&manager.get_context(), control_listener, &control_handle);
+
+    let mut controls_accepted = ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN;
+    if T::control_settings().signals {
+        controls_accepted |=
+            ServiceControlAccept::PAUSE_CONTINUE | ServiceControlAccept::PARAM_CHANGE;
+    }
+    let (control_tx, control_rx) = mpsc::channel(32);
 
     let windows_service_event_handler = move |control_event| -> ServiceControlHandlerResult {
         match control_event {
@@ -63,6 +86,11 @@ async fn get_service_main_impl<T: Handler>(
                     .ok();
                 ServiceControlHandlerResult::NoError
             }
+            ServiceControl::Pause => send_control_request(&control_tx, ControlRequest::Pause),
+            ServiceControl::Continue => send_control_request(&control_tx, ControlRequest::Resume),
+            ServiceControl::ParamChange => {
+                send_control_request(&control_tx, ControlRequest::Reload)
+            }
             _ => ServiceControlHandlerResult::NotImplemented,
         }
     };
@@ -79,6 +107,15 @@ async fn get_service_main_impl<T: Handler>(
             ))?;
         }
     };
+    if T::control_settings().signals {
+        manager.get_context().spawn(ScmControlService::<T>::new(
+            control_handle,
+            control_rx,
+            status_handle.clone(),
+            controls_accepted,
+        ));
+    }
+
     let status_handle_ = status_handle.clone();
     let notify_ready = move || {
         info!("Setting status to 'running'");
@@ -88,7 +125,7 @@ async fn get_service_main_impl<T: Handler>(
             .set_service_status(ServiceStatus {
                 service_type: ServiceType::OWN_PROCESS,
                 current_state: ServiceState::Running,
-                controls_accepted: ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+                controls_accepted,
                 exit_code: ServiceExitCode::Win32(0),
                 checkpoint: 0,
                 wait_hint: Duration::default(),
@@ -100,14 +137,18 @@ async fn get_service_main_impl<T: Handler>(
     };
 
     let result = handler.run_service(notify_ready).await;
+    let result = ServiceError::from_shutdown_result(
+        result,
+        cancel_with_deadline(manager, T::shutdown_deadline()).await,
+    );
 
+    // Application-defined codes need to be reported as service-specific so the SCM doesn't
+    // interpret them as Win32 error codes
     let exit_code = match &result {
-        Ok(()) => 0,
-        Err(_) => 1,
+        Ok(()) => ServiceExitCode::Win32(0),
+        Err(e) => ServiceExitCode::ServiceSpecific(e.exit_code() as u32),
     };
 
-    let background_service_errors = manager.cancel().await;
-
     {
         let handle = status_handle.lock().unwrap();
         handle
@@ -115,7 +156,7 @@ async fn get_service_main_impl<T: Handler>(
                 service_type: ServiceType::OWN_PROCESS,
                 current_state: ServiceState::Stopped,
                 controls_accepted: ServiceControlAccept::empty(),
-                exit_code: ServiceExitCode::Win32(exit_code),
+                exit_code,
                 checkpoint: 0,
                 wait_hint: Duration::default(),
                 process_id: None,
@@ -129,7 +170,25 @@ async fn get_service_main_impl<T: Handler>(
     }
 
     drop(status_handle);
-    ServiceError::from_service_result(result, background_service_errors)
+    result
+}
+
+#[deprecated(note = "Use `run_in_foreground` instead")]
+pub async fn get_direct_handler<T: Handler>(
+    input_data: Option<T::InputData>,
+) -> Result<(), ServiceError<T::Error>> {
+    crate::run_in_foreground::<T>(input_data, Default::default()).await
+}
+
+fn send_control_request(
+    control_tx: &mpsc::Sender<ControlRequest>,
+    request: ControlRequest,
+) -> ServiceControlHandlerResult {
+    control_tx
+        .try_send(request)
+        .tap_err(|e| error!("Error sending control request: {e:?}"))
+        .ok();
+    ServiceControlHandlerResult::NoError
 }
 
 fn set_env_vars<T: Handler>() {
@@ -165,17 +224,3 @@ fn set_env_vars<T: Handler>() {
         }
     }
 }
-
-pub async fn get_direct_handler<T: Handler>(
-    input_data: Option<T::InputData>,
-) -> Result<(), ServiceError<T::Error>> {
-    let manager = Manager::new(CancellationToken::new(), T::background_service_settings());
-    let handler = T::new(manager.get_context(), input_data)
-        .await
-        .map_err(|e| ServiceError::ExecutionFailure(e, None))?;
-
-    let result = handler.run_service(|| {}).await;
-    let background_service_errors = manager.cancel().await;
-    info!("shutdown successful");
-    ServiceError::from_service_result(result, background_service_errors)
-}
@@ -0,0 +1,313 @@
+use std::ffi::{CString, c_char};
+use std::{io, mem, ptr};
+
+#[cfg(target_os = "linux")]
+pub use caps::Capability;
+#[cfg(target_os = "linux")]
+use caps::{CapSet, CapsHashSet};
+use tracing::{error, info};
+
+#[derive(thiserror::Error, Debug)]
+pub enum PrivilegeError {
+    #[error("User {0} does not exist")]
+    UnknownUser(String),
+    #[error("Group {0} does not exist")]
+    UnknownGroup(String),
+    #[error("Failed to {0}: {1}")]
+    SystemCall(&'static str, #[source] io::Error),
+    #[cfg(target_os = "linux")]
+    #[error("Failed to update capabilities: {0}")]
+    Capabilities(#[source] caps::errors::CapsError),
+    #[error("Privileges were not dropped: {0}")]
+    NotDropped(String),
+    #[error(
+        "Privileges must be dropped before the runtime or any other threads are started, found \
+         {0} threads"
+    )]
+    MultipleThreads(usize),
+}
+
+/// Switches the process to an unprivileged user and group once any privileged resources, such as
+/// sockets on low ports, have been acquired.
+///
+/// Capabilities and `no_new_privs` only apply to the calling thread and any threads it creates
+/// afterwards, so [`apply`](Self::apply) must be called from `main` before the Tokio runtime or any
+/// other threads, such as a non-blocking log writer, are started. On Linux, it returns an error if
+/// other threads are running. With socket activation, bind the sockets first using
+/// `bind_activation_sockets`, then drop privileges and start the runtime. The sockets can be
+/// retrieved from inside the runtime with `BoundActivationSockets::into_result`.
+#[derive(Clone, Debug)]
+pub struct PrivilegeDrop {
+    user: Option<String>,
+    group: Option<String>,
+    #[cfg(target_os = "linux")]
+    capabilities: CapsHashSet,
+    no_new_privs: bool,
+}
+
+impl Default for PrivilegeDrop {
+    fn default() -> Self {
+        Self {
+            user: None,
+            group: None,
+            #[cfg(target_os = "linux")]
+            capabilities: CapsHashSet::new(),
+            no_new_privs: cfg!(target_os = "linux"),
+        }
+    }
+}
+
+impl PrivilegeDrop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_user(mut self, user: impl Into<String>) -> Self {
+        self.user = Some(user.into());
+        self
+    }
+
+    /// Defaults to the user's primary group if not set.
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Capability to retain after switching users, such as `CAP_NET_BIND_SERVICE`.
+    #[cfg(target_os = "linux")]
+    pub fn with_capability(mut self, capability: Capability) -> Self {
+        self.capabilities.insert(capability);
+        self
+    }
+
+    /// Sets `PR_SET_NO_NEW_PRIVS` so the process can't gain privileges through `execve`. Only
+    /// supported on Linux, where it's enabled by default.
+    pub fn with_no_new_privs(mut self, no_new_privs: bool) -> Self {
+        self.no_new_privs = no_new_privs;
+        self
+    }
+
+    pub fn apply(&self) -> Result<(), PrivilegeError> {
+        self.apply_inner()
+            .inspect_err(|e| error!("Unable to drop privileges: {e}"))
+    }
+
+    fn apply_inner(&self) -> Result<(), PrivilegeError> {
+        #[cfg(target_os = "linux")]
+        {
+            let threads = thread_count()?;
+            if threads > 1 {
+                return Err(PrivilegeError::MultipleThreads(threads));
+            }
+        }
+
+        let user = self.user.as_deref().map(lookup_user).transpose()?;
+        let gid = match (&self.group, user) {
+            (Some(group), _) => Some(lookup_group(group)?),
+            (None, Some((_, primary_gid))) => Some(primary_gid),
+            (None, None) => None,
+        };
+        let uid = user.map(|(uid, _)| uid);
+
+        #[cfg(target_os = "linux")]
+        if uid.is_some() && !self.capabilities.is_empty() {
+            // Capabilities are cleared when switching away from root unless this is set
+            prctl(libc::PR_SET_KEEPCAPS, 1, "keep capabilities")?;
+        }
+
+        if let Some(gid) = gid {
+            // SAFETY: clearing the supplementary groups doesn't read from the pointer
+            check(
+                unsafe { libc::setgroups(0, ptr::null()) },
+                "clear supplementary groups",
+            )?;
+            // SAFETY: no memory is accessed
+            check(unsafe { libc::setgid(gid) }, "set group")?;
+        }
+        if let Some(uid) = uid {
+            // SAFETY: no memory is accessed
+            check(unsafe { libc::setuid(uid) }, "set user")?;
+        }
+
+        #[cfg(target_os = "linux")]
+        if uid.is_some() {
+            prctl(libc::PR_SET_KEEPCAPS, 0, "reset keep capabilities")?;
+            for cap_set in [CapSet::Effective, CapSet::Permitted, CapSet::Inheritable] {
+                caps::set(None, cap_set, &self.capabilities)
+                    .map_err(PrivilegeError::Capabilities)?;
+            }
+        }
+
+        if self.no_new_privs {
+            #[cfg(target_os = "linux")]
+            prctl(libc::PR_SET_NO_NEW_PRIVS, 1, "set no_new_privs")?;
+            #[cfg(not(target_os = "linux"))]
+            tracing::warn!("no_new_privs is not supported on this platform");
+        }
+
+        self.verify(uid, gid)?;
+        info!("Dropped privileges to uid={uid:?} gid={gid:?}");
+        Ok(())
+    }
+
+    fn verify(
+        &self,
+        uid: Option<libc::uid_t>,
+        gid: Option<libc::gid_t>,
+    ) -> Result<(), PrivilegeError> {
+        // SAFETY: these calls have no preconditions
+        let (real_uid, effective_uid, real_gid, effective_gid) = unsafe {
+            (
+                libc::getuid(),
+                libc::geteuid(),
+                libc::getgid(),
+                libc::getegid(),
+            )
+        };
+
+        if let Some(uid) = uid {
+            if real_uid != uid || effective_uid != uid {
+                return Err(PrivilegeError::NotDropped(format!(
+                    "expected uid {uid}, found real={real_uid} effective={effective_uid}"
+                )));
+            }
+            // SAFETY: no memory is accessed
+            if uid != 0 && unsafe { libc::setuid(0) } == 0 {
+                return Err(PrivilegeError::NotDropped(
+                    "root privileges could be regained".to_owned(),
+                ));
+            }
+        }
+        if let Some(gid) = gid {
+            if real_gid != gid || effective_gid != gid {
+                return Err(PrivilegeError::NotDropped(format!(
+                    "expected gid {gid}, found real={real_gid} effective={effective_gid}"
+                )));
+            }
+            // SAFETY: passing a size of 0 only returns the number of groups
+            let group_count = unsafe { libc::getgroups(0, ptr::null_mut()) };
+            if group_count > 1 {
+                return Err(PrivilegeError::NotDropped(format!(
+                    "{group_count} supplementary groups remain"
+                )));
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if uid.is_some() {
+                let effective =
+                    caps::read(None, CapSet::Effective).map_err(PrivilegeError::Capabilities)?;
+                if effective != self.capabilities {
+                    return Err(PrivilegeError::NotDropped(format!(
+                        "expected capabilities {:?}, found {effective:?}",
+                        self.capabilities
+                    )));
+                }
+            }
+            // SAFETY: PR_GET_NO_NEW_PRIVS doesn't take any arguments
+            if self.no_new_privs
+                && unsafe { libc::prctl(libc::PR_GET_NO_NEW_PRIVS, 0, 0, 0, 0) } != 1
+            {
+                return Err(PrivilegeError::NotDropped(
+                    "no_new_privs is not set".to_owned(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check(result: libc::c_int, action: &'static str) -> Result<(), PrivilegeError> {
+    if result == -1 {
+        Err(PrivilegeError::SystemCall(
+            action,
+            io::Error::last_os_error(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn prctl(
+    option: libc::c_int,
+    value: libc::c_ulong,
+    action: &'static str,
+) -> Result<(), PrivilegeError> {
+    // SAFETY: only integer arguments are passed
+    check(unsafe { libc::prctl(option, value, 0, 0, 0) }, action)
+}
+
+#[cfg(target_os = "linux")]
+fn thread_count() -> Result<usize, PrivilegeError> {
+    std::fs::read_dir("/proc/self/task")
+        .map(|tasks| tasks.count())
+        .map_err(|e| PrivilegeError::SystemCall("count threads", e))
+}
+
+fn lookup_user(name: &str) -> Result<(libc::uid_t, libc::gid_t), PrivilegeError> {
+    let c_name = CString::new(name).map_err(|_| PrivilegeError::UnknownUser(name.to_owned()))?;
+    let mut buf = vec![0 as c_char; 1024];
+    loop {
+        // SAFETY: passwd only contains integers and pointers, so zeroed memory is valid
+        let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+        let mut result = ptr::null_mut();
+        // SAFETY: the buffer outlives the call and its length is passed along with it
+        let ret = unsafe {
+            libc::getpwnam_r(
+                c_name.as_ptr(),
+                &mut passwd,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
+        };
+        match ret {
+            libc::ERANGE => buf.resize(buf.len() * 2, 0),
+            0 if result.is_null() => return Err(PrivilegeError::UnknownUser(name.to_owned())),
+            0 => return Ok((passwd.pw_uid, passwd.pw_gid)),
+            err => {
+                return Err(PrivilegeError::SystemCall(
+                    "look up user",
+                    io::Error::from_raw_os_error(err),
+                ));
+            }
+        }
+    }
+}
+
+fn lookup_group(name: &str) -> Result<libc::gid_t, PrivilegeError> {
+    let c_name = CString::new(name).map_err(|_| PrivilegeError::UnknownGroup(name.to_owned()))?;
+    let mut buf = vec![0 as c_char; 1024];
+    loop {
+        // SAFETY: group only contains integers and pointers, so zeroed memory is valid
+        let mut group: libc::group = unsafe { mem::zeroed() };
+        let mut result = ptr::null_mut();
+        // SAFETY: the buffer outlives the call and its length is passed along with it
+        let ret = unsafe {
+            libc::getgrnam_r(
+                c_name.as_ptr(),
+                &mut group,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            )
+        };
+        match ret {
+            libc::ERANGE => buf.resize(buf.len() * 2, 0),
+            0 if result.is_null() => return Err(PrivilegeError::UnknownGroup(name.to_owned())),
+            0 => return Ok(group.gr_gid),
+            err => {
+                return Err(PrivilegeError::SystemCall(
+                    "look up group",
+                    io::Error::from_raw_os_error(err),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./privileges_test.rs"]
+mod privileges_test;
@@ -0,0 +1,41 @@
+use super::{PrivilegeDrop, PrivilegeError, lookup_group, lookup_user};
+
+#[test]
+fn test_lookup_user() {
+    assert_eq!(0, lookup_user("root").unwrap().0);
+    assert!(matches!(
+        lookup_user("daemon-slayer-missing-user"),
+        Err(PrivilegeError::UnknownUser(_))
+    ));
+    assert!(matches!(
+        lookup_user("invalid\0user"),
+        Err(PrivilegeError::UnknownUser(_))
+    ));
+}
+
+#[test]
+fn test_lookup_group() {
+    assert!(matches!(
+        lookup_group("daemon-slayer-missing-group"),
+        Err(PrivilegeError::UnknownGroup(_))
+    ));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_multiple_threads() {
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    let thread = std::thread::spawn(move || rx.recv());
+
+    // Nothing is changed if the check fails, so this is safe to run as any user
+    let result = PrivilegeDrop::new().with_user("root").apply();
+    assert!(matches!(result, Err(PrivilegeError::MultipleThreads(threads)) if threads > 1));
+
+    drop(tx);
+    thread.join().unwrap().unwrap_err();
+}
+
+#[test]
+fn test_no_new_privs_default() {
+    assert_eq!(cfg!(target_os = "linux"), PrivilegeDrop::new().no_new_privs);
+}
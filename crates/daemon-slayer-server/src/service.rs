@@ -1,13 +1,16 @@
 use futures::Future;
 
-use crate::{Handler, ServiceError};
+use crate::{ForegroundSettings, Handler, ServiceError};
 
 pub trait Service: Handler {
     fn run_as_service(
         input_data: Option<Self::InputData>,
     ) -> impl Future<Output = Result<(), ServiceError<Self::Error>>> + Send;
 
+    /// Runs the service outside of the service manager. The derived implementation uses
+    /// [`run_in_foreground`](crate::run_in_foreground).
     fn run_directly(
         input_data: Option<Self::InputData>,
+        settings: ForegroundSettings,
     ) -> impl Future<Output = Result<(), ServiceError<Self::Error>>> + Send;
 }
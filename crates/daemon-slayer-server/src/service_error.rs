@@ -1,7 +1,10 @@
 use std::fmt;
 
 use daemon_slayer_core::BoxedError;
-use daemon_slayer_core::server::background_service::error::BackgroundServiceErrors;
+use daemon_slayer_core::server::tracked::error::BackgroundServiceErrors;
+
+/// Exit code used when background services fail to stop before the shutdown deadline.
+pub const SHUTDOWN_TIMEOUT_EXIT_CODE: i32 = 124;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ServiceError<E: fmt::Debug + Send + Sync + 'static> {
@@ -11,9 +14,24 @@ pub enum ServiceError<E: fmt::Debug + Send + Sync + 'static> {
     BackgroundServiceFailure(BackgroundServiceErrors),
     #[error("Service manager failed during initialization: {0}: {1:?}")]
     InitializationFailure(String, #[source] BoxedError),
+    #[error(
+        "Background services did not stop before the shutdown deadline: {0:?}. Service error: \
+         {1:?}"
+    )]
+    ShutdownTimeout(Vec<String>, Option<E>),
+    #[cfg(feature = "single-instance")]
+    #[error(transparent)]
+    InstanceLock(#[from] crate::InstanceLockError),
 }
 
 impl<E: fmt::Debug + Send + Sync> ServiceError<E> {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::ShutdownTimeout(..) => SHUTDOWN_TIMEOUT_EXIT_CODE,
+            _ => 1,
+        }
+    }
+
     pub(crate) fn from_service_result(
         service_result: Result<(), E>,
         background_service_errors: Result<(), BackgroundServiceErrors>,
@@ -29,4 +47,16 @@ impl<E: fmt::Debug + Send + Sync> ServiceError<E> {
             }
         }
     }
+
+    pub(crate) fn from_shutdown_result(
+        service_result: Result<(), E>,
+        shutdown_result: Result<Result<(), BackgroundServiceErrors>, Vec<String>>,
+    ) -> Result<(), Self> {
+        match shutdown_result {
+            Ok(background_service_errors) => {
+                Self::from_service_result(service_result, background_service_errors)
+            }
+            Err(pending) => Err(ServiceError::ShutdownTimeout(pending, service_result.err())),
+        }
+    }
 }
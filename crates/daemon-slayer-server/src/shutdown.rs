@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use daemon_slayer_core::server::tracked::Manager;
+use daemon_slayer_core::server::tracked::error::BackgroundServiceErrors;
+use tracing::error;
+
+/// Cancels all background services, waiting at most `deadline` for them to finish. Returns the
+/// names of any services that were still running if the deadline expired.
+pub(crate) async fn cancel_with_deadline(
+    manager: Manager,
+    deadline: Option<Duration>,
+) -> Result<Result<(), BackgroundServiceErrors>, Vec<String>> {
+    let Some(deadline) = deadline else {
+        return Ok(manager.cancel().await);
+    };
+    let services = manager.services();
+    match tokio::time::timeout(deadline, manager.cancel()).await {
+        Ok(result) => Ok(result),
+        Err(_) => {
+            let pending = services.running();
+            error!(
+                "Background services did not stop within {deadline:?}. Still running: {pending:?}"
+            );
+            Err(pending)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./shutdown_test.rs"]
+mod shutdown_test;
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use daemon_slayer_core::server::tracked::{BackgroundService, Manager, ServiceContext, Settings};
+use daemon_slayer_core::{BoxedError, CancellationToken};
+
+use super::cancel_with_deadline;
+
+struct TestService {
+    name: &'static str,
+    ignore_cancellation: bool,
+}
+
+impl BackgroundService for TestService {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        context.cancellation_token().cancelled().await;
+        if self.ignore_cancellation {
+            std::future::pending::<()>().await;
+        }
+        Ok(())
+    }
+}
+
+fn manager(services: &[(&'static str, bool)]) -> Manager {
+    let manager = Manager::new(CancellationToken::new(), Settings::default());
+    let context = manager.get_context();
+    for &(name, ignore_cancellation) in services {
+        context.spawn(TestService {
+            name,
+            ignore_cancellation,
+        });
+    }
+    manager
+}
+
+#[tokio::test]
+async fn test_no_deadline() {
+    let manager = manager(&[("a", false)]);
+    assert!(matches!(
+        cancel_with_deadline(manager, None).await,
+        Ok(Ok(()))
+    ));
+}
+
+#[tokio::test]
+async fn test_stopped_before_deadline() {
+    let manager = manager(&[("a", false), ("b", false)]);
+    assert!(matches!(
+        cancel_with_deadline(manager, Some(Duration::from_secs(5))).await,
+        Ok(Ok(()))
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_deadline_expired() {
+    let manager = manager(&[("stopped", false), ("hung", true)]);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        Err(vec!["hung".to_owned()]),
+        cancel_with_deadline(manager, Some(Duration::from_millis(100)))
+            .await
+            .map(|_| ())
+    );
+}
@@ -0,0 +1,112 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::{io, process};
+
+use daemon_slayer_core::Label;
+use daemon_slayer_core::instance::InstancePaths;
+use daemon_slayer_core::runtime_dir::create_runtime_dir;
+use tracing::{info, warn};
+
+use crate::Handler;
+
+#[derive(thiserror::Error, Debug)]
+pub enum InstanceLockError {
+    #[error("Another instance of {label} is already running{}", pid_message(.pid))]
+    AlreadyRunning { label: Label, pid: Option<u32> },
+    #[error("Unable to lock {label}: {source}")]
+    Io {
+        label: Label,
+        #[source]
+        source: io::Error,
+    },
+}
+
+fn pid_message(pid: &Option<u32>) -> String {
+    pid.map(|pid| format!(" with PID {pid}"))
+        .unwrap_or_default()
+}
+
+/// Prevents multiple instances of the same service from running at once. The lock is released and
+/// the PID file is removed when this is dropped.
+#[derive(Debug)]
+pub struct InstanceLock {
+    paths: InstancePaths,
+    _lock_file: File,
+}
+
+impl InstanceLock {
+    pub fn acquire(label: &Label) -> Result<Self, InstanceLockError> {
+        create_runtime_dir(label).map_err(|source| InstanceLockError::Io {
+            label: label.clone(),
+            source,
+        })?;
+        Self::acquire_at(InstancePaths::from_label(label), label)
+    }
+
+    fn acquire_at(paths: InstancePaths, label: &Label) -> Result<Self, InstanceLockError> {
+        let io_error = |source| InstanceLockError::Io {
+            label: label.clone(),
+            source,
+        };
+        let lock_file = open_lock_file(&paths).map_err(io_error)?;
+        match lock_file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(InstanceLockError::AlreadyRunning {
+                    label: label.clone(),
+                    pid: paths.read_pid(),
+                });
+            }
+            Err(TryLockError::Error(e)) => return Err(io_error(e)),
+        }
+        fs::write(&paths.pid_file, format!("{}\n", process::id())).map_err(io_error)?;
+        info!("Acquired instance lock at {:?}", paths.lock_file);
+        Ok(Self {
+            paths,
+            _lock_file: lock_file,
+        })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.paths.pid_file) {
+            warn!("Error removing PID file: {e:?}");
+        }
+    }
+}
+
+fn open_lock_file(paths: &InstancePaths) -> io::Result<File> {
+    if let Some(parent) = paths.lock_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let result = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&paths.lock_file);
+    match result {
+        // The file may have been created by another user, such as a system service. Locking doesn't
+        // require write access, so it's still usable.
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => File::open(&paths.lock_file),
+        result => result,
+    }
+}
+
+pub(crate) fn acquire_instance_lock<T: Handler>() -> Result<Option<InstanceLock>, InstanceLockError>
+{
+    let enabled = T::single_instance();
+    // Daemons always write a PID file since there's no service manager to track them
+    #[cfg(all(unix, feature = "daemonize"))]
+    let enabled = enabled || crate::is_daemonized();
+    let lock = enabled
+        .then(|| InstanceLock::acquire(&T::label()))
+        .transpose();
+    #[cfg(all(unix, feature = "daemonize"))]
+    crate::daemonize::notify_daemon_status(lock.as_ref().map(|_| ()));
+    lock
+}
+
+#[cfg(test)]
+#[path = "./single_instance_test.rs"]
+mod single_instance_test;
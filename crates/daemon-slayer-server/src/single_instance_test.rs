@@ -0,0 +1,41 @@
+use daemon_slayer_core::Label;
+use daemon_slayer_core::instance::InstancePaths;
+
+use super::{InstanceLock, InstanceLockError};
+
+fn label() -> Label {
+    "com.test.daemon_slayer_instance_test".parse().unwrap()
+}
+
+#[test]
+fn test_lock_path() {
+    assert_eq!(
+        InstancePaths::from_label(&label()).lock_file,
+        InstancePaths::from_label(&label()).lock_file
+    );
+    assert_ne!(
+        InstancePaths::from_label(&label()).lock_file,
+        InstancePaths::from_label(&"com.test.other".parse().unwrap()).lock_file
+    );
+}
+
+#[test]
+fn test_lock_twice() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = InstancePaths::new(dir.path(), &label());
+
+    let lock = InstanceLock::acquire_at(paths.clone(), &label()).unwrap();
+    assert!(paths.is_locked());
+    assert_eq!(Some(std::process::id()), paths.read_pid());
+
+    let second = InstanceLock::acquire_at(paths.clone(), &label());
+    assert!(matches!(
+        second,
+        Err(InstanceLockError::AlreadyRunning { pid: Some(pid), .. }) if pid == std::process::id()
+    ));
+
+    drop(lock);
+    assert!(!paths.is_locked());
+    assert_eq!(None, paths.read_pid());
+    InstanceLock::acquire_at(paths, &label()).unwrap();
+}
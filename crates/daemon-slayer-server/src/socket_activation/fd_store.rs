@@ -0,0 +1,154 @@
+#[cfg(unix)]
+use std::env;
+#[cfg(unix)]
+use std::ffi::OsString;
+use std::io;
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+#[cfg(unix)]
+use std::process::{self, Command};
+
+#[cfg(unix)]
+pub(super) const LISTEN_FDS_START: RawFd = 3;
+// Distinguishes sockets handed back to the process from ones passed in by a socket unit, which
+// can restart the service on demand
+#[cfg(unix)]
+pub(super) const STORED_FD_PREFIX: &str = "fdstore.";
+
+/// Copies of the listening sockets that can be handed off so they survive a restart.
+///
+/// Nothing is handed off automatically. Call [`FdStore::store`] once the sockets are created, or
+/// [`FdStore::reexec`] in place of restarting, to keep the sockets open across restarts.
+#[derive(Default)]
+pub struct FdStore {
+    #[cfg(unix)]
+    fds: Vec<(String, OwnedFd)>,
+}
+
+impl FdStore {
+    #[cfg(unix)]
+    pub(super) fn new(fds: Vec<(String, OwnedFd)>) -> Self {
+        Self { fds }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        #[cfg(unix)]
+        {
+            self.fds.is_empty()
+        }
+        #[cfg(not(unix))]
+        {
+            true
+        }
+    }
+
+    /// Sends the sockets to systemd's file descriptor store so they're passed back to the service
+    /// when it starts again. The unit must set `FileDescriptorStoreMax=` for systemd to keep them.
+    /// systemd ignores sockets it's already storing, so this is safe to call on every start and
+    /// should be done right after the sockets are created so they're kept even if the service
+    /// crashes. Returns `false` if the service isn't being run by systemd.
+    pub fn store(&self) -> io::Result<bool> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsFd;
+
+            use sd_notify::NotifyState;
+
+            if std::env::var_os("NOTIFY_SOCKET").is_none() {
+                return Ok(false);
+            }
+            for (name, fd) in &self.fds {
+                sd_notify::notify_with_fds(
+                    false,
+                    &[
+                        NotifyState::FdStore,
+                        NotifyState::FdName(&format!("{STORED_FD_PREFIX}{name}")),
+                    ],
+                    &[fd.as_fd()],
+                )?;
+            }
+            Ok(true)
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Ok(false)
+        }
+    }
+
+    /// Replaces the current process with a new instance of the same executable, passing the
+    /// sockets along using the `LISTEN_FDS` protocol. This is intended for hosts without a service
+    /// manager that supports storing file descriptors. The process ID is preserved so any
+    /// supervisor tracking it is unaffected. This only returns if the new process could not be
+    /// started, so any cleanup should be performed beforehand.
+    #[cfg(unix)]
+    pub fn reexec(self) -> io::Error {
+        match env::current_exe() {
+            Ok(exe) => self.reexec_with(exe, env::args_os().skip(1)),
+            Err(e) => e,
+        }
+    }
+
+    #[cfg(unix)]
+    pub fn reexec_with(
+        self,
+        program: impl Into<OsString>,
+        args: impl IntoIterator<Item = impl Into<OsString>>,
+    ) -> io::Error {
+        let count = self.fds.len() as RawFd;
+        // Move each socket out of the range it will be assigned to in the new process so the
+        // descriptors can't overwrite each other when they're moved into place
+        let high_fds = match self
+            .fds
+            .iter()
+            .map(|(_, fd)| dup_above(fd, LISTEN_FDS_START + count))
+            .collect::<io::Result<Vec<_>>>()
+        {
+            Ok(fds) => fds,
+            Err(e) => return e,
+        };
+        let raw_fds: Vec<RawFd> = high_fds.iter().map(AsRawFd::as_raw_fd).collect();
+        let names = self
+            .fds
+            .iter()
+            .map(|(name, _)| format!("{STORED_FD_PREFIX}{name}"))
+            .collect::<Vec<_>>()
+            .join(":");
+
+        let mut command = Command::new(program.into());
+        command
+            .args(args.into_iter().map(Into::into))
+            .env("LISTEN_PID", process::id().to_string())
+            .env("LISTEN_FDS", count.to_string())
+            .env("LISTEN_FDNAMES", names);
+
+        // SAFETY: dup2 is async-signal-safe and the closure doesn't allocate
+        unsafe {
+            command.pre_exec(move || {
+                for (i, fd) in raw_fds.iter().enumerate() {
+                    if libc::dup2(*fd, LISTEN_FDS_START + i as RawFd) == -1 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+        let err = command.exec();
+        drop(high_fds);
+        err
+    }
+}
+
+#[cfg(unix)]
+fn dup_above(fd: &OwnedFd, min: RawFd) -> io::Result<OwnedFd> {
+    // SAFETY: fcntl is called with a valid descriptor and returns a new one that we take
+    // ownership of
+    unsafe {
+        let new_fd = libc::fcntl(fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, min);
+        if new_fd == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(OwnedFd::from_raw_fd(new_fd))
+    }
+}
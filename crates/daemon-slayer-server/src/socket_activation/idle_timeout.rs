@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use daemon_slayer_core::BoxedError;
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
+use tokio::sync::watch;
+use tracing::info;
+
+/// Shuts down all background services once no connections have been in flight for the configured
+/// duration. Only takes effect when the sockets were passed in by the service manager so that the
+/// service can be restarted on the next connection.
+pub struct IdleTimeout {
+    timeout: Duration,
+    enabled: bool,
+    active_tx: Arc<watch::Sender<usize>>,
+}
+
+impl IdleTimeout {
+    pub fn new(timeout: Duration, is_activated: bool) -> Self {
+        let (active_tx, _) = watch::channel(0);
+        Self {
+            timeout,
+            enabled: is_activated,
+            active_tx: Arc::new(active_tx),
+        }
+    }
+
+    /// Connections from sockets wrapped with [`SocketResult::tracked`](super::SocketResult::tracked)
+    /// are counted automatically. The tracker can also be used to count other work manually.
+    pub fn get_tracker(&self) -> ConnectionTracker {
+        ConnectionTracker {
+            active_tx: self.active_tx.clone(),
+        }
+    }
+}
+
+impl BackgroundService for IdleTimeout {
+    fn name(&self) -> &str {
+        "idle_timeout_service"
+    }
+
+    async fn run(self, context: ServiceContext) -> Result<(), BoxedError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let cancellation_token = context.cancellation_token().clone();
+        let mut active_rx = self.active_tx.subscribe();
+
+        loop {
+            let active = *active_rx.borrow_and_update();
+            if active > 0 {
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => return Ok(()),
+                    _ = active_rx.changed() => continue,
+                }
+            }
+
+            tokio::select! {
+                _ = cancellation_token.cancelled() => return Ok(()),
+                // Any connection activity resets the timer
+                _ = active_rx.changed() => continue,
+                _ = tokio::time::sleep(self.timeout) => {
+                    info!("No connections received in {:?}, shutting down", self.timeout);
+                    context.cancel_all();
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConnectionTracker {
+    active_tx: Arc<watch::Sender<usize>>,
+}
+
+impl ConnectionTracker {
+    /// Marks a connection as in flight until the returned guard is dropped.
+    pub fn track(&self) -> ConnectionGuard {
+        self.active_tx.send_modify(|active| *active += 1);
+        ConnectionGuard {
+            active_tx: self.active_tx.clone(),
+        }
+    }
+
+    // Resets the idle timer without keeping a connection open
+    pub(super) fn touch(&self) {
+        self.active_tx.send_modify(|_| {});
+    }
+
+    pub fn active_connections(&self) -> usize {
+        *self.active_tx.borrow()
+    }
+}
+
+pub struct ConnectionGuard {
+    active_tx: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.active_tx
+            .send_modify(|active| *active = active.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+#[path = "./idle_timeout_test.rs"]
+mod idle_timeout_test;
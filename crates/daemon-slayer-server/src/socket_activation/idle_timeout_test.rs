@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use daemon_slayer_core::CancellationToken;
+use daemon_slayer_core::server::tracked::{self, Manager};
+
+use super::IdleTimeout;
+use crate::socket_activation::{SocketResult, TrackedSocket};
+
+fn manager() -> Manager {
+    Manager::new(CancellationToken::new(), tracked::Settings::default())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_idle_shutdown() {
+    let manager = manager();
+    let context = manager.get_context();
+    context.spawn(IdleTimeout::new(Duration::from_secs(10), true));
+
+    tokio::time::sleep(Duration::from_secs(9)).await;
+    assert!(!context.cancellation_token().is_cancelled());
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(context.cancellation_token().is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_active_connection() {
+    let manager = manager();
+    let context = manager.get_context();
+    let idle_timeout = IdleTimeout::new(Duration::from_secs(10), true);
+    let tracker = idle_timeout.get_tracker();
+    let guard = tracker.track();
+    context.spawn(idle_timeout);
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(!context.cancellation_token().is_cancelled());
+    assert_eq!(1, tracker.active_connections());
+
+    drop(guard);
+    assert_eq!(0, tracker.active_connections());
+    tokio::time::sleep(Duration::from_secs(9)).await;
+    assert!(!context.cancellation_token().is_cancelled());
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(context.cancellation_token().is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_not_activated() {
+    let manager = manager();
+    let context = manager.get_context();
+    context.spawn(IdleTimeout::new(Duration::from_secs(10), false));
+
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    assert!(!context.cancellation_token().is_cancelled());
+}
+
+#[tokio::test]
+async fn test_tracked_tcp_listener() {
+    let idle_timeout = IdleTimeout::new(Duration::from_secs(10), true);
+    let tracker = idle_timeout.get_tracker();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let TrackedSocket::Tcp(listener) = SocketResult::Tcp(listener).tracked(&tracker) else {
+        panic!("expected a TCP listener");
+    };
+
+    let _client = tokio::net::TcpStream::connect(listener.local_addr().unwrap())
+        .await
+        .unwrap();
+    let (connection, _) = listener.accept().await.unwrap();
+    assert_eq!(1, tracker.active_connections());
+
+    drop(connection);
+    assert_eq!(0, tracker.active_connections());
+}
+
+#[tokio::test]
+async fn test_tracked_udp_socket() {
+    let idle_timeout = IdleTimeout::new(Duration::from_secs(10), true);
+    let tracker = idle_timeout.get_tracker();
+    let changes = idle_timeout.active_tx.subscribe();
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let TrackedSocket::Udp(socket) = SocketResult::Udp(socket).tracked(&tracker) else {
+        panic!("expected a UDP socket");
+    };
+
+    let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client
+        .send_to(b"ping", socket.local_addr().unwrap())
+        .await
+        .unwrap();
+    let mut buf = [0; 4];
+    socket.recv_from(&mut buf).await.unwrap();
+
+    // Datagrams reset the idle timer without counting as open connections
+    assert!(changes.has_changed().unwrap());
+    assert_eq!(0, tracker.active_connections());
+}
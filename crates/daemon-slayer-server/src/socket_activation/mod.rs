@@ -1,3 +1,6 @@
+mod fd_store;
+mod idle_timeout;
+mod tracked;
 #[cfg(unix)]
 mod unix;
 #[cfg(windows)]
@@ -5,12 +8,13 @@ mod windows;
 
 use std::collections::HashMap;
 use std::io;
-use std::path::PathBuf;
+use std::time::Duration;
 
-use daemon_slayer_core::socket_activation::{ActivationSocketConfig, SocketType};
-use futures::future;
-use tipsy::{Endpoint, IpcStream, OnConflict, SecurityAttributes};
+pub use fd_store::*;
+pub use idle_timeout::*;
+use tipsy::IpcStream;
 use tokio::net::{TcpListener, UdpSocket};
+pub use tracked::*;
 #[cfg(unix)]
 pub use unix::*;
 #[cfg(windows)]
@@ -37,37 +41,17 @@ pub enum SocketActivationError {
 
 pub struct SocketActivationResult {
     pub sockets: HashMap<String, Vec<SocketResult>>,
+    /// Whether any of the sockets were passed in by a socket unit or launchd. Sockets restored
+    /// from the file descriptor store don't count since nothing will start the service again if
+    /// it exits.
     pub is_activated: bool,
+    pub fd_store: FdStore,
 }
 
-pub(super) async fn create_socket(
-    config: ActivationSocketConfig,
-) -> Result<SocketResult, SocketActivationError> {
-    Ok(match config.socket_type() {
-        SocketType::Ipc => {
-            let endpoint = Endpoint::new(PathBuf::from(config.addr()), OnConflict::Overwrite)
-                .map_err(SocketActivationError::CreationFailure)?
-                .security_attributes(
-                    SecurityAttributes::allow_everyone_create()
-                        .map_err(SocketActivationError::CreationFailure)?,
-                );
-            SocketResult::Ipc(
-                endpoint
-                    .incoming()
-                    .map_err(SocketActivationError::CreationFailure)?,
-            )
-        }
-        SocketType::Tcp => SocketResult::Tcp(
-            TcpListener::bind(config.addr())
-                .await
-                .map_err(SocketActivationError::CreationFailure)?,
-        ),
-        SocketType::Udp => SocketResult::Udp(
-            UdpSocket::bind(config.addr())
-                .await
-                .map_err(SocketActivationError::CreationFailure)?,
-        ),
-    })
+impl SocketActivationResult {
+    pub fn idle_timeout(&self, timeout: Duration) -> IdleTimeout {
+        IdleTimeout::new(timeout, self.is_activated)
+    }
 }
 
 pub(super) fn to_hash_map(
@@ -84,14 +68,3 @@ pub(super) fn to_hash_map(
             map
         })
 }
-
-pub(super) async fn create_sockets(
-    socket_config: Vec<ActivationSocketConfig>,
-) -> Result<Vec<(String, SocketResult)>, SocketActivationError> {
-    future::try_join_all(
-        socket_config
-            .into_iter()
-            .map(|config| async { Ok((config.name().to_owned(), create_socket(config).await?)) }),
-    )
-    .await
-}
@@ -0,0 +1,175 @@
+use std::io;
+use std::net::SocketAddr;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use tipsy::IpcStream;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+
+use super::{ConnectionGuard, ConnectionTracker, SocketResult};
+
+/// An activated socket that counts its connections towards the idle timeout.
+pub enum TrackedSocket {
+    Ipc(TrackedIncoming<IpcStream>),
+    Tcp(TrackedTcpListener),
+    Udp(TrackedUdpSocket),
+}
+
+impl SocketResult {
+    pub fn tracked(self, tracker: &ConnectionTracker) -> TrackedSocket {
+        let tracker = tracker.clone();
+        match self {
+            SocketResult::Ipc(incoming) => {
+                TrackedSocket::Ipc(TrackedIncoming { incoming, tracker })
+            }
+            SocketResult::Tcp(listener) => {
+                TrackedSocket::Tcp(TrackedTcpListener { listener, tracker })
+            }
+            SocketResult::Udp(socket) => TrackedSocket::Udp(TrackedUdpSocket { socket, tracker }),
+        }
+    }
+}
+
+/// A connection that's counted as in flight until it's dropped.
+pub struct TrackedConnection<S> {
+    inner: S,
+    _guard: ConnectionGuard,
+}
+
+impl<S> TrackedConnection<S> {
+    fn new(inner: S, tracker: &ConnectionTracker) -> Self {
+        Self {
+            inner,
+            _guard: tracker.track(),
+        }
+    }
+}
+
+impl<S> Deref for TrackedConnection<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<S> DerefMut for TrackedConnection<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TrackedConnection<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TrackedConnection<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+/// Stream of incoming connections that are tracked as they're accepted.
+pub struct TrackedIncoming<S> {
+    incoming: S,
+    tracker: ConnectionTracker,
+}
+
+impl<S, C> Stream for TrackedIncoming<S>
+where
+    S: Stream<Item = io::Result<C>> + Unpin,
+{
+    type Item = io::Result<TrackedConnection<C>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        Pin::new(&mut this.incoming)
+            .poll_next(cx)
+            .map(|connection| {
+                connection.map(|connection| {
+                    connection.map(|connection| TrackedConnection::new(connection, &this.tracker))
+                })
+            })
+    }
+}
+
+pub struct TrackedTcpListener {
+    listener: TcpListener,
+    tracker: ConnectionTracker,
+}
+
+impl TrackedTcpListener {
+    pub async fn accept(&self) -> io::Result<(TrackedConnection<TcpStream>, SocketAddr)> {
+        let (stream, addr) = self.listener.accept().await?;
+        Ok((TrackedConnection::new(stream, &self.tracker), addr))
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// UDP has no connections, so each datagram that's received resets the idle timer instead.
+pub struct TrackedUdpSocket {
+    socket: UdpSocket,
+    tracker: ConnectionTracker,
+}
+
+impl TrackedUdpSocket {
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        let received = self.socket.recv_from(buf).await?;
+        self.tracker.touch();
+        Ok(received)
+    }
+
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        let received = self.socket.recv(buf).await?;
+        self.tracker.touch();
+        Ok(received)
+    }
+
+    pub async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.socket.send_to(buf, target).await
+    }
+
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.socket.send(buf).await
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
@@ -1,75 +1,260 @@
-#[cfg(target_os = "macos")]
-use std::os::fd::FromRawFd;
-use std::os::fd::OwnedFd;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::os::unix::fs::PermissionsExt;
 use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{env, fs, io, process};
 
 use daemon_slayer_core::socket_activation::{ActivationSocketConfig, SocketType};
-use futures::future;
 use tipsy::IpcStream;
 use tokio::net::{TcpListener, UdpSocket};
+use tracing::warn;
+
+use super::fd_store::{LISTEN_FDS_START, STORED_FD_PREFIX};
+use super::{FdStore, SocketActivationError, SocketActivationResult, SocketResult, to_hash_map};
 
-use super::{
-    SocketActivationError, SocketActivationResult, SocketResult, create_sockets, to_hash_map,
-};
+static INHERITED_FDS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FdOrigin {
+    // Passed in by a socket unit or launchd, so the service manager can start the service again
+    // on the next connection
+    Activated,
+    // Restored from the file descriptor store or passed along by a re-exec
+    Stored,
+}
+
+struct InheritedFd {
+    name: String,
+    fd: OwnedFd,
+    origin: FdOrigin,
+}
 
 pub async fn get_activation_sockets(
     socket_config: Vec<ActivationSocketConfig>,
 ) -> Result<SocketActivationResult, SocketActivationError> {
-    #[cfg(target_os = "linux")]
-    let fds: Vec<_> = sd_listen_fds::get()
-        .map_err(|e| SocketActivationError::UnableToLoad(e.to_string()))?
-        .into_iter()
-        .map(|r| r.1.into_std())
-        .collect();
+    bind_activation_sockets(socket_config)?.into_result()
+}
 
-    #[cfg(target_os = "macos")]
-    let fds: Result<Vec<_>, _> = socket_config
-        .iter()
-        .filter_map(|s| match raunch::activate_socket(s.name()) {
-            Ok(fds) => Some(Ok(fds)),
-            Err(raunch::Error::NotManaged) => None,
-            Err(e) => Some(Err(SocketActivationError::UnableToLoad(e.to_string()))),
+/// Activation sockets that were bound or inherited before the Tokio runtime started.
+pub struct BoundActivationSockets {
+    sockets: Vec<(ActivationSocketConfig, OwnedFd)>,
+    is_activated: bool,
+}
+
+impl BoundActivationSockets {
+    /// Registers the sockets with the current Tokio runtime. This must be called from within the
+    /// runtime.
+    pub fn into_result(self) -> Result<SocketActivationResult, SocketActivationError> {
+        let mut sockets = Vec::with_capacity(self.sockets.len());
+        let mut stored_fds = Vec::with_capacity(self.sockets.len());
+        for (config, fd) in self.sockets {
+            stored_fds.push((
+                config.name().to_owned(),
+                fd.try_clone()
+                    .map_err(SocketActivationError::CreationFailure)?,
+            ));
+            sockets.push((
+                config.name().to_owned(),
+                create_activated_socket(fd, config)?,
+            ));
+        }
+
+        Ok(SocketActivationResult {
+            sockets: to_hash_map(sockets),
+            is_activated: self.is_activated,
+            fd_store: FdStore::new(stored_fds),
         })
-        .collect();
+    }
+}
+
+/// Same as [`get_activation_sockets`], but doesn't require a Tokio runtime. This allows sockets on
+/// privileged ports to be bound from `main` before privileges are dropped and the runtime is
+/// started. Use [`BoundActivationSockets::into_result`] to get the sockets once the runtime is
+/// running.
+pub fn bind_activation_sockets(
+    socket_config: Vec<ActivationSocketConfig>,
+) -> Result<BoundActivationSockets, SocketActivationError> {
+    #[allow(unused_mut)]
+    let mut fds = inherited_fds()?;
+
     #[cfg(target_os = "macos")]
-    let fds: Vec<_> = fds?
+    if fds.is_empty() {
+        fds = launchd_fds(&socket_config)?;
+    }
+
+    let is_activated = fds.iter().any(|fd| fd.origin == FdOrigin::Activated);
+    let sockets = match_fds(fds, socket_config)?
         .into_iter()
-        .flatten()
-        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd) })
-        .collect();
+        .map(|(config, fd)| {
+            let fd = match fd {
+                Some(fd) => fd,
+                None => bind_socket(&config)?,
+            };
+            Ok((config, fd))
+        })
+        .collect::<Result<_, SocketActivationError>>()?;
 
-    let activated = !fds.is_empty();
-    let supplied = socket_config.len();
-    let returned = fds.len();
-    if activated && fds.len() != socket_config.len() {
-        return Err(SocketActivationError::Mismatch { supplied, returned });
-    }
+    Ok(BoundActivationSockets {
+        sockets,
+        is_activated,
+    })
+}
 
-    if activated {
-        let sockets = future::try_join_all(fds.into_iter().zip(socket_config.into_iter()).map(
-            |(fd, config)| async {
-                Ok((
-                    config.name().to_owned(),
-                    create_activated_socket(fd, config).await?,
-                ))
-            },
-        ))
-        .await?;
+// Sockets passed in using the LISTEN_FDS protocol, either from systemd or from a previous instance
+// of the process that was re-executed
+fn inherited_fds() -> Result<Vec<InheritedFd>, SocketActivationError> {
+    let listen_fds = parse_listen_fds(
+        env::var("LISTEN_PID").ok().as_deref(),
+        env::var("LISTEN_FDS").ok().as_deref(),
+        env::var("LISTEN_FDNAMES").ok().as_deref(),
+        process::id(),
+    )?;
+    // The environment variables stay set, so make sure we don't take ownership of the same
+    // descriptors twice
+    if listen_fds.is_empty() || INHERITED_FDS_TAKEN.swap(true, Ordering::SeqCst) {
+        return Ok(vec![]);
+    }
 
-        Ok(SocketActivationResult {
-            sockets: to_hash_map(sockets),
-            is_activated: true,
+    listen_fds
+        .into_iter()
+        .map(|(fd, name, origin)| {
+            // SAFETY: fcntl only updates the descriptor flags and reports invalid descriptors as
+            // an error
+            if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+                return Err(SocketActivationError::UnableToLoad(
+                    io::Error::last_os_error().to_string(),
+                ));
+            }
+            Ok(InheritedFd {
+                name,
+                // SAFETY: systemd passes ownership of LISTEN_FDS to the process matching
+                // LISTEN_PID. INHERITED_FDS_TAKEN ensures they're only wrapped once, so nothing
+                // else in this process owns or closes them.
+                fd: unsafe { OwnedFd::from_raw_fd(fd) },
+                origin,
+            })
         })
-    } else {
-        let sockets = create_sockets(socket_config).await?;
-        Ok(SocketActivationResult {
-            sockets: to_hash_map(sockets),
-            is_activated: false,
+        .collect()
+}
+
+fn parse_listen_fds(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    listen_fdnames: Option<&str>,
+    pid: u32,
+) -> Result<Vec<(RawFd, String, FdOrigin)>, SocketActivationError> {
+    if listen_pid.and_then(|listen_pid| listen_pid.parse::<u32>().ok()) != Some(pid) {
+        return Ok(vec![]);
+    }
+    let count = listen_fds
+        .and_then(|count| count.parse::<RawFd>().ok())
+        .filter(|count| *count >= 0 && count.checked_add(LISTEN_FDS_START).is_some())
+        .ok_or_else(|| SocketActivationError::UnableToLoad("invalid LISTEN_FDS".to_owned()))?;
+    let mut names: Vec<&str> = listen_fdnames
+        .map(|names| names.split(':').collect())
+        .unwrap_or_default();
+    names.resize(count as usize, "unknown");
+
+    Ok((LISTEN_FDS_START..LISTEN_FDS_START + count)
+        .zip(names)
+        .map(|(fd, name)| match name.strip_prefix(STORED_FD_PREFIX) {
+            Some(name) => (fd, name.to_owned(), FdOrigin::Stored),
+            None => (fd, name.to_owned(), FdOrigin::Activated),
         })
+        .collect())
+}
+
+#[cfg(target_os = "macos")]
+fn launchd_fds(
+    socket_config: &[ActivationSocketConfig],
+) -> Result<Vec<InheritedFd>, SocketActivationError> {
+    let mut fds = vec![];
+    let mut names: Vec<&str> = socket_config.iter().map(|s| s.name()).collect();
+    names.dedup();
+    for name in names {
+        match raunch::activate_socket(name) {
+            Ok(raw_fds) => fds.extend(raw_fds.into_iter().map(|fd| InheritedFd {
+                name: name.to_owned(),
+                // SAFETY: launch_activate_socket hands ownership of the returned descriptors to
+                // the caller. The names are deduplicated above, so each one is only wrapped once.
+                fd: unsafe { OwnedFd::from_raw_fd(fd) },
+                origin: FdOrigin::Activated,
+            })),
+            Err(raunch::Error::NotManaged) => {}
+            Err(e) => return Err(SocketActivationError::UnableToLoad(e.to_string())),
+        }
     }
+    Ok(fds)
 }
 
-async fn create_activated_socket(
+fn match_fds(
+    mut fds: Vec<InheritedFd>,
+    socket_config: Vec<ActivationSocketConfig>,
+) -> Result<Vec<(ActivationSocketConfig, Option<OwnedFd>)>, SocketActivationError> {
+    // Sockets restored from the file descriptor store are named, so we can match them up with the
+    // config and create any that are missing
+    let match_by_name = fds
+        .iter()
+        .any(|fd| socket_config.iter().any(|c| c.name() == fd.name));
+    if match_by_name {
+        let matched = socket_config
+            .into_iter()
+            .map(|config| {
+                let fd = fds
+                    .iter()
+                    .position(|fd| fd.name == config.name())
+                    .map(|i| fds.remove(i).fd);
+                (config, fd)
+            })
+            .collect();
+        // The leftover sockets are closed before any missing ones are bound in case they're
+        // holding the same address
+        if !fds.is_empty() {
+            let names: Vec<_> = fds.iter().map(|fd| fd.name.as_str()).collect();
+            warn!("Closing inherited sockets that don't match the socket configuration: {names:?}");
+        }
+        return Ok(matched);
+    }
+
+    let supplied = socket_config.len();
+    let returned = fds.len();
+    if returned == 0 {
+        return Ok(socket_config.into_iter().map(|c| (c, None)).collect());
+    }
+    if returned != supplied {
+        return Err(SocketActivationError::Mismatch { supplied, returned });
+    }
+    Ok(socket_config
+        .into_iter()
+        .zip(fds)
+        .map(|(config, inherited)| (config, Some(inherited.fd)))
+        .collect())
+}
+
+fn bind_socket(config: &ActivationSocketConfig) -> Result<OwnedFd, SocketActivationError> {
+    Ok(match config.socket_type() {
+        SocketType::Ipc => {
+            let path = Path::new(config.addr());
+            if path.exists() {
+                fs::remove_file(path).map_err(SocketActivationError::CreationFailure)?;
+            }
+            let listener =
+                UnixListener::bind(path).map_err(SocketActivationError::CreationFailure)?;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o666))
+                .map_err(SocketActivationError::CreationFailure)?;
+            listener.into()
+        }
+        SocketType::Tcp => std::net::TcpListener::bind(config.addr())
+            .map_err(SocketActivationError::CreationFailure)?
+            .into(),
+        SocketType::Udp => std::net::UdpSocket::bind(config.addr())
+            .map_err(SocketActivationError::CreationFailure)?
+            .into(),
+    })
+}
+
+fn create_activated_socket(
     fd: OwnedFd,
     config: ActivationSocketConfig,
 ) -> Result<SocketResult, SocketActivationError> {
@@ -108,3 +293,7 @@ async fn create_activated_socket(
         }
     })
 }
+
+#[cfg(test)]
+#[path = "./unix_test.rs"]
+mod unix_test;
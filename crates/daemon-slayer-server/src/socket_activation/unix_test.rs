@@ -0,0 +1,128 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::os::fd::OwnedFd;
+
+use daemon_slayer_core::socket_activation::ActivationSocketConfig;
+
+use super::{FdOrigin, InheritedFd, bind_activation_sockets, match_fds, parse_listen_fds};
+use crate::socket_activation::{SocketActivationError, SocketResult};
+
+const PID: u32 = 1234;
+
+fn inherited(name: &str) -> InheritedFd {
+    InheritedFd {
+        name: name.to_owned(),
+        fd: std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .into(),
+        origin: FdOrigin::Activated,
+    }
+}
+
+fn config(name: &str) -> ActivationSocketConfig {
+    ActivationSocketConfig::new_tcp(name, SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))
+}
+
+fn matched_names(matched: &[(ActivationSocketConfig, Option<OwnedFd>)]) -> Vec<(&str, bool)> {
+    matched
+        .iter()
+        .map(|(config, fd)| (config.name(), fd.is_some()))
+        .collect()
+}
+
+#[test]
+fn test_parse_other_pid() {
+    assert!(
+        parse_listen_fds(Some("1"), Some("2"), None, PID)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        parse_listen_fds(None, Some("2"), None, PID)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_parse_invalid_count() {
+    for count in [None, Some("-1"), Some("abc"), Some("2147483647")] {
+        assert!(matches!(
+            parse_listen_fds(Some("1234"), count, None, PID),
+            Err(SocketActivationError::UnableToLoad(_))
+        ));
+    }
+}
+
+#[test]
+fn test_parse_names() {
+    assert_eq!(
+        vec![
+            (3, "http".to_owned(), FdOrigin::Activated),
+            (4, "ipc".to_owned(), FdOrigin::Stored),
+            (5, "unknown".to_owned(), FdOrigin::Activated),
+        ],
+        parse_listen_fds(Some("1234"), Some("3"), Some("http:fdstore.ipc"), PID).unwrap()
+    );
+}
+
+#[test]
+fn test_parse_extra_names() {
+    assert_eq!(
+        vec![(3, "http".to_owned(), FdOrigin::Activated)],
+        parse_listen_fds(Some("1234"), Some("1"), Some("http:ipc"), PID).unwrap()
+    );
+}
+
+#[test]
+fn test_match_by_name() {
+    let matched = match_fds(
+        vec![inherited("other"), inherited("b"), inherited("a")],
+        vec![config("a"), config("b"), config("c")],
+    )
+    .unwrap();
+    assert_eq!(
+        vec![("a", true), ("b", true), ("c", false)],
+        matched_names(&matched)
+    );
+}
+
+#[test]
+fn test_match_by_position() {
+    let matched = match_fds(
+        vec![inherited("unknown"), inherited("unknown")],
+        vec![config("a"), config("b")],
+    )
+    .unwrap();
+    assert_eq!(vec![("a", true), ("b", true)], matched_names(&matched));
+}
+
+#[test]
+fn test_match_none() {
+    let matched = match_fds(vec![], vec![config("a")]).unwrap();
+    assert_eq!(vec![("a", false)], matched_names(&matched));
+}
+
+#[test]
+fn test_match_mismatch() {
+    assert!(matches!(
+        match_fds(vec![inherited("unknown")], vec![config("a"), config("b")]),
+        Err(SocketActivationError::Mismatch {
+            supplied: 2,
+            returned: 1
+        })
+    ));
+}
+
+#[test]
+fn test_bind_before_runtime() {
+    let bound = bind_activation_sockets(vec![config("tcp")]).unwrap();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let result = bound.into_result().unwrap();
+        assert!(!result.is_activated);
+        assert!(matches!(result.sockets["tcp"][..], [SocketResult::Tcp(_)]));
+    });
+}
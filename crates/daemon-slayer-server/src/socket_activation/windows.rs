@@ -1,6 +1,11 @@
-use daemon_slayer_core::socket_activation::ActivationSocketConfig;
+use std::path::PathBuf;
 
-use super::{SocketActivationError, SocketActivationResult, create_sockets, to_hash_map};
+use daemon_slayer_core::socket_activation::{ActivationSocketConfig, SocketType};
+use futures::future;
+use tipsy::{Endpoint, OnConflict, SecurityAttributes};
+use tokio::net::{TcpListener, UdpSocket};
+
+use super::{FdStore, SocketActivationError, SocketActivationResult, SocketResult, to_hash_map};
 
 pub async fn get_activation_sockets(
     socket_config: Vec<ActivationSocketConfig>,
@@ -9,5 +14,47 @@ pub async fn get_activation_sockets(
     Ok(SocketActivationResult {
         sockets: to_hash_map(sockets),
         is_activated: false,
+        fd_store: FdStore::default(),
+    })
+}
+
+async fn create_socket(
+    config: ActivationSocketConfig,
+) -> Result<SocketResult, SocketActivationError> {
+    Ok(match config.socket_type() {
+        SocketType::Ipc => {
+            let endpoint = Endpoint::new(PathBuf::from(config.addr()), OnConflict::Overwrite)
+                .map_err(SocketActivationError::CreationFailure)?
+                .security_attributes(
+                    SecurityAttributes::allow_everyone_create()
+                        .map_err(SocketActivationError::CreationFailure)?,
+                );
+            SocketResult::Ipc(
+                endpoint
+                    .incoming()
+                    .map_err(SocketActivationError::CreationFailure)?,
+            )
+        }
+        SocketType::Tcp => SocketResult::Tcp(
+            TcpListener::bind(config.addr())
+                .await
+                .map_err(SocketActivationError::CreationFailure)?,
+        ),
+        SocketType::Udp => SocketResult::Udp(
+            UdpSocket::bind(config.addr())
+                .await
+                .map_err(SocketActivationError::CreationFailure)?,
+        ),
     })
 }
+
+async fn create_sockets(
+    socket_config: Vec<ActivationSocketConfig>,
+) -> Result<Vec<(String, SocketResult)>, SocketActivationError> {
+    future::try_join_all(
+        socket_config
+            .into_iter()
+            .map(|config| async { Ok((config.name().to_owned(), create_socket(config).await?)) }),
+    )
+    .await
+}
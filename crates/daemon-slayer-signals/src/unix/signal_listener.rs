@@ -2,7 +2,7 @@ use std::ffi::c_int;
 
 use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::server::BroadcastEventStore;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use daemon_slayer_core::signal::{self, Signal};
 use futures::stream::StreamExt;
 use signal_hook_tokio::SignalsInfo;
@@ -1,6 +1,6 @@
 use daemon_slayer_core::BoxedError;
 use daemon_slayer_core::server::BroadcastEventStore;
-use daemon_slayer_core::server::background_service::{BackgroundService, ServiceContext};
+use daemon_slayer_core::server::tracked::{BackgroundService, ServiceContext};
 use daemon_slayer_core::signal::{self, Signal};
 use tap::TapFallible;
 use tokio::sync::broadcast;
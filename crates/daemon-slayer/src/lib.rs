@@ -29,6 +29,10 @@ pub mod error_handler {
 pub mod health_check {
     pub use daemon_slayer_health_check::*;
 }
+#[cfg(feature = "metrics")]
+pub mod metrics {
+    pub use daemon_slayer_metrics::*;
+}
 #[cfg(feature = "tray")]
 pub mod tray {
     pub use daemon_slayer_tray::*;
@@ -24,7 +24,7 @@ use daemon_slayer::signals::SignalListener;
 use derive_more::AsRef;
 use tracing::info;
 
-#[derive(Debug, Config, AsRef, Default, Clone)]
+#[derive(Debug, Config, AsRef, Default, Clone, schemars::JsonSchema)]
 struct MyConfig {
     #[as_ref]
     #[config(nested)]
@@ -93,6 +93,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = AppData;
+    type ControlHandle = ();
 
     fn label() -> Label {
         containerized::label()
@@ -118,6 +119,8 @@ impl Handler for ServiceHandler {
         Ok(Self { signal_store })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
         notify_ready();
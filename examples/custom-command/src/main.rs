@@ -76,6 +76,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = ();
+    type ControlHandle = ();
 
     fn label() -> Label {
         "com.example.daemon_slayer_custom_command"
@@ -91,6 +92,8 @@ impl Handler for ServiceHandler {
         Ok(Self { signal_store })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
         notify_ready();
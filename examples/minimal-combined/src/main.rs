@@ -55,7 +55,7 @@ pub async fn main() -> Result<(), BoxedError> {
 
     match Cli::parse().arg {
         None => {
-            ServiceHandler::run_directly(None).await?;
+            ServiceHandler::run_directly(None, Default::default()).await?;
         }
         Some(Arg::Run) => {
             ServiceHandler::run_as_service(None).await?;
@@ -91,6 +91,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = ();
+    type ControlHandle = ();
 
     fn label() -> Label {
         "com.example.daemon_slayer_minimal_combined"
@@ -109,6 +110,8 @@ impl Handler for ServiceHandler {
         Ok(Self { signal_store })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         println!("running service");
         let start_time = Instant::now();
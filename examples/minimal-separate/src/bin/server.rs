@@ -16,7 +16,7 @@ pub async fn main() -> Result<(), BoxedError> {
             return Ok(());
         }
     }
-    ServiceHandler::run_directly(None).await?;
+    ServiceHandler::run_directly(None, Default::default()).await?;
 
     Ok(())
 }
@@ -29,6 +29,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = ();
+    type ControlHandle = ();
 
     fn label() -> Label {
         minimal_separate::label()
@@ -45,6 +46,8 @@ impl Handler for ServiceHandler {
         Ok(Self { signal_store })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         println!("running service");
         notify_ready();
@@ -27,7 +27,7 @@ use daemon_slayer::signals::SignalListener;
 use derive_more::AsRef;
 use tracing::{error, info};
 
-#[derive(Debug, Config, AsRef, Default, Clone)]
+#[derive(Debug, Config, AsRef, Default, Clone, schemars::JsonSchema)]
 struct MyConfig {
     #[as_ref]
     #[config(nested)]
@@ -99,6 +99,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = AppData;
+    type ControlHandle = ();
 
     fn label() -> Label {
         notifications::label()
@@ -138,6 +139,8 @@ impl Handler for ServiceHandler {
         Ok(Self { context })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
         notify_ready();
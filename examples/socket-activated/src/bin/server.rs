@@ -1,10 +1,11 @@
 use std::future::IntoFuture;
+use std::io;
+use std::net::SocketAddr;
 use std::time::Duration;
 
-use axum::extract::{Path, State};
-use axum::middleware::Next;
+use axum::Router;
+use axum::extract::Path;
 use axum::routing::get;
-use axum::{Router, middleware};
 use confique::Config;
 use daemon_slayer::build_info::cli::BuildInfoCliProvider;
 use daemon_slayer::build_info::vergen_pretty::{self, Style};
@@ -24,20 +25,22 @@ use daemon_slayer::logging::tracing_subscriber::util::SubscriberInitExt;
 use daemon_slayer::logging::{self, EnvConfig, LoggerBuilder, ReloadHandle};
 use daemon_slayer::server::cli::ServerCliProvider;
 use daemon_slayer::server::futures::StreamExt;
-use daemon_slayer::server::socket_activation::{SocketResult, get_activation_sockets};
+use daemon_slayer::server::socket_activation::{
+    TrackedConnection, TrackedSocket, TrackedTcpListener, get_activation_sockets,
+};
 use daemon_slayer::server::{
     BroadcastEventStore, EventStore, Handler, ServiceContext, Signal, SignalHandler,
 };
 use daemon_slayer::signals::SignalListener;
 use derive_more::AsRef;
 use socket_activated::SOCKET_NAME;
-use tokio::sync::mpsc::Sender;
+use tokio::net::TcpStream;
 use tokio_util::future::FutureExt;
 use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
-#[derive(Debug, Config, AsRef, Default, Clone)]
+#[derive(Debug, Config, AsRef, Default, Clone, schemars::JsonSchema)]
 struct MyConfig {
     #[as_ref]
     #[config(nested)]
@@ -113,6 +116,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = AppData;
+    type ControlHandle = ();
 
     fn label() -> Label {
         socket_activated::label()
@@ -141,6 +145,8 @@ impl Handler for ServiceHandler {
         })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
         notify_ready();
@@ -148,31 +154,26 @@ impl Handler for ServiceHandler {
         let mut socket_result = get_activation_sockets(socket_activated::sockets()).await?;
         let is_activated = socket_result.is_activated;
         info!("is_activated: {is_activated}");
+        // Keep the listener open across restarts if the unit sets FileDescriptorStoreMax=
+        if let Err(e) = socket_result.fd_store.store() {
+            warn!("Error storing sockets: {e:?}");
+        }
         let socket = socket_result
             .sockets
             .remove(SOCKET_NAME)
             .ok_or("missing socket")?
             .remove(0);
 
-        let SocketResult::Tcp(listener) = socket else {
+        let idle_timeout = socket_result.idle_timeout(Duration::from_secs(10));
+        let TrackedSocket::Tcp(listener) = socket.tracked(&idle_timeout.get_tracker()) else {
             return Err("invalid socket config")?;
         };
+        self.context.spawn(idle_timeout);
 
-        let (refresh_tx, mut refresh_rx) = tokio::sync::mpsc::channel(32);
-
-        let mut app = Router::new()
+        let app = Router::new()
             .route("/hello/:name", get(greeter))
             .route("/health", get(health))
             .layer(TraceLayer::new_for_http());
-        if is_activated {
-            app = app.layer(middleware::from_fn_with_state(
-                refresh_tx,
-                |State(tx): State<Sender<()>>, request, next: Next| async move {
-                    tx.try_send(()).unwrap();
-                    next.run(request).await
-                },
-            ));
-        }
 
         let mut signals = self.signal_store.subscribe_events();
         let cancellation = self.context.cancellation_token().clone();
@@ -180,6 +181,7 @@ impl Handler for ServiceHandler {
 
         tokio::spawn({
             let timeout_cancellation = timeout_cancellation.clone();
+            let cancellation = cancellation.clone();
             async move {
                 cancellation.cancelled().await;
                 tokio::time::sleep(Duration::from_secs(2)).await;
@@ -187,29 +189,16 @@ impl Handler for ServiceHandler {
             }
         });
 
-        if let Some(res) = axum::serve(listener, app)
+        if let Some(res) = axum::serve(TrackedListener(listener), app)
             .with_graceful_shutdown(async move {
-                if is_activated {
-                    loop {
-                        let timeout = tokio::time::sleep(Duration::from_secs(10));
-                        tokio::select! {
-                            _ = signals.next() => {
-                                info!("Got shutdown signal");
-                            },
-                            _ = timeout => {
-                                info!("Terminating due to timeout");
-                            }
-                            res = refresh_rx.recv() => {
-                                if res.is_some() {
-                                    continue;
-                                }
-                            }
-                        }
-                        return;
+                tokio::select! {
+                    _ = signals.next() => {
+                        info!("Got shutdown signal");
+                    }
+                    _ = cancellation.cancelled() => {
+                        info!("Service cancelled");
                     }
                 }
-                signals.next().await;
-                info!("Got shutdown signal");
             })
             .into_future()
             .with_cancellation_token(&timeout_cancellation)
@@ -224,6 +213,30 @@ impl Handler for ServiceHandler {
     }
 }
 
+// Open connections are counted towards the idle timeout
+struct TrackedListener(TrackedTcpListener);
+
+impl axum::serve::Listener for TrackedListener {
+    type Io = TrackedConnection<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            match self.0.accept().await {
+                Ok(connection) => return connection,
+                Err(e) => {
+                    warn!("Error accepting connection: {e:?}");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.0.local_addr()
+    }
+}
+
 async fn greeter(Path(name): Path<String>) -> String {
     format!("Hello {name}")
 }
@@ -22,7 +22,7 @@ use daemon_slayer::logging::{self, LoggerBuilder};
 use daemon_slayer::process::cli::ProcessCliProvider;
 use derive_more::AsRef;
 
-#[derive(Debug, confique::Config, AsRef, Default, Clone)]
+#[derive(Debug, confique::Config, AsRef, Default, Clone, schemars::JsonSchema)]
 struct MyConfig {
     #[as_ref]
     #[config(nested)]
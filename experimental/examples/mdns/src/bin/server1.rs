@@ -42,7 +42,7 @@ use tokio::sync::mpsc::Sender;
 use tower_http::trace::TraceLayer;
 use tracing::info;
 
-#[derive(Debug, Config, AsRef, Default, Clone)]
+#[derive(Debug, Config, AsRef, Default, Clone, schemars::JsonSchema)]
 struct MyConfig {
     #[as_ref]
     #[config(nested)]
@@ -117,6 +117,7 @@ pub struct ServiceHandler {
 impl Handler for ServiceHandler {
     type Error = BoxedError;
     type InputData = AppData;
+    type ControlHandle = ();
 
     fn label() -> Label {
         mdns::label()
@@ -187,6 +188,8 @@ impl Handler for ServiceHandler {
         })
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
         notify_ready();
@@ -48,6 +48,7 @@ pub struct ServiceHandler {
 
 impl Handler for ServiceHandler {
     type InputData = ();
+    type ControlHandle = ();
     type Error = BoxedError;
 
     async fn new(
@@ -65,6 +66,8 @@ impl Handler for ServiceHandler {
         integration_tests::label()
     }
 
+    fn control_handle(&self) {}
+
     async fn run_service<F: FnOnce() + Send>(self, notify_ready: F) -> Result<(), Self::Error> {
         info!("running service");
 
@@ -4,7 +4,7 @@ use confique::Config;
 use daemon_slayer::client;
 use daemon_slayer::core::{CommandArg, Label};
 
-#[derive(Debug, Config, Default, Clone)]
+#[derive(Debug, Config, Default, Clone, schemars::JsonSchema)]
 pub struct TestConfig {
     #[config(nested)]
     client_config: client::config::UserConfig,
@@ -5,7 +5,7 @@ use daemon_slayer::client::{self, State};
 use daemon_slayer::config::server::ConfigService;
 use daemon_slayer::config::{AppConfig, ConfigDir};
 use daemon_slayer::core::CancellationToken;
-use daemon_slayer::core::server::background_service::{self, Manager};
+use daemon_slayer::core::server::tracked::{self, Manager};
 use daemon_slayer::server::EventStore;
 use futures::{Future, StreamExt};
 use integration_tests::TestConfig;
@@ -82,10 +82,7 @@ async fn run_tests(is_user_service: bool) {
     app_config.overwrite_config_file().unwrap();
 
     // Don't start file watcher until after we reset the config
-    let background_services = Manager::new(
-        CancellationToken::new(),
-        background_service::Settings::default(),
-    );
+    let background_services = Manager::new(CancellationToken::new(), tracked::Settings::default());
     background_services.get_context().spawn(config_service);
 
     manager.install().await.unwrap();